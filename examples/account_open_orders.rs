@@ -36,7 +36,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(balance_managers),
         None,
         None,
-    );
+    )?;
     let db_client = DeepBookClient::new(Arc::new(sui.clone()), Arc::new(db_config));
 
     // Step 5: Define the pool and manager which you want to fetch open orders for
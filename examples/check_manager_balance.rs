@@ -34,7 +34,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(balance_managers),
         None,
         None,
-    );
+    )?;
     let db_client = DeepBookClient::new(Arc::new(sui.clone()), Arc::new(db_config));
 
     // Step 5: Call check_manager_balance with deepbook-sdk and check the response
@@ -23,7 +23,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None,
         None,
         None,
-    );
+    )?;
     let db_client = DeepBookClient::new(Arc::new(sui.clone()), Arc::new(db_config));
 
     // Step 4: Add create_and_share_balance_manager transaction to PTB with deepbook-sdk
@@ -38,7 +38,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(balance_managers),
         None,
         None,
-    );
+    )?;
     let db_client = DeepBookClient::new(
         Arc::new(sui.clone()),
         Arc::new(db_config),
@@ -0,0 +1,53 @@
+/// Example: Record a tracing span, with its latency, for each RPC of a read query
+/// Run with `cargo run --example tracing_spans --features tracing`
+
+mod utils;
+
+use deepbook::client::DeepBookClient;
+use deepbook::utils::constants::{BalanceManager, BalanceManagerMap};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio;
+use tracing_subscriber::fmt::format::FmtSpan;
+use deepbook::DeepBookConfig;
+use crate::utils::{setup_for_read};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Step 1: Install a subscriber printing every span when it closes, with its busy/idle time
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
+    // Step 2: Initialize Sui client
+    let (sui, sender) = setup_for_read().await?;
+
+    // Step 3: Initialize balance managers
+    let mut balance_managers: BalanceManagerMap = HashMap::new();
+    balance_managers.insert(
+        "MANAGER_1".to_string(),
+        BalanceManager {
+            address: "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9".to_string(),
+            trade_cap: None,
+        },
+    );
+
+    // Step 4: Initialize DeepBookClient with DeepBookConfig
+    let db_config = DeepBookConfig::new(
+        "testnet",
+        sender.to_string(),
+        None,
+        Some(balance_managers),
+        None,
+        None,
+    )?;
+    let db_client = DeepBookClient::new(Arc::new(sui.clone()), Arc::new(db_config));
+
+    // Step 5: Run a read query. The output shows an `account_open_orders` span with
+    // pool_key/manager_key fields, wrapping the object fetch and dev-inspect spans.
+    let orders = db_client.account_open_orders("SUI_DBUSDC", "MANAGER_1").await?;
+    println!("[orders]\n {:?}", orders);
+
+    Ok(())
+}
@@ -3,24 +3,62 @@
 //
 // This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
 
+use std::collections::HashMap;
+use std::future::Future;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use futures::{Stream, StreamExt};
+use tokio::time::{Interval, MissedTickBehavior};
 use crate::transactions::{
-    balance_manager::BalanceManagerContract, deepbook::DeepBookContract,
+    balance_manager::{
+        append_bootstrap_manager, append_create_and_transfer_manager, append_deposits, BalanceManagerContract,
+    },
+    deepbook::{
+        effective_taker_price, notional_value, order_deep_required, order_id_is_bid, order_id_price,
+        quote_to_base_quantity, recommended_deep_deposit, DeepBookContract, OrderSpec, PlaceOrderOptions, PriceEncoder,
+        RoundingMode, SelfMatchingOptions,
+    },
     deepbook_admin::DeepBookAdminContract, flash_loan::FlashLoanContract,
     governance::GovernanceContract,
 };
-use crate::utils::config::{DeepBookConfig};
+use crate::indexer::IndexerClient;
+use crate::utils::config::{DeepBookConfig, DEEPBOOK_VERSION, DEFAULT_REQUEST_TIMEOUT, GAS_BUDGET, MAX_EVENT_PAGES};
+use crate::utils::constants::{format_coin_amount, format_coin_amount_trimmed, Coin};
+use crate::utils::error::{aborted_command, execution_error};
+use crate::utils::execution::{
+    find_created_balance_manager_id, parse_order_events, select_gas_coin, DeepBookSigner, ExecutionOutcome,
+};
+use crate::utils::pricing::{holdings_value, PriceFeed};
+use crate::utils::transactions::{
+    collect_pages_up_to, fetch_all_dynamic_fields, fetch_dynamic_field_by_type, fetch_events_page, fetch_events_since,
+    fetch_events_while, fetch_move_object_fields, fetch_object, fetch_owned_coins, fetch_owned_object_ids,
+    fetch_pool_inner_fields, decode_return_value, dev_inspect_ptb, dev_inspect_ptb_as, fetch_versioned_inner_fields,
+    merge_coin_arguments, object_type_string, parse_return_value_at, pool_coin_types_from_type,
+    prepare_balance_manager_argument, return_value_bytes, split_coin_argument, with_timeout, ObjectFetcher,
+};
+use crate::utils::types::{
+    allowed_versions_from_json, coin_types_from_pool_keys, deep_price_age_from_json, json_number, json_object_id,
+    move_field, order_counts_from_json, proposal_voter_count, Account, BookMetrics, CoinMetadata, DeepTreasuryInfo,
+    FeeRate, FeeTier, ManagerCaps, ManagerTrade, ManagerTradeHistory, Order, OrderBook, OrderDeepPrice, OrderEvent,
+    PoolBookParams, PoolParams, PoolStats, PoolTradeParams, Proposal, Trade,
+};
 use anyhow::{anyhow, Context, Result};
-use log::debug;
+use log::{debug, warn};
+use reqwest::header::HeaderMap;
 use serde_json::json;
-use sui_sdk::rpc_types::{DevInspectResults, SuiObjectDataOptions};
+use sui_sdk::rpc_types::{
+    DevInspectResults, SuiEvent, SuiObjectDataOptions, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
+};
 use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
-use sui_sdk::types::transaction::{ObjectArg, TransactionKind};
-use sui_sdk::SuiClient;
-use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_sdk::types::transaction::{Argument, CallArg, Command, ObjectArg, Transaction, TransactionData};
+use sui_sdk::{SuiClient, SuiClientBuilder, SUI_COIN_TYPE};
+use sui_sdk::types::base_types::{ObjectRef, SuiAddress};
 use sui_sdk::types::collection_types::VecSet;
-use sui_sdk::types::sui_serde::BigInt;
+use sui_sdk::types::event::EventID;
 use sui_sdk::types::TypeTag;
 use sui_types::base_types::{ObjectID};
 use sui_types::{Identifier};
@@ -46,6 +84,106 @@ pub struct DeepBookClient {
     pub flash_loans: FlashLoanContract,
     /// Contract for interacting with governance features.
     pub governance: GovernanceContract,
+    /// Source of unique client order ids.
+    client_order_ids: ClientOrderIds,
+    /// DeepBook indexer preferred over dev-inspects for market data reads, if set.
+    indexer: Option<IndexerClient>,
+    /// Bound on each dev-inspect and object fetch issued by the client.
+    request_timeout: Duration,
+    /// On-chain coin metadata fetched with `fetch_coin_metadata`, by coin key.
+    coin_metadata: RwLock<HashMap<String, CoinMetadata>>,
+}
+
+/// Strictly increasing client order ids, shared by all callers of a client.
+#[derive(Debug)]
+struct ClientOrderIds(AtomicU64);
+
+impl ClientOrderIds {
+    /// Starts the sequence at `seed`.
+    fn new(seed: u64) -> Self {
+        ClientOrderIds(AtomicU64::new(seed))
+    }
+
+    /// Starts the sequence at the current time in microseconds, so ids keep increasing
+    /// across restarts unless more than a million ids were issued per second.
+    fn from_clock() -> Self {
+        let micros = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros() as u64)
+            .unwrap_or_default();
+
+        Self::new(micros)
+    }
+
+    fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+/// Builder connecting a `DeepBookClient` to a full node, e.g. a commercial RPC endpoint
+/// that authenticates requests with an API key header.
+#[derive(Debug)]
+pub struct DeepBookClientBuilder {
+    rpc_url: String,
+    config: DeepBookConfig,
+    rpc_headers: HeaderMap,
+    request_timeout: Duration,
+}
+
+impl DeepBookClientBuilder {
+    /// Creates a new `DeepBookClientBuilder`.
+    ///
+    /// # Arguments
+    /// * `rpc_url` - The JSON-RPC URL of the full node.
+    /// * `config` - A configuration object containing environment details.
+    pub fn new(rpc_url: &str, config: DeepBookConfig) -> Self {
+        DeepBookClientBuilder {
+            rpc_url: rpc_url.to_string(),
+            config,
+            rpc_headers: HeaderMap::new(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Attaches `headers` to every request sent to the full node, e.g. `x-api-key` for a paid provider.
+    /// Headers set by earlier calls are kept unless overwritten.
+    ///
+    /// # Arguments
+    /// * `headers` - The HTTP headers to send.
+    ///
+    /// # Returns
+    /// The updated `DeepBookClientBuilder` instance.
+    pub fn with_rpc_headers(mut self, headers: HeaderMap) -> Self {
+        self.rpc_headers.extend(headers);
+        self
+    }
+
+    /// Bounds each full-node request, see `DeepBookClient::with_request_timeout`.
+    ///
+    /// # Arguments
+    /// * `request_timeout` - The maximum duration of a single request.
+    ///
+    /// # Returns
+    /// The updated `DeepBookClientBuilder` instance.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Connects to the full node and creates the client.
+    ///
+    /// # Returns
+    /// The connected `DeepBookClient`, or an error if a header is not valid text or the full node can't be reached.
+    pub async fn connect(self) -> Result<DeepBookClient> {
+        let client = SuiClientBuilder::default()
+            .request_timeout(self.request_timeout)
+            .custom_headers(rpc_header_strings(&self.rpc_headers)?)
+            .build(&self.rpc_url)
+            .await
+            .with_context(|| format!("Failed to connect to full node: {}", self.rpc_url))?;
+
+        Ok(DeepBookClient::new(Arc::new(client), Arc::new(self.config)).with_request_timeout(self.request_timeout))
+    }
 }
 
 impl DeepBookClient {
@@ -64,8 +202,11 @@ impl DeepBookClient {
         let deep_book = DeepBookContract::new(client.clone(), config.clone(), balance_manager.clone());
         let deep_book_admin = DeepBookAdminContract::new(client.clone(), config.clone());
         let flash_loans = FlashLoanContract::new(client.clone(), config.clone());
-        let governance = GovernanceContract::new(client.clone(), config.clone());
+        let governance = GovernanceContract::new(client.clone(), config.clone(), balance_manager.clone());
 
+        if let Err(err) = config.validate() {
+            warn!("Invalid DeepBook configuration: {}", err);
+        }
         debug!("DeepBook client initialized, config: {:?}", config);
 
         DeepBookClient {
@@ -76,9 +217,102 @@ impl DeepBookClient {
             deep_book_admin,
             flash_loans,
             governance,
+            client_order_ids: ClientOrderIds::from_clock(),
+            indexer: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            coin_metadata: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Creates a `DeepBookClientBuilder` connecting to the full node at `rpc_url`.
+    ///
+    /// # Arguments
+    /// * `rpc_url` - The JSON-RPC URL of the full node.
+    /// * `config` - A configuration object containing environment details.
+    pub fn builder(rpc_url: &str, config: DeepBookConfig) -> DeepBookClientBuilder {
+        DeepBookClientBuilder::new(rpc_url, config)
+    }
+
+    /// Sets the self-matching option used for orders placed without one, e.g. `CancelTaker`
+    /// to prevent an account quoting both sides from trading against itself.
+    ///
+    /// # Arguments
+    /// * `self_matching_option` - The default self-matching option.
+    ///
+    /// # Returns
+    /// The updated `DeepBookClient` instance.
+    pub fn with_default_self_matching(mut self, self_matching_option: SelfMatchingOptions) -> Self {
+        self.deep_book.set_default_self_matching(self_matching_option);
+        self
+    }
+
+    /// Encodes the prices and quantities of the orders of a pool with `encoder` instead of
+    /// `DefaultPriceEncoder`, e.g. `StablePriceEncoder` for a pool of two stablecoins.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `encoder` - The price encoder of the pool.
+    ///
+    /// # Returns
+    /// The updated `DeepBookClient` instance.
+    pub fn with_price_encoder(mut self, pool_key: &str, encoder: impl PriceEncoder + 'static) -> Self {
+        self.deep_book.set_price_encoder(pool_key, Arc::new(encoder));
+        self
+    }
+
+    /// Reads market data (e.g. `order_book`) from a DeepBook indexer instead of the full node.
+    /// The indexer is faster for large reads but lags the chain by a few seconds.
+    ///
+    /// # Arguments
+    /// * `indexer` - The indexer client, e.g. `IndexerClient::for_env("mainnet")`.
+    ///
+    /// # Returns
+    /// The updated `DeepBookClient` instance.
+    pub fn with_indexer(mut self, indexer: IndexerClient) -> Self {
+        self.indexer = Some(indexer);
+        self
+    }
+
+    /// The indexer used for market data reads, if set with `with_indexer`.
+    pub fn indexer(&self) -> Option<&IndexerClient> {
+        self.indexer.as_ref()
+    }
+
+    /// Bounds each dev-inspect and object fetch issued by the client, so a hanging full node
+    /// can't stall the caller. An expired request fails with `DeepBookError::Timeout`.
+    /// Defaults to `DEFAULT_REQUEST_TIMEOUT`. The object lookups preparing the arguments of a
+    /// contract call are bounded together, as one request. Paged queries (events, coins) are
+    /// not bounded.
+    ///
+    /// # Arguments
+    /// * `request_timeout` - The maximum duration of a single request.
+    ///
+    /// # Returns
+    /// The updated `DeepBookClient` instance.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Restarts the client order id sequence at `seed`, e.g. for deterministic ids in tests.
+    ///
+    /// # Arguments
+    /// * `seed` - The next client order id to return.
+    ///
+    /// # Returns
+    /// The updated `DeepBookClient` instance.
+    pub fn with_client_order_id_seed(mut self, seed: u64) -> Self {
+        self.client_order_ids = ClientOrderIds::new(seed);
+        self
+    }
+
+    /// Returns a client order id that is unique for this client and greater than every id
+    /// it returned before. The sequence is seeded from the current time unless set with
+    /// `with_client_order_id_seed`.
+    pub fn next_client_order_id(&self) -> u64 {
+        self.client_order_ids.next()
+    }
+
     /// Get open orders for a balance manager in a pool.
     ///
     /// # Arguments
@@ -91,22 +325,74 @@ impl DeepBookClient {
         &self,
         pool_key: &str,
         manager_key: &str,
+    ) -> Result<Vec<u128>> {
+        self.account_open_orders_as(pool_key, manager_key, None).await
+    }
+
+    /// Like `account_open_orders`, with the dev-inspect sender chosen per call.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `sender` - The address to inspect as, e.g. the owner of the manager; the configured address if None.
+    ///
+    /// # Returns
+    /// A vector of open order IDs.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn account_open_orders_as(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        sender: Option<SuiAddress>,
     ) -> Result<Vec<u128>> {
         let mut ptb = ProgrammableTransactionBuilder::new();
 
-        self.deep_book
-            .account_open_orders(&mut ptb, pool_key, manager_key)
+        with_timeout(self.request_timeout, self.deep_book.account_open_orders(&mut ptb, pool_key, manager_key))
             .await.with_context(|| "Failed to add account_open_orders command to PTB")?;
 
         let response = self
-            .dev_inspect_transaction_results(ptb)
+            .dev_inspect_with_sender(ptb, sender)
             .await
             .with_context(|| "Failed to inspect transaction results")?;
 
-        let order_ids: VecSet<u128> = parse_data_from_response(&response)
+        let order_ids: Option<VecSet<u128>> = parse_optional_data_from_response(&response)
             .with_context(|| "Failed to parse order IDs from dev-inspect-transaction response")?;
 
-        Ok(order_ids.contents)
+        Ok(order_ids.map(|order_ids| order_ids.contents).unwrap_or_default())
+    }
+
+    /// Get open orders for a balance manager in every configured pool, e.g. for a portfolio scan,
+    /// with one dev-inspect of a PTB reading all pools instead of one RPC per pool.
+    /// A Move abort fails the whole dev-inspect, so a pool aborting (e.g. one the manager never
+    /// traded in) is mapped to no open orders and the other pools are inspected again.
+    ///
+    /// # Arguments
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// The open order IDs by pool key.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn all_open_orders(&self, manager_key: &str) -> Result<HashMap<String, Vec<u128>>> {
+        let mut pool_keys: Vec<&str> = self.config.pools.keys().map(String::as_str).collect();
+        pool_keys.sort_unstable();
+
+        batched_open_orders(&pool_keys, |pool_keys| async move {
+            let mut ptb = ProgrammableTransactionBuilder::new();
+            with_timeout(self.request_timeout, self.deep_book.accounts_open_orders(&mut ptb, &pool_keys, manager_key))
+                .await.with_context(|| "Failed to add account_open_orders commands to PTB")?;
+
+            let response = self
+                .dev_inspect_transaction_results(ptb)
+                .await
+                .with_context(|| "Failed to inspect transaction results")?;
+
+            Ok(match response.error.clone() {
+                Some(error) => Err(error),
+                None => Ok((0..pool_keys.len())
+                    .map(|command| return_value_bytes(&response, command, 0).map(<[u8]>::to_vec))
+                    .collect()),
+            })
+        }).await
     }
 
     /// Checks the balance of a specific coin for a balance manager.
@@ -116,144 +402,2996 @@ impl DeepBookClient {
     /// * `coin_key` - The key identifying the coin.
     ///
     /// # Returns
-    /// A tuple containing the coin type as a string and its balance as a floating-point number.
+    /// A JSON object with the coin type, the balance as a floating-point number and
+    /// the balance as a fixed-decimal string (`balance_formatted`).
     pub async fn check_manager_balance(
         &self,
         manager_key: &str,
         coin_key: &str,
+    ) -> Result<serde_json::Value> {
+        self.check_manager_balance_as(manager_key, coin_key, None).await
+    }
+
+    /// Like `check_manager_balance`, with the dev-inspect sender chosen per call.
+    ///
+    /// # Arguments
+    /// * `manager_key` - The key identifying the balance manager.
+    /// * `coin_key` - The key identifying the coin.
+    /// * `sender` - The address to inspect as, e.g. the owner of the manager; the configured address if None.
+    ///
+    /// # Returns
+    /// A JSON object with the coin type, the balance as a floating-point number and
+    /// the balance as a fixed-decimal string (`balance_formatted`).
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn check_manager_balance_as(
+        &self,
+        manager_key: &str,
+        coin_key: &str,
+        sender: Option<SuiAddress>,
     ) -> Result<serde_json::Value> {
         let mut ptb = ProgrammableTransactionBuilder::new();
 
-        let coin = self
-            .config
-            .get_coin(coin_key)
-            .with_context(|| format!("Coin not found for key: {}", coin_key))?;
+        let coin = self.config.require_coin(coin_key)?;
 
-        self.balance_manager
-            .check_manager_balance(&mut ptb, manager_key, coin_key)
+        with_timeout(self.request_timeout, self.balance_manager.check_manager_balance(&mut ptb, manager_key, coin_key))
             .await.with_context(|| "Failed to add check_manager_balance command to PTB")?;
 
         let response = self
-            .dev_inspect_transaction_results(ptb)
+            .dev_inspect_with_sender(ptb, sender)
             .await
             .with_context(|| "Failed to inspect transaction results")?;
 
         let parsed_balance: u64 = parse_data_from_response(&response)
             .with_context(|| "Failed to parse balance from dev-inspect-transaction response")?;
-        let adjusted_balance = parsed_balance as f64 / coin.scalar as f64;
 
-        Ok(json!({
-            "coin_type": coin.type_,
-            "balance": format!("{:.9}", adjusted_balance).parse::<f64>()?,
-        }))
+        Ok(manager_balance_json(parsed_balance, coin))
     }
 
-    /// Mint and transfer trade cap to a receiver.
-    /// With trade cap, the receiver can place orders via specified BalanceManager.
+    /// Checks the balance of a coin that is not in the configuration for a balance manager,
+    /// e.g. a newly listed token.
+    ///
     /// # Arguments
-    /// * `ptb` - ProgrammableTransactionBuilder instance.
     /// * `manager_key` - The key identifying the balance manager.
-    /// * `receiver` - The address of the receiver.
+    /// * `coin_type` - The coin type, e.g. `0x2::sui::SUI`.
+    /// * `scalar` - The scalar of the coin, `10^decimals`.
+    ///
     /// # Returns
-    /// None on success, or an error.
-    pub async fn mint_and_transfer_trade_cap(
+    /// A JSON object like `check_manager_balance`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn check_manager_balance_by_type(
         &self,
-        ptb: &mut ProgrammableTransactionBuilder,
         manager_key: &str,
-        receiver: SuiAddress,
-    ) -> Result<(), anyhow::Error> {
-        let manager = self
-            .config
-            .get_balance_manager(manager_key)
-            .ok_or_else(|| anyhow!("Manager not found for key {}", manager_key))?;
+        coin_type: &str,
+        scalar: u64,
+    ) -> Result<serde_json::Value> {
+        let coin = Coin::from_type(coin_type, scalar)?;
 
-        let manager_obj = self.client.read_api().get_object_with_options(
-            ObjectID::from_hex_literal(&manager.address)?,
-            SuiObjectDataOptions::new()
-                .with_content()
-                .with_type()
-                .with_owner(),
-        ).await?;
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(
+            self.request_timeout,
+            self.balance_manager.check_manager_balance_by_type(&mut ptb, manager_key, coin_type),
+        )
+            .await.with_context(|| "Failed to add check_manager_balance command to PTB")?;
 
-        match manager_obj.owner() {
-            Some(owner) => {
-                match owner {
-                    Owner::Shared { initial_shared_version, .. } => {
-                        let initial_shared_version = initial_shared_version.clone();
-                        let manager_argument = ptb.obj(ObjectArg::SharedObject {
-                            id: ObjectID::from_hex_literal(&manager.address)?,
-                            initial_shared_version,
-                            mutable: true,
-                        })?;
-                        let trade_cap = ptb.programmable_move_call(
-                            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
-                            Identifier::new("balance_manager")?,
-                            Identifier::new("mint_trade_cap")?,
-                            vec![],
-                            vec![manager_argument],
-                        );
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
 
-                        let trade_cap_type = TypeTag::from_str(format!("{}::balance_manager::TradeCap", self.config.deepbook_package_id).as_str())?;
-                        let receiver_arg = ptb.pure(receiver)?;
-                        ptb.programmable_move_call(
-                            ObjectID::from_hex_literal("0x2")?,
-                            Identifier::new("transfer")?,
-                            Identifier::new("public_transfer")?,
-                            vec![trade_cap_type],
-                            vec![trade_cap, receiver_arg],
-                        );
-                    }
-                    _ => {
-                        return Err(anyhow!("BalanceManager must be a shared object"));
-                    }
+        let parsed_balance: u64 = parse_data_from_response(&response)
+            .with_context(|| "Failed to parse balance from dev-inspect-transaction response")?;
+
+        Ok(manager_balance_json(parsed_balance, &coin))
+    }
+
+    /// Get the capabilities authorized on a balance manager, e.g. to audit delegated trading.
+    /// The allow-listed caps are fetched with one extra RPC to sort them by kind.
+    ///
+    /// # Arguments
+    /// * `manager_key` - The key identifying the balance manager.
+    ///
+    /// # Returns
+    /// The owner and the trade, deposit and withdraw caps of the manager.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn manager_capabilities(&self, manager_key: &str) -> Result<ManagerCaps> {
+        let manager = self.config.require_balance_manager(manager_key)?;
+        let manager_fields = with_timeout(self.request_timeout, fetch_move_object_fields(&self.client, &manager.address))
+            .await
+            .with_context(|| "Failed to fetch balance manager")?;
+        let mut caps = ManagerCaps::from_manager_json(&manager_fields)
+            .with_context(|| format!("Failed to decode balance manager: {}", manager_key))?;
+        if caps.allow_listed.is_empty() {
+            return Ok(caps);
+        }
+
+        let cap_objects = with_timeout(self.request_timeout, self.client.multi_get_objects(&caps.allow_listed))
+            .await
+            .with_context(|| "Failed to fetch allow-listed caps")?;
+        let cap_types: Vec<(ObjectID, String)> = caps
+            .allow_listed
+            .iter()
+            .zip(&cap_objects)
+            .filter_map(|(cap_id, cap_object)| {
+                let cap_type = cap_object.data.as_ref()?.type_.as_ref()?;
+                Some((*cap_id, cap_type.to_string()))
+            })
+            .collect();
+        caps.classify(&cap_types);
+
+        Ok(caps)
+    }
+
+    /// Check the balance of a coin in several balance managers with a single dev-inspect.
+    /// Managers whose object can't be resolved on-chain are left out of the result.
+    ///
+    /// # Arguments
+    /// * `manager_keys` - The keys identifying the balance managers.
+    /// * `coin_key` - The key identifying the coin.
+    ///
+    /// # Returns
+    /// The balance in human units by manager key.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn managers_balance(
+        &self,
+        manager_keys: &[&str],
+        coin_key: &str,
+    ) -> Result<HashMap<String, f64>> {
+        let coin = self.config.require_coin(coin_key)?;
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let included = with_timeout(
+            self.request_timeout,
+            self.balance_manager.check_managers_balance(&mut ptb, manager_keys, coin_key),
+        )
+            .await.with_context(|| "Failed to add check_manager_balance commands to PTB")?;
+        if included.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let balances = (0..included.len())
+            .map(|command| return_value_bytes(&response, command, 0))
+            .collect();
+
+        manager_balances(included, balances, coin)
+    }
+
+    /// Watch the balance of a coin in a balance manager by polling `check_manager_balance`.
+    /// The first value is queried immediately, then one per `interval`; polling stops when the
+    /// stream is dropped. A failed query yields an error item and polling continues.
+    ///
+    /// # Arguments
+    /// * `manager_key` - The key identifying the balance manager.
+    /// * `coin_key` - The key identifying the coin.
+    /// * `interval` - The time between two queries; a zero interval yields a single error.
+    ///
+    /// # Returns
+    /// A stream of balances in human units.
+    pub fn watch_manager_balance<'a>(
+        &'a self,
+        manager_key: &'a str,
+        coin_key: &'a str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<f64>> + 'a {
+        poll_every(interval, move || async move {
+            let balance = self.check_manager_balance(manager_key, coin_key).await?;
+
+            balance["balance"]
+                .as_f64()
+                .ok_or_else(|| anyhow!("Manager balance is not a number: {}", balance))
+        })
+    }
+
+    /// Load the book parameters of every configured pool with a single dev-inspect and cache them,
+    /// so order placement can validate tick size, lot size and min size without any RPC.
+    /// A pool whose object is missing or whose parameters can't be read is skipped with a warning.
+    ///
+    /// # Returns
+    /// The book parameters by pool key, for the pools that loaded.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn load_all_pool_params(&self) -> Result<HashMap<String, PoolBookParams>> {
+        let mut pool_keys: Vec<&str> = self.config.pools.keys().map(String::as_str).collect();
+        pool_keys.sort_unstable();
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let included = with_timeout(
+            self.request_timeout,
+            self.deep_book.available_pool_book_params(&mut ptb, &pool_keys),
+        )
+            .await.with_context(|| "Failed to add pool_book_params commands to PTB")?;
+        for pool_key in pool_keys.iter().filter(|pool_key| !included.iter().any(|key| key == *pool_key)) {
+            warn!("Skipping book params of pool {}: its object could not be resolved", pool_key);
+        }
+        if included.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+
+        let mut pool_params = HashMap::new();
+        for (command, pool_key) in included.into_iter().enumerate() {
+            let value = |index| parse_return_value_at::<u64>(&response, command, index);
+            match (value(0), value(1), value(2)) {
+                (Ok(tick_size), Ok(lot_size), Ok(min_size)) => {
+                    pool_params.insert(pool_key, PoolBookParams { tick_size, lot_size, min_size });
+                }
+                (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
+                    warn!("Skipping book params of pool {}: {:#}", pool_key, err);
                 }
-            },
-            None => {
-                return Err(anyhow!("BalanceManager has no owner"));
             }
         }
+        self.deep_book.cache_pool_params(pool_params.clone());
 
-        Ok(())
+        Ok(pool_params)
     }
 
-    /// Return the inspection of the transaction block, or an error upon failure.
-    /// Use this method to inspect the results of a transaction before executing it.
-    /// It does not execute the transaction.
-    async fn dev_inspect_transaction_results(
-        &self,
-        ptb: ProgrammableTransactionBuilder,
-    ) -> Result<DevInspectResults> {
-        let tx_data = TransactionKind::ProgrammableTransaction(ptb.finish().to_owned());
-        let gas_budget = BigInt::from(10_000);
+    /// Get the book parameters of a pool, from the cache if loaded, otherwise with a dev-inspect.
+    /// Fetched parameters are cached.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The tick size, lot size and min size of the pool.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn pool_book_params(&self, pool_key: &str) -> Result<PoolBookParams> {
+        if let Some(pool_params) = self.deep_book.cached_pool_params(pool_key) {
+            return Ok(pool_params);
+        }
 
-        self.client
-            .read_api()
-            .dev_inspect_transaction_block(
-                SuiAddress::from_str(&self.config.address).with_context(|| "Invalid sender address in configuration")?,
-                tx_data,
-                Some(gas_budget),
-                None,
-                None,
-            )
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(self.request_timeout, self.deep_book.pool_book_params(&mut ptb, &[pool_key]))
+            .await.with_context(|| "Failed to add pool_book_params command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
             .await
-            .with_context(|| "Failed to dev inspect transaction block")
+            .with_context(|| "Failed to inspect transaction results")?;
+        let value = |index| parse_return_value_at::<u64>(&response, 0, index)
+            .with_context(|| format!("Failed to parse book params of pool: {}", pool_key));
+        let pool_params = PoolBookParams {
+            tick_size: value(0)?,
+            lot_size: value(1)?,
+            min_size: value(2)?,
+        };
+        self.deep_book.cache_pool_params(HashMap::from([(pool_key.to_string(), pool_params)]));
+
+        Ok(pool_params)
     }
-}
 
-/// Parses data from the dev inspect results(Sui RPC response) and returns the deserialized data.
-/// Data type must implement serde::de::DeserializeOwned.
-fn parse_data_from_response<T: serde::de::DeserializeOwned>(response: &DevInspectResults) -> Result<T> {
-    let bytes = response
-        .results
-        .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Transaction response missing results"))?
-        .get(0)
-        .ok_or_else(|| anyhow::anyhow!("Transaction response missing first result"))?
-        .return_values
-        .get(0)
-        .ok_or_else(|| anyhow::anyhow!("Transaction response missing return value"))?
-        .0
-        .clone();
-
-    bcs::from_bytes::<T>(&bytes).context("Failed to decode data from BCS bytes")
+    /// Get the smallest permissible order value of a pool at a price, in quote units,
+    /// i.e. the pool's min size (in base) valued at `price`.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `price` - The price in quote per base.
+    ///
+    /// # Returns
+    /// The minimum order notional in quote units.
+    pub async fn min_order_notional(&self, pool_key: &str, price: f64) -> Result<f64> {
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+        let pool_params = self
+            .pool_book_params(pool_key)
+            .await
+            .with_context(|| "Failed to get pool book params")?;
+
+        let encoder = self.deep_book.price_encoder(pool_key);
+
+        Ok(min_notional(&pool_params, price, base_coin, quote_coin, encoder))
+    }
+
+    /// Place a limit order sized by the quote amount to spend (bid) or receive (ask),
+    /// e.g. "spend 100 USDC at 2.0". The base quantity is rounded down to the pool's lot size.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quote_amount` - The amount of quote asset the order is worth.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `options` - Optional order parameters.
+    ///
+    /// # Returns
+    /// The base quantity of the placed order.
+    pub async fn place_limit_order_by_quote(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quote_amount: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<f64> {
+        let (base_coin, _) = self.pool_coins(pool_key)?;
+        let pool_params = self
+            .pool_book_params(pool_key)
+            .await
+            .with_context(|| "Failed to get pool book params")?;
+        let quantity = quote_to_base_quantity(quote_amount, price, base_coin, pool_params.lot_size);
+
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.place_limit_order_with_options(
+                ptb,
+                pool_key,
+                manager_key,
+                client_order_id,
+                price,
+                quantity,
+                is_bid,
+                options,
+            ),
+        )
+            .await.with_context(|| "Failed to add place_limit_order command to PTB")?;
+
+        Ok(quantity)
+    }
+
+    /// Place a reduce-only limit order: one that can only shrink the manager's net resting
+    /// exposure in the pool (bid minus ask quantity of its open orders), never grow or flip it.
+    /// The quantity is capped at the net exposure on the opposite side, and the order is refused
+    /// if there is none. DeepBook is a spot market with no reduce-only flag: this is enforced by
+    /// the SDK when building the PTB, against open orders read beforehand, not by the protocol.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Maximum quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `options` - Optional order parameters.
+    ///
+    /// # Returns
+    /// The quantity of the placed order, possibly lower than `quantity`.
+    pub async fn place_limit_order_reduce_only(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<f64> {
+        let (base_coin, _) = self.pool_coins(pool_key)?;
+        let orders = self
+            .get_account_order_details(pool_key, manager_key)
+            .await
+            .with_context(|| "Failed to fetch open orders")?;
+        let input_quantity = self
+            .deep_book
+            .price_encoder(pool_key)
+            .encode_quantity(quantity, base_coin, options.rounding_mode);
+        let reduce_only_quantity = base_coin.to_human(reduce_only_quantity(&orders, input_quantity, is_bid)?);
+
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.place_limit_order_with_options(
+                ptb,
+                pool_key,
+                manager_key,
+                client_order_id,
+                price,
+                reduce_only_quantity,
+                is_bid,
+                options,
+            ),
+        )
+            .await.with_context(|| "Failed to add place_limit_order command to PTB")?;
+
+        Ok(reduce_only_quantity)
+    }
+
+    /// Place a limit order that expires at a wall-clock deadline, e.g. `SystemTime::now() +
+    /// Duration::from_secs(3600)` for one hour, instead of a raw millisecond `expiration`.
+    /// The deadline overrides `options.expiration` and must be in the future.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `deadline` - When the order expires.
+    /// * `options` - Optional order parameters.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the `pool::place_limit_order` call.
+    pub async fn place_limit_order_with_deadline(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        deadline: SystemTime,
+        options: PlaceOrderOptions,
+    ) -> Result<Argument> {
+        let expiration = deadline_expiration(deadline, SystemTime::now())?;
+
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.place_limit_order_with_options(
+                ptb,
+                pool_key,
+                manager_key,
+                client_order_id,
+                price,
+                quantity,
+                is_bid,
+                PlaceOrderOptions { expiration: Some(expiration), ..options },
+            ),
+        )
+            .await.with_context(|| "Failed to add place_limit_order command to PTB")
+    }
+
+    /// Resolve the arguments of a limit order as `CallArg`s, for PTBs built with another library.
+    /// See `DeepBookContract::place_limit_order_args` for the argument layout.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `options` - Optional order parameters.
+    ///
+    /// # Returns
+    /// The `pool::place_limit_order` arguments, without the trade proof.
+    pub async fn place_limit_order_args(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<Vec<CallArg>> {
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.place_limit_order_args(
+                pool_key,
+                manager_key,
+                client_order_id,
+                price,
+                quantity,
+                is_bid,
+                options,
+            ),
+        )
+            .await.with_context(|| "Failed to resolve place_limit_order arguments")
+    }
+
+    /// Requote a balance manager in a pool: cancel all its open orders, withdraw its settled
+    /// amounts and place a fresh ladder of limit orders, atomically in one PTB with one trade proof.
+    /// Each order gets a fresh client order id from `next_client_order_id`.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `new_ladder` - The new orders, as `OrderSpec`s or `(price, quantity, is_bid)` tuples.
+    ///   An `OrderSpec` can set its own order type and whether it pays fees in DEEP.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of each placement, in ladder order.
+    pub async fn requote(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        new_ladder: Vec<impl Into<OrderSpec>>,
+    ) -> Result<Vec<Argument>> {
+        let new_ladder: Vec<OrderSpec> = new_ladder.into_iter().map(Into::into).collect();
+        let client_order_ids: Vec<u64> = new_ladder.iter().map(|_| self.next_client_order_id()).collect();
+
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.requote(
+                ptb,
+                pool_key,
+                manager_key,
+                &new_ladder,
+                &client_order_ids,
+                PlaceOrderOptions::default(),
+            ),
+        )
+            .await.with_context(|| format!("Failed to add requote commands to PTB for pool: {}", pool_key))
+    }
+
+    /// Get the details of all open orders of a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// A vector of the decoded open orders.
+    pub async fn get_account_order_details(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<Vec<Order>> {
+        self.get_account_order_details_as(pool_key, manager_key, None).await
+    }
+
+    /// Like `get_account_order_details`, with the dev-inspect sender chosen per call.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `sender` - The address to inspect as, e.g. the owner of the manager; the configured address if None.
+    ///
+    /// # Returns
+    /// A vector of the decoded open orders.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_account_order_details_as(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        sender: Option<SuiAddress>,
+    ) -> Result<Vec<Order>> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        with_timeout(self.request_timeout, self.deep_book.get_account_order_details(&mut ptb, pool_key, manager_key))
+            .await.with_context(|| "Failed to add get_account_order_details command to PTB")?;
+
+        let response = self
+            .dev_inspect_with_sender(ptb, sender)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+
+        let orders: Option<Vec<Order>> = parse_optional_data_from_response(&response)
+            .with_context(|| "Failed to parse orders from dev-inspect-transaction response")?;
+
+        Ok(orders.unwrap_or_default())
+    }
+
+    /// Simulate placing a limit order without signing or spending gas: the order is placed and
+    /// the manager's open orders read back in a single dev-inspect, so the result reflects any
+    /// matching against the book. Note that nothing is committed, so the book may have moved by
+    /// the time a real order is placed.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `options` - Optional order parameters.
+    ///
+    /// # Returns
+    /// The open orders of the manager after the simulated placement, or an error if the
+    /// placement would fail, e.g. for lack of balance.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn simulate_place_order(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<Vec<Order>> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.place_limit_order_with_options(
+                &mut ptb,
+                pool_key,
+                manager_key,
+                client_order_id,
+                price,
+                quantity,
+                is_bid,
+                options,
+            ),
+        )
+            .await.with_context(|| "Failed to add place_limit_order command to PTB")?;
+        with_timeout(self.request_timeout, self.deep_book.get_account_order_details(&mut ptb, pool_key, manager_key))
+            .await.with_context(|| "Failed to add get_account_order_details command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let last_command = response.results.as_ref().map_or(0, |results| results.len().saturating_sub(1));
+
+        simulated_open_orders(response.error.as_deref(), return_value_bytes(&response, last_command, 0))
+    }
+
+    /// Cancel an open order identified by the client order id it was placed with.
+    /// The manager's open orders are fetched to resolve the protocol order id.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - The client order id assigned on placement.
+    ///
+    /// # Returns
+    /// The protocol order id of the canceled order, or an error if no open order matches.
+    pub async fn cancel_by_client_order_id(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: u64,
+    ) -> Result<u128> {
+        let orders = self
+            .get_account_order_details(pool_key, manager_key)
+            .await
+            .with_context(|| "Failed to fetch open orders")?;
+        let order_id = find_order_id_by_client_order_id(&orders, client_order_id)?;
+
+        with_timeout(self.request_timeout, self.deep_book.cancel_order(ptb, pool_key, manager_key, order_id))
+            .await.with_context(|| "Failed to add cancel_order command to PTB")?;
+
+        Ok(order_id)
+    }
+
+    /// Cancel the open orders of a balance manager that expire before a cutoff, e.g. to clear
+    /// stale quotes. DeepBook orders carry no placement time, so the expiration is the age
+    /// proxy; orders placed without an expiration are never canceled.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `cutoff_ms` - Orders expiring strictly before this timestamp, in milliseconds, are canceled.
+    ///
+    /// # Returns
+    /// The protocol order ids of the canceled orders. No command is added if there are none.
+    pub async fn cancel_orders_older_than(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        cutoff_ms: u64,
+    ) -> Result<Vec<u128>> {
+        let orders = self
+            .get_account_order_details(pool_key, manager_key)
+            .await
+            .with_context(|| "Failed to fetch open orders")?;
+        let order_ids = order_ids_expiring_before(&orders, cutoff_ms);
+        if order_ids.is_empty() {
+            return Ok(order_ids);
+        }
+
+        with_timeout(self.request_timeout, self.deep_book.cancel_orders(ptb, pool_key, manager_key, &order_ids))
+            .await.with_context(|| "Failed to add cancel_order commands to PTB")?;
+
+        Ok(order_ids)
+    }
+
+    /// Get an order on the book of a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `order_id` - The protocol order id.
+    ///
+    /// # Returns
+    /// The decoded `Order`, or an error saying the order was not found if it is not on the book.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_order(
+        &self,
+        pool_key: &str,
+        order_id: u128,
+    ) -> Result<Order> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        with_timeout(self.request_timeout, self.deep_book.get_order(&mut ptb, pool_key, order_id))
+            .await.with_context(|| "Failed to add get_order command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        check_get_order_inspect(response.error.as_deref(), pool_key, order_id)?;
+
+        parse_data_from_response(&response)
+            .with_context(|| format!("Failed to parse order {} from dev-inspect-transaction response", order_id))
+    }
+
+    /// Get the unfilled quantity of an order on the book of a pool, e.g. for a keeper topping up
+    /// partially filled quotes.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `order_id` - The protocol order id.
+    ///
+    /// # Returns
+    /// The remaining base quantity, or an error if the order is not on the book.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn order_remaining_quantity(&self, pool_key: &str, order_id: u128) -> Result<f64> {
+        let (base_coin, _) = self.pool_coins(pool_key)?;
+        let order = self
+            .get_order(pool_key, order_id)
+            .await
+            .with_context(|| "Failed to fetch order")?;
+
+        Ok(base_coin.to_human(order.remaining_quantity()))
+    }
+
+    /// Extend the expiry of an open order. The order is read with `get_order`, then canceled
+    /// and placed again with the new expiration in the PTB. This is a cancel/replace, not an
+    /// atomic in-place modify: the replacement gets a new order id and loses time priority.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `order_id` - The protocol order id.
+    /// * `new_expiration` - The new expiration timestamp in milliseconds.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the replacement order.
+    pub async fn extend_order_expiry(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        order_id: u128,
+        new_expiration: u64,
+    ) -> Result<Argument> {
+        let order = self
+            .get_order(pool_key, order_id)
+            .await
+            .with_context(|| "Failed to fetch order")?;
+
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.extend_order_expiry(ptb, pool_key, manager_key, &order, new_expiration),
+        )
+            .await.with_context(|| "Failed to add cancel and replace commands to PTB")
+    }
+
+    /// Resize an open order to `new_quantity`, its new total quantity filled part included. The
+    /// order is read with `get_order` first. Shrinking uses `pool::modify_order`, which is atomic
+    /// and keeps the order id and time priority; growing is a cancel/replace at the same price
+    /// and side, so the replacement gets a new order id and loses time priority.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `order_id` - The protocol order id.
+    /// * `new_quantity` - The new total quantity of the order in base units.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the replacement order when growing, None when shrinking.
+    pub async fn resize_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        order_id: u128,
+        new_quantity: f64,
+    ) -> Result<Option<Argument>> {
+        let (base_coin, _) = self.pool_coins(pool_key)?;
+        let order = self
+            .get_order(pool_key, order_id)
+            .await
+            .with_context(|| "Failed to fetch order")?;
+        let new_quantity = self
+            .deep_book
+            .price_encoder(pool_key)
+            .encode_quantity(new_quantity, base_coin, RoundingMode::Nearest);
+
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.resize_order(ptb, pool_key, manager_key, &order, new_quantity),
+        )
+            .await.with_context(|| "Failed to add resize commands to PTB")
+    }
+
+    /// Check whether an order is still on the book of a pool, without decoding it.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `order_id` - The protocol order id.
+    ///
+    /// # Returns
+    /// `true` if the order exists, `false` if the pool reports it as not found.
+    /// RPC failures and other aborts are returned as errors.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn order_exists(
+        &self,
+        pool_key: &str,
+        order_id: u128,
+    ) -> Result<bool> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        with_timeout(self.request_timeout, self.deep_book.get_order(&mut ptb, pool_key, order_id))
+            .await.with_context(|| "Failed to add get_order command to PTB")?;
+
+        let inspect_error = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .map(|response| response.error);
+
+        order_exists_from_inspect(inspect_error)
+    }
+
+    /// Compute the notional value of an order in human quote units, i.e. `price * quantity`
+    /// after encoding both to on-chain units with the pool's coin scalars. No RPC is issued.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `price` - The price in quote per base.
+    /// * `quantity` - The quantity in base units.
+    ///
+    /// # Returns
+    /// The notional value in quote units.
+    pub fn order_notional(&self, pool_key: &str, price: f64, quantity: f64) -> Result<f64> {
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+        let encoder = self.deep_book.price_encoder(pool_key);
+        let input_price = encoder.encode_price(price, base_coin, quote_coin, RoundingMode::Nearest, true);
+        let input_quantity = encoder.encode_quantity(quantity, base_coin, RoundingMode::Nearest);
+
+        Ok(notional_value(input_price, input_quantity, quote_coin))
+    }
+
+    /// Compute the notional value of the unfilled part of an existing order in human quote units.
+    /// The price is read from the order id. No RPC is issued.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool the order belongs to.
+    /// * `order` - The order, e.g. from `get_account_order_details`.
+    ///
+    /// # Returns
+    /// The notional value in quote units.
+    pub fn open_order_notional(&self, pool_key: &str, order: &Order) -> Result<f64> {
+        let (_, quote_coin) = self.pool_coins(pool_key)?;
+        let open_quantity = order.quantity.saturating_sub(order.filled_quantity);
+
+        Ok(notional_value(order_id_price(order.order_id), open_quantity, quote_coin))
+    }
+
+    /// Get the account information of a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// The decoded `Account` of the balance manager.
+    pub async fn account(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<Account> {
+        self.account_as(pool_key, manager_key, None).await
+    }
+
+    /// Like `account`, with the dev-inspect sender chosen per call.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `sender` - The address to inspect as, e.g. the owner of the manager; the configured address if None.
+    ///
+    /// # Returns
+    /// The decoded `Account` of the balance manager.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn account_as(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        sender: Option<SuiAddress>,
+    ) -> Result<Account> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        with_timeout(self.request_timeout, self.deep_book.account(&mut ptb, pool_key, manager_key))
+            .await.with_context(|| "Failed to add account command to PTB")?;
+
+        let response = self
+            .dev_inspect_with_sender(ptb, sender)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+
+        parse_data_from_response(&response)
+            .with_context(|| "Failed to parse account from dev-inspect-transaction response")
+    }
+
+    /// Get the DEEP stake of a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// A tuple of `(active_stake, inactive_stake)` in DEEP.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn manager_stake(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<(f64, f64)> {
+        let account = self.account(pool_key, manager_key).await?;
+
+        Ok(account.stakes(self.config.deep_scalar()))
+    }
+
+    /// Get the lifetime trading volume of a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// A tuple of `(maker_volume, taker_volume)` in base coin units.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn manager_volume(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<(f64, f64)> {
+        let (base_coin, _) = self.pool_coins(pool_key)?;
+        let account = self.account(pool_key, manager_key).await?;
+
+        Ok(account.volumes(base_coin.scalar))
+    }
+
+    /// Get the amounts of a balance manager settled from fills in a pool but not yet withdrawn,
+    /// i.e. what `withdraw_settled_amounts` would reclaim. They are neither part of the manager's
+    /// free balance nor locked in open orders.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// A tuple of the settled `(base, quote, deep)` amounts.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn settled_balances(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<(f64, f64, f64)> {
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+        let account = self.account(pool_key, manager_key).await?;
+
+        Ok(account.settled_amounts(base_coin.scalar, quote_coin.scalar, self.config.deep_scalar()))
+    }
+
+    /// Get the balances held by the vault of a pool, i.e. the funds of every balance manager
+    /// trading in it: resting orders, settled and unclaimed balances.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// A tuple of the `(base, quote, deep)` amounts held by the vault.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn vault_balances(&self, pool_key: &str) -> Result<(f64, f64, f64)> {
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(self.request_timeout, self.deep_book.vault_balances(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add vault_balances command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let value = |index| parse_return_value_at::<u64>(&response, 0, index)
+            .with_context(|| format!("Failed to parse vault balances of pool: {}", pool_key));
+
+        Ok((
+            base_coin.to_human(value(0)?),
+            quote_coin.to_human(value(1)?),
+            value(2)? as f64 / self.config.deep_scalar() as f64,
+        ))
+    }
+
+    /// Get the total value locked in a pool, i.e. the base and quote held by its vault, priced
+    /// with a caller-supplied feed so the SDK stays oracle-agnostic. The DEEP held for fees
+    /// is not counted.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `price_feed` - The feed pricing the base and quote coins of the pool.
+    ///
+    /// # Returns
+    /// The total value of the vault in the unit of the feed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, price_feed)))]
+    pub async fn pool_tvl<F: PriceFeed + ?Sized>(&self, pool_key: &str, price_feed: &F) -> Result<f64> {
+        let pool = self.config.require_pool(pool_key)?;
+        let (base, quote, _) = self
+            .vault_balances(pool_key)
+            .await
+            .with_context(|| "Failed to get vault balances")?;
+
+        holdings_value(price_feed, &[(pool.base_coin.as_str(), base), (pool.quote_coin.as_str(), quote)])
+            .await
+            .with_context(|| format!("Failed to value vault of pool: {}", pool_key))
+    }
+
+    /// Get the level-2 order book of a pool, from the indexer if one is set, otherwise with a
+    /// dev-inspect of `get_level2_ticks_from_mid`.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `ticks` - The number of price levels to return on each side. On-chain, levels are
+    ///   counted in ticks from the mid price, so empty ticks count too.
+    ///
+    /// # Returns
+    /// The order book, best prices first.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn order_book(&self, pool_key: &str, ticks: u64) -> Result<OrderBook> {
+        if let Some(indexer) = &self.indexer {
+            let mut book = indexer.order_book(pool_key).await?;
+            book.truncate(ticks as usize);
+            return Ok(book);
+        }
+
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(self.request_timeout, self.deep_book.get_level2_ticks_from_mid(&mut ptb, pool_key, ticks))
+            .await.with_context(|| "Failed to add get_level2_ticks_from_mid command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let level = |index| parse_return_value_at::<Vec<u64>>(&response, 0, index)
+            .with_context(|| format!("Failed to parse order book of pool: {}", pool_key));
+
+        Ok(OrderBook::from_level2([level(0)?, level(1)?, level(2)?, level(3)?], base_coin.scalar, quote_coin.scalar))
+    }
+
+    /// Get the best bid and best ask prices of a pool, the lightest quote fetch, with a
+    /// one-tick `order_book` read.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The best bid and best ask in quote per base, each None if that side of the book is empty.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn best_bid_ask(&self, pool_key: &str) -> Result<(Option<f64>, Option<f64>)> {
+        let book = self
+            .order_book(pool_key, 1)
+            .await
+            .with_context(|| format!("Failed to get order book of pool: {}", pool_key))?;
+
+        Ok(book.best_bid_ask())
+    }
+
+    /// Get the spread, depth and imbalance of a pool's order book, e.g. as trading signals,
+    /// from a single `order_book` fetch.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `depth` - The number of price levels to include on each side.
+    ///
+    /// # Returns
+    /// The book metrics, or an error if either side of the book is empty.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn book_metrics(&self, pool_key: &str, depth: u64) -> Result<BookMetrics> {
+        let book = self
+            .order_book(pool_key, depth)
+            .await
+            .with_context(|| format!("Failed to get order book of pool: {}", pool_key))?;
+
+        book.metrics()
+            .ok_or_else(|| anyhow!("Order book of pool {} has an empty side", pool_key))
+    }
+
+    /// Get the trading statistics of a pool over the last 24 hours, aggregated from the
+    /// `order_info::OrderFilled` events of the DeepBook package. Events of every pool are paged
+    /// through, so this costs one RPC per 50 fills across DeepBook in the period, and fails
+    /// rather than read more than `MAX_EVENT_PAGES` pages; busy deployments are better served
+    /// by the indexer.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The volumes, high, low and last prices and price change of the pool, or an error if it
+    /// had no trades in the period.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn pool_stats_24h(&self, pool_key: &str) -> Result<PoolStats> {
+        const DAY_MS: u64 = 24 * 60 * 60 * 1_000;
+
+        let pool = self.config.require_pool(pool_key)?;
+        let pool_id = ObjectID::from_hex_literal(&pool.address)
+            .with_context(|| format!("Invalid pool address: {}", pool.address))?;
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .with_context(|| "System clock is before the Unix epoch")?
+            .as_millis() as u64;
+
+        let event_type = format!("{}::order_info::OrderFilled", self.config.deepbook_package_id);
+        let events = fetch_events_since(&self.client, &event_type, now_ms.saturating_sub(DAY_MS), MAX_EVENT_PAGES)
+            .await
+            .with_context(|| "Failed to fetch fill events")?;
+        let trades = events
+            .iter()
+            .filter(|event| event.parsed_json.get("pool_id").and_then(json_object_id) == Some(pool_id))
+            .map(|event| Trade::from_order_filled_json(
+                format!("{}:{}", event.id.tx_digest, event.id.event_seq),
+                &event.parsed_json,
+                base_coin.scalar,
+                quote_coin.scalar,
+            ))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("Failed to decode fill events of pool: {}", pool_key))?;
+
+        PoolStats::from_trades(&trades)
+            .ok_or_else(|| anyhow!("No trades in pool {} in the last 24 hours", pool_key))
+    }
+
+    /// Get the most recent fills of a balance manager's orders in a pool, as maker or taker, e.g.
+    /// to compute PnL. `OrderFilled` events of every pool are paged through, newest first, until
+    /// `limit` fills of the manager are found, the events run out or 20 pages of 50 events were
+    /// read. Pass the returned `next_cursor` back in to continue with older fills.
+    ///
+    /// # Arguments
+    /// * `manager_key` - The key of the balance manager.
+    /// * `pool_key` - The key of the pool.
+    /// * `limit` - The maximum number of fills to return.
+    /// * `cursor` - The `next_cursor` of the previous call, or None to start from the newest fill.
+    ///
+    /// # Returns
+    /// The fills, most recent first, and the cursor to continue from.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn manager_trade_history(
+        &self,
+        manager_key: &str,
+        pool_key: &str,
+        limit: usize,
+        cursor: Option<EventID>,
+    ) -> Result<ManagerTradeHistory> {
+        const MAX_PAGES: usize = 20;
+
+        let manager = self.config.require_balance_manager(manager_key)?;
+        let manager_id = ObjectID::from_hex_literal(&manager.address)
+            .with_context(|| format!("Invalid balance manager address: {}", manager.address))?;
+        let pool = self.config.require_pool(pool_key)?;
+        let pool_id = ObjectID::from_hex_literal(&pool.address)
+            .with_context(|| format!("Invalid pool address: {}", pool.address))?;
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+        let scalars = (base_coin.scalar, quote_coin.scalar, self.config.deep_scalar());
+
+        let event_type = format!("{}::order_info::OrderFilled", self.config.deepbook_package_id);
+        let (trades, next_cursor) = collect_pages_up_to(
+            cursor,
+            limit,
+            MAX_PAGES,
+            |cursor| fetch_events_page(&self.client, &event_type, cursor),
+            |event: &SuiEvent| event.id,
+            |event: &SuiEvent| {
+                if event.parsed_json.get("pool_id").and_then(json_object_id) != Some(pool_id) {
+                    return Ok(None);
+                }
+                ManagerTrade::from_order_filled_json(
+                    format!("{}:{}", event.id.tx_digest, event.id.event_seq),
+                    &event.parsed_json,
+                    manager_id,
+                    scalars,
+                ).with_context(|| format!("Failed to decode fill events of pool: {}", pool_key))
+            },
+        )
+            .await
+            .with_context(|| "Failed to fetch fill events")?;
+
+        Ok(ManagerTradeHistory { trades, next_cursor })
+    }
+
+    /// Get the on-chain metadata of a configured coin, e.g. to check its configured `scalar`.
+    /// A scalar that doesn't match `10^decimals` is logged as a warning, since every amount of
+    /// the coin would be off by a power of ten. Results are cached for the client's lifetime.
+    ///
+    /// # Arguments
+    /// * `coin_key` - The key of the coin.
+    ///
+    /// # Returns
+    /// The decimals, symbol and name of the coin, or an error if it has no metadata.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn fetch_coin_metadata(&self, coin_key: &str) -> Result<CoinMetadata> {
+        let cached = self.coin_metadata
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(coin_key)
+            .cloned();
+        if let Some(metadata) = cached {
+            return Ok(metadata);
+        }
+
+        let coin = self.config.require_coin(coin_key)?;
+        let fetch = async {
+            self.client
+                .coin_read_api()
+                .get_coin_metadata(coin.type_.clone())
+                .await
+                .with_context(|| format!("Failed to fetch metadata of coin: {}", coin_key))
+        };
+        let metadata = with_timeout(self.request_timeout, fetch)
+            .await?
+            .ok_or_else(|| anyhow!("No coin metadata found for coin: {}", coin_key))?;
+        let metadata = CoinMetadata { decimals: metadata.decimals, symbol: metadata.symbol, name: metadata.name };
+        if !metadata.scalar_matches(coin.scalar) {
+            warn!(
+                "Configured scalar {} of coin {} does not match its {} on-chain decimals",
+                coin.scalar, coin_key, metadata.decimals,
+            );
+        }
+
+        self.coin_metadata
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(coin_key.to_string(), metadata.clone());
+
+        Ok(metadata)
+    }
+
+    /// Get the base and quote coin types of a pool from its on-chain type, e.g. to check the
+    /// configuration or to trade coins that aren't in the `CoinMap`.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The `(base, quote)` coin types read from the pool object.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn pool_coin_types(&self, pool_key: &str) -> Result<(TypeTag, TypeTag)> {
+        let pool = self.config.require_pool(pool_key)?;
+        let object = with_timeout(self.request_timeout, fetch_object(&self.client, &pool.address))
+            .await
+            .with_context(|| format!("Failed to fetch pool: {}", pool_key))?;
+
+        pool_coin_types_from_type(&object_type_string(&object)?)
+            .with_context(|| format!("Failed to read coin types of pool: {}", pool_key))
+    }
+
+    /// Get the trade parameters of a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The taker and maker fee rates of the pool, and its stake required in DEEP base units.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn pool_trade_params(&self, pool_key: &str) -> Result<PoolTradeParams> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(self.request_timeout, self.deep_book.pool_trade_params(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add pool_trade_params command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let value = |index| parse_return_value_at::<u64>(&response, 0, index)
+            .with_context(|| format!("Failed to parse trade params of pool: {}", pool_key));
+
+        Ok(PoolTradeParams {
+            taker_fee: FeeRate::from_float_scalar(value(0)?),
+            maker_fee: FeeRate::from_float_scalar(value(1)?),
+            stake_required: value(2)?,
+        })
+    }
+
+    /// Get a pre-trade snapshot of a pool, e.g. for a trading UI selecting a market: its book
+    /// and trade parameters, whether it is whitelisted and its mid price, with a single dev-inspect.
+    /// The fetched book parameters are cached.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The pool parameters, with no mid price if either side of the book is empty.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn pool_full_params(&self, pool_key: &str) -> Result<PoolParams> {
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(self.request_timeout, self.deep_book.pool_book_params(&mut ptb, &[pool_key]))
+            .await.with_context(|| "Failed to add pool_book_params command to PTB")?;
+        with_timeout(self.request_timeout, self.deep_book.pool_trade_params(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add pool_trade_params command to PTB")?;
+        with_timeout(self.request_timeout, self.deep_book.whitelisted(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add whitelisted command to PTB")?;
+        with_timeout(self.request_timeout, self.deep_book.get_level2_ticks_from_mid(&mut ptb, pool_key, 1))
+            .await.with_context(|| "Failed to add get_level2_ticks_from_mid command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let pool_params = PoolParams::from_return_values(
+            |command, value| return_value_bytes(&response, command, value),
+            base_coin.scalar,
+            quote_coin.scalar,
+        ).with_context(|| format!("Failed to parse params of pool: {}", pool_key))?;
+        self.deep_book.cache_pool_params(HashMap::from([(pool_key.to_string(), pool_params.book)]));
+
+        Ok(pool_params)
+    }
+
+    /// Compute the all-in price of a taker fill at `price`, including the pool's taker fee,
+    /// e.g. for a UI showing "you effectively pay X". Stake-based fee discounts are not applied.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `price` - The quoted price in quote per base.
+    /// * `is_bid` - Whether the taker buys the base coin.
+    /// * `pay_with_deep` - Whether the fee is paid in DEEP instead of the input coin.
+    ///
+    /// # Returns
+    /// The price paid per base for a bid, or received for an ask, after fees.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn effective_price(
+        &self,
+        pool_key: &str,
+        price: f64,
+        is_bid: bool,
+        pay_with_deep: bool,
+    ) -> Result<f64> {
+        let trade_params = self
+            .pool_trade_params(pool_key)
+            .await
+            .with_context(|| "Failed to get pool trade params")?;
+
+        Ok(effective_taker_price(price, trade_params.taker_fee.as_float_scalar(), is_bid, pay_with_deep))
+    }
+
+    /// Estimate the DEEP fees of a batch of orders, e.g. a ladder before it is placed.
+    /// The pool's trade params and DEEP price are fetched once for all orders, and each order
+    /// is charged the taker fee, an upper bound since a resting quantity pays the maker fee.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `orders` - The orders as `(price, quantity, is_bid)`.
+    ///
+    /// # Returns
+    /// The total DEEP required by the orders.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn estimate_batch_deep(&self, pool_key: &str, orders: &[(f64, f64, bool)]) -> Result<f64> {
+        let (base_coin, quote_coin) = self.pool_coins(pool_key)?;
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(self.request_timeout, self.deep_book.pool_trade_params(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add pool_trade_params command to PTB")?;
+        with_timeout(self.request_timeout, self.deep_book.get_order_deep_price(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add get_order_deep_price command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let taker_fee = parse_return_value_at::<u64>(&response, 0, 0)
+            .with_context(|| format!("Failed to parse taker fee of pool: {}", pool_key))?;
+        let deep_price = parse_return_value_at::<OrderDeepPrice>(&response, 1, 0)
+            .with_context(|| format!("Failed to parse DEEP price of pool: {}", pool_key))?;
+
+        let encoder = self.deep_book.price_encoder(pool_key);
+        let deep_required: u64 = orders
+            .iter()
+            .map(|&(price, quantity, is_bid)| order_deep_required(
+                encoder.encode_price(price, base_coin, quote_coin, RoundingMode::Nearest, is_bid),
+                encoder.encode_quantity(quantity, base_coin, RoundingMode::Nearest),
+                taker_fee,
+                &deep_price,
+            ))
+            .sum();
+
+        Ok(deep_required as f64 / self.config.deep_scalar() as f64)
+    }
+
+    /// Recommend how much DEEP to deposit into a balance manager to pay the fees of
+    /// `expected_notional` of trading, e.g. to pre-fund a manager before running a strategy.
+    /// Every fill is charged the taker fee, and `DEEP_DEPOSIT_SAFETY_MARGIN` is added on top.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `expected_notional` - The expected trading volume in the quote coin.
+    ///
+    /// # Returns
+    /// The DEEP to deposit.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn recommend_deep_deposit(&self, pool_key: &str, expected_notional: f64) -> Result<f64> {
+        let (_, quote_coin) = self.pool_coins(pool_key)?;
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        with_timeout(self.request_timeout, self.deep_book.pool_trade_params(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add pool_trade_params command to PTB")?;
+        with_timeout(self.request_timeout, self.deep_book.get_order_deep_price(&mut ptb, pool_key))
+            .await.with_context(|| "Failed to add get_order_deep_price command to PTB")?;
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let taker_fee = parse_return_value_at::<u64>(&response, 0, 0)
+            .with_context(|| format!("Failed to parse taker fee of pool: {}", pool_key))?;
+        let deep_price = parse_return_value_at::<OrderDeepPrice>(&response, 1, 0)
+            .with_context(|| format!("Failed to parse DEEP price of pool: {}", pool_key))?;
+
+        // A DEEP price per base unit needs the mid price to convert the quote notional
+        let mid_price = if deep_price.asset_is_base {
+            let mut ptb = ProgrammableTransactionBuilder::new();
+            with_timeout(self.request_timeout, self.deep_book.mid_price(&mut ptb, pool_key))
+                .await.with_context(|| "Failed to add mid_price command to PTB")?;
+            let response = self
+                .dev_inspect_transaction_results(ptb)
+                .await
+                .with_context(|| format!("Failed to get mid price of pool: {}", pool_key))?;
+            parse_return_value_at::<u64>(&response, 0, 0)
+                .with_context(|| format!("Failed to parse mid price of pool: {}", pool_key))?
+        } else {
+            0
+        };
+
+        let deep_required = recommended_deep_deposit(
+            quote_coin.to_units(expected_notional),
+            mid_price,
+            taker_fee,
+            &deep_price,
+        );
+
+        Ok(deep_required as f64 / self.config.deep_scalar() as f64)
+    }
+
+    /// Get the fee tier of a balance manager in a pool, i.e. whether its active stake
+    /// meets the pool's `stake_required` and it qualifies for reduced fees.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// The `FeeTier` of the balance manager.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn manager_fee_tier(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<FeeTier> {
+        let trade_params = self.pool_trade_params(pool_key).await?;
+        let account = self.account(pool_key, manager_key).await?;
+
+        Ok(trade_params.fee_tier(account.active_stake))
+    }
+
+    /// Get the governance proposals of a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The proposals with fees as fractions and stake/votes in DEEP.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_proposals(&self, pool_key: &str) -> Result<Vec<Proposal>> {
+        let pool_inner = with_timeout(self.request_timeout, fetch_pool_inner_fields(&self.client, &self.config, pool_key))
+            .await
+            .with_context(|| "Failed to fetch pool state")?;
+        let governance = move_field(&pool_inner, "state")
+            .and_then(|state| move_field(state, "governance"))
+            .ok_or_else(|| anyhow!("Pool state is missing governance for key: {}", pool_key))?;
+
+        Proposal::from_governance_json(governance, self.config.deep_scalar())
+            .with_context(|| format!("Failed to decode proposals for pool: {}", pool_key))
+    }
+
+    /// Get the supply of the DEEP token from the treasury referenced by `deep_treasury_id`.
+    ///
+    /// # Returns
+    /// The decoded `DeepTreasuryInfo`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn deep_treasury_info(&self) -> Result<DeepTreasuryInfo> {
+        let treasury_cap = with_timeout(self.request_timeout, fetch_dynamic_field_by_type(&self.client, &self.config.deep_treasury_id, "TreasuryCap"))
+            .await
+            .with_context(|| "Failed to fetch DEEP treasury cap")?;
+
+        DeepTreasuryInfo::from_treasury_cap_json(&treasury_cap, self.config.deep_scalar())
+            .with_context(|| "Failed to decode DEEP treasury cap")
+    }
+
+    /// Get the vote tally of a governance proposal of a pool, e.g. to see whether it is near the
+    /// quorum. The votes are read from the pool's governance state. The voters are counted from
+    /// the `state::VoteEvent`s of the current governance epoch, paged through for every pool, and
+    /// include managers that unstaked after voting.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    /// * `proposal_id` - The id of the proposal.
+    ///
+    /// # Returns
+    /// A tuple of `(votes_in_deep, voter_count)`, or an error if the pool has no such proposal.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn proposal_votes(&self, pool_key: &str, proposal_id: &str) -> Result<(f64, u64)> {
+        let proposal_id = ObjectID::from_hex_literal(proposal_id)
+            .with_context(|| format!("Invalid proposal id: {}", proposal_id))?;
+        let pool = self.config.require_pool(pool_key)?;
+        let pool_id = ObjectID::from_hex_literal(&pool.address)
+            .with_context(|| format!("Invalid pool address: {}", pool.address))?;
+
+        let pool_inner = with_timeout(self.request_timeout, fetch_pool_inner_fields(&self.client, &self.config, pool_key))
+            .await
+            .with_context(|| "Failed to fetch pool state")?;
+        let governance = move_field(&pool_inner, "state")
+            .and_then(|state| move_field(state, "governance"))
+            .ok_or_else(|| anyhow!("Pool state is missing governance for key: {}", pool_key))?;
+        let epoch = move_field(governance, "epoch")
+            .and_then(json_number::<u64>)
+            .ok_or_else(|| anyhow!("Governance is missing epoch for key: {}", pool_key))?;
+        let votes = Proposal::from_governance_json(governance, self.config.deep_scalar())
+            .with_context(|| format!("Failed to decode proposals for pool: {}", pool_key))?
+            .into_iter()
+            .find(|proposal| proposal.id == proposal_id)
+            .map(|proposal| proposal.votes)
+            .ok_or_else(|| anyhow!("Proposal {} not found in pool {}", proposal_id, pool_key))?;
+
+        let event_type = format!("{}::state::VoteEvent", self.config.deepbook_package_id);
+        let events = fetch_events_while(&self.client, &event_type, |event| {
+            event.parsed_json.get("epoch").and_then(json_number::<u64>).is_some_and(|event_epoch| event_epoch >= epoch)
+        }, MAX_EVENT_PAGES)
+            .await
+            .with_context(|| "Failed to fetch vote events")?;
+        let voter_count = proposal_voter_count(
+            events
+                .iter()
+                .filter(|event| event.parsed_json.get("pool_id").and_then(json_object_id) == Some(pool_id))
+                .map(|event| &event.parsed_json),
+            proposal_id,
+            epoch,
+        );
+
+        Ok((votes, voter_count))
+    }
+
+    /// Get the number of orders resting on each side of the book of a pool, to gauge how busy
+    /// it is before fetching full level-2 data.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// A tuple of `(bid_order_count, ask_order_count)`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn pool_order_count(&self, pool_key: &str) -> Result<(u64, u64)> {
+        let pool_inner = with_timeout(self.request_timeout, fetch_pool_inner_fields(&self.client, &self.config, pool_key))
+            .await
+            .with_context(|| "Failed to fetch pool state")?;
+
+        order_counts_from_json(&pool_inner)
+            .with_context(|| format!("Failed to decode order counts of pool: {}", pool_key))
+    }
+
+    /// Get how old the DEEP price of a pool is, i.e. the time since the last `add_deep_price_point`
+    /// relative to the on-chain clock, so keepers know when fees are computed from a stale price.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// The age of the latest DEEP price point, or an error if the pool has none.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn deep_price_age(&self, pool_key: &str) -> Result<Duration> {
+        let pool_inner = with_timeout(self.request_timeout, fetch_pool_inner_fields(&self.client, &self.config, pool_key))
+            .await
+            .with_context(|| "Failed to fetch pool state")?;
+        let clock = with_timeout(self.request_timeout, fetch_move_object_fields(&self.client, &self.config.clock_object_id.to_string()))
+            .await
+            .with_context(|| "Failed to fetch clock")?;
+        let now_ms = move_field(&clock, "timestamp_ms")
+            .and_then(json_number::<u64>)
+            .ok_or_else(|| anyhow!("Clock is missing timestamp_ms"))?;
+
+        deep_price_age_from_json(&pool_inner, now_ms)
+            .with_context(|| format!("Failed to decode DEEP price of pool: {}", pool_key))
+    }
+
+    /// Check whether a pool is enabled, i.e. still allows the DeepBook version of this SDK.
+    /// A pool that no longer allows it rejects every order.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key of the pool.
+    ///
+    /// # Returns
+    /// `true` if orders can be placed in the pool.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn pool_enabled(&self, pool_key: &str) -> Result<bool> {
+        let pool_inner = with_timeout(self.request_timeout, fetch_pool_inner_fields(&self.client, &self.config, pool_key))
+            .await
+            .with_context(|| "Failed to fetch pool state")?;
+        let allowed_versions = allowed_versions_from_json(&pool_inner)
+            .with_context(|| format!("Failed to decode allowed versions of pool: {}", pool_key))?;
+
+        Ok(allowed_versions.contains(&DEEPBOOK_VERSION))
+    }
+
+    /// Get the latest DeepBook package version allowed by the on-chain registry.
+    /// Logs a warning if the version this SDK is written against is no longer allowed,
+    /// which means the configured package ids are stale.
+    ///
+    /// # Returns
+    /// The highest allowed package version.
+    pub async fn package_version(&self) -> Result<u64> {
+        let registry_fields = with_timeout(self.request_timeout, fetch_move_object_fields(&self.client, &self.config.registry_id))
+            .await
+            .with_context(|| "Failed to fetch registry")?;
+        let registry_inner = with_timeout(self.request_timeout, fetch_versioned_inner_fields(&self.client, &registry_fields))
+            .await
+            .with_context(|| "Failed to fetch inner state of registry")?;
+        let allowed_versions = allowed_versions_from_json(&registry_inner)
+            .with_context(|| "Failed to decode registry allowed versions")?;
+
+        if !allowed_versions.contains(&DEEPBOOK_VERSION) {
+            warn!(
+                "DeepBook version {} is not allowed on-chain (allowed: {:?}), package ids may be stale",
+                DEEPBOOK_VERSION, allowed_versions,
+            );
+        }
+
+        allowed_versions
+            .last()
+            .copied()
+            .ok_or_else(|| anyhow!("Registry allows no versions"))
+    }
+
+    /// Check that the configured DeepBook package and registry ids are live objects of the
+    /// expected types on the connected network, e.g. at startup. The ids are hardcoded per
+    /// environment and go stale on redeploys, which otherwise surfaces as confusing failures later.
+    ///
+    /// # Arguments
+    /// * `strict` - Whether stale ids are an error instead of a logged warning.
+    ///
+    /// # Returns
+    /// Ok(()) if the ids are live, or if `strict` is false.
+    pub async fn verify_package_ids(&self, strict: bool) -> Result<()> {
+        let object_ids = [
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)
+                .with_context(|| format!("Invalid DeepBook package id: {}", self.config.deepbook_package_id))?,
+            ObjectID::from_hex_literal(&self.config.registry_id)
+                .with_context(|| format!("Invalid registry id: {}", self.config.registry_id))?,
+        ];
+        let objects = with_timeout(self.request_timeout, self.client.multi_get_objects(&object_ids))
+            .await
+            .with_context(|| "Failed to fetch DeepBook package and registry")?;
+        let object_type = |index: usize| objects.get(index).and_then(|object| object_type_string(object).ok());
+
+        let problems = package_id_problems(&self.config, object_type(0).as_deref(), object_type(1).as_deref());
+        if problems.is_empty() {
+            return Ok(());
+        }
+        if strict {
+            return Err(anyhow!("Stale DeepBook ids: {}", problems.join("; ")));
+        }
+        for problem in &problems {
+            warn!("Stale DeepBook id: {}", problem);
+        }
+
+        Ok(())
+    }
+
+    /// Get the coin types used by the pools registered in the on-chain registry, e.g. to
+    /// discover which coins already trade on DeepBook. The registry's pools are paged through,
+    /// so this costs one RPC per 50 pools.
+    ///
+    /// # Returns
+    /// The coin types, each listed once.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn get_registered_coins(&self) -> Result<Vec<TypeTag>> {
+        let registry_fields = with_timeout(self.request_timeout, fetch_move_object_fields(&self.client, &self.config.registry_id))
+            .await
+            .with_context(|| "Failed to fetch registry")?;
+        let registry_inner = with_timeout(self.request_timeout, fetch_versioned_inner_fields(&self.client, &registry_fields))
+            .await
+            .with_context(|| "Failed to fetch inner state of registry")?;
+        let pools_id = move_field(&registry_inner, "pools")
+            .and_then(|pools| move_field(pools, "id"))
+            .and_then(json_object_id)
+            .ok_or_else(|| anyhow!("Registry is missing its pools"))?;
+
+        let pool_keys: Vec<serde_json::Value> = fetch_all_dynamic_fields(&self.client, pools_id)
+            .await
+            .with_context(|| "Failed to fetch registered pools")?
+            .into_iter()
+            .map(|field_info| field_info.name.value)
+            .collect();
+
+        coin_types_from_pool_keys(&pool_keys).with_context(|| "Failed to decode registered pools")
+    }
+
+    /// Get the DEEP fee required to create a permissionless pool.
+    /// DeepBook defines the fee in its `constants` module rather than in the registry,
+    /// so it's read by dev-inspecting `constants::pool_creation_fee`.
+    ///
+    /// # Returns
+    /// The pool creation fee in DEEP.
+    pub async fn pool_creation_fee(&self) -> Result<f64> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("constants")?,
+            Identifier::new("pool_creation_fee")?,
+            vec![],
+            vec![],
+        );
+
+        let response = self
+            .dev_inspect_transaction_results(ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+
+        decode_deep_amount(return_value_bytes(&response, 0, 0), self.config.deep_scalar())
+            .with_context(|| "Failed to parse pool creation fee from dev-inspect-transaction response")
+    }
+
+    /// Get the ids of the TradeCap objects owned by an address.
+    /// A TradeCap lets its owner place orders through the BalanceManager it was minted for.
+    ///
+    /// # Arguments
+    /// * `owner` - The address owning the trade caps.
+    ///
+    /// # Returns
+    /// The object ids of all trade caps owned by `owner`.
+    pub async fn owned_trade_caps(&self, owner: SuiAddress) -> Result<Vec<ObjectID>> {
+        let trade_cap_type = format!("{}::balance_manager::TradeCap", self.config.deepbook_package_id);
+
+        fetch_owned_object_ids(&self.client, owner, &trade_cap_type)
+            .await
+            .with_context(|| format!("Failed to fetch trade caps owned by {}", owner))
+    }
+
+    /// Mint and transfer trade cap to a receiver.
+    /// With trade cap, the receiver can place orders via specified BalanceManager.
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `manager_key` - The key identifying the balance manager.
+    /// * `receiver` - The address of the receiver.
+    /// # Returns
+    /// None on success, or an error.
+    pub async fn mint_and_transfer_trade_cap(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        receiver: SuiAddress,
+    ) -> Result<(), anyhow::Error> {
+        let manager = self.config.require_balance_manager(manager_key)?;
+
+        let manager_id = ObjectID::from_hex_literal(&manager.address)?;
+        let options = SuiObjectDataOptions::new().with_content().with_type().with_owner();
+        let fetch = async {
+            self.client
+                .read_api()
+                .get_object_with_options(manager_id, options)
+                .await
+                .with_context(|| format!("Failed to fetch balance manager: {}", manager.address))
+        };
+        let manager_obj = with_timeout(self.request_timeout, fetch).await?;
+
+        match manager_obj.owner() {
+            Some(owner) => {
+                match owner {
+                    Owner::Shared { initial_shared_version, .. } => {
+                        let initial_shared_version = initial_shared_version.clone();
+                        let manager_argument = ptb.obj(ObjectArg::SharedObject {
+                            id: ObjectID::from_hex_literal(&manager.address)?,
+                            initial_shared_version,
+                            mutable: true,
+                        })?;
+                        let trade_cap = ptb.programmable_move_call(
+                            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+                            Identifier::new("balance_manager")?,
+                            Identifier::new("mint_trade_cap")?,
+                            vec![],
+                            vec![manager_argument],
+                        );
+
+                        let trade_cap_type = TypeTag::from_str(format!("{}::balance_manager::TradeCap", self.config.deepbook_package_id).as_str())?;
+                        let receiver_arg = ptb.pure(receiver)?;
+                        ptb.programmable_move_call(
+                            ObjectID::from_hex_literal("0x2")?,
+                            Identifier::new("transfer")?,
+                            Identifier::new("public_transfer")?,
+                            vec![trade_cap_type],
+                            vec![trade_cap, receiver_arg],
+                        );
+                    }
+                    _ => {
+                        return Err(anyhow!("BalanceManager must be a shared object"));
+                    }
+                }
+            },
+            None => {
+                return Err(anyhow!("BalanceManager has no owner"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unstake all DEEP of a balance manager from a pool, keeping it in the manager.
+    /// `pool::unstake` has no coin to deposit back: DeepBook settles the unstaked DEEP
+    /// straight into the manager's balance, so the PTB only needs the unstake call.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn unstake_and_deposit(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<()> {
+        with_timeout(self.request_timeout, self.governance.unstake(ptb, pool_key, manager_key))
+            .await.with_context(|| "Failed to add unstake command to PTB")?;
+
+        Ok(())
+    }
+
+    /// Unstake all DEEP of a balance manager from a pool and transfer it to `recipient`.
+    /// The current stake is read first, then withdrawn from the manager after the unstake.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `recipient` - The address receiving the DEEP.
+    ///
+    /// # Returns
+    /// The unstaked amount in DEEP.
+    pub async fn unstake_and_transfer(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        recipient: SuiAddress,
+    ) -> Result<f64> {
+        let account = self
+            .account(pool_key, manager_key)
+            .await
+            .with_context(|| "Failed to fetch manager stake")?;
+        let stake = account.active_stake + account.inactive_stake;
+        if stake == 0 {
+            return Err(anyhow!("BalanceManager {} has no stake in pool {}", manager_key, pool_key));
+        }
+
+        with_timeout(
+            self.request_timeout,
+            self.governance.unstake_and_transfer(ptb, pool_key, manager_key, stake, recipient),
+        )
+            .await.with_context(|| "Failed to add unstake and transfer commands to PTB")?;
+
+        Ok(stake as f64 / self.config.deep_scalar() as f64)
+    }
+
+    /// Stake DEEP of a balance manager in a pool and vote for a proposal in one transaction,
+    /// with one trade proof shared by both calls. The DEEP is taken from the manager's balance
+    /// and only counts towards votes once active in the next epoch.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `amount` - The amount of DEEP to stake.
+    /// * `proposal_id` - The id of the proposal to vote for.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn stake_and_vote(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        amount: f64,
+        proposal_id: &str,
+    ) -> Result<()> {
+        let proposal_id = ObjectID::from_hex_literal(proposal_id)
+            .with_context(|| format!("Invalid proposal id: {}", proposal_id))?;
+        let deep_coin = self.config
+            .require_deep_coin()?;
+        let stake_amount = deep_coin.to_units(amount);
+
+        with_timeout(
+            self.request_timeout,
+            self.governance.stake_and_vote(ptb, pool_key, manager_key, stake_amount, proposal_id),
+        )
+            .await.with_context(|| "Failed to add stake and vote commands to PTB")?;
+
+        Ok(())
+    }
+
+    /// Submit a governance proposal for new trade parameters of a pool, e.g.
+    /// `FeeRate::from_bps(10.0)` for a 0.1% taker fee.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key of the pool.
+    /// * `manager_key` - The key of the balance manager, with active stake in the pool.
+    /// * `taker_fee` - The proposed taker fee rate.
+    /// * `maker_fee` - The proposed maker fee rate.
+    /// * `stake_required` - The proposed stake required for reduced fees, in DEEP.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn submit_proposal(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        taker_fee: FeeRate,
+        maker_fee: FeeRate,
+        stake_required: f64,
+    ) -> Result<()> {
+        let deep_coin = self.config
+            .require_deep_coin()?;
+
+        with_timeout(
+            self.request_timeout,
+            self.governance.submit_proposal(
+                ptb,
+                pool_key,
+                manager_key,
+                taker_fee,
+                maker_fee,
+                deep_coin.to_units(stake_required),
+            ),
+        )
+            .await.with_context(|| "Failed to add submit_proposal command to PTB")?;
+
+        Ok(())
+    }
+
+    /// Add a DEEP price point to a pool, e.g. from a keeper that keeps DEEP fees priced.
+    /// Without `reference_pool_key`, the reference pool is resolved from the configuration,
+    /// e.g. `DEEP_USDC` for USDC-quoted pools.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `target_pool_key` - The key of the pool to add the price point to.
+    /// * `reference_pool_key` - The key of the DEEP reference pool, or None to resolve it.
+    ///
+    /// # Returns
+    /// The key of the reference pool used.
+    pub async fn add_deep_price_point(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        target_pool_key: &str,
+        reference_pool_key: Option<&str>,
+    ) -> Result<String> {
+        with_timeout(
+            self.request_timeout,
+            self.deep_book.add_deep_price_point(ptb, target_pool_key, reference_pool_key),
+        )
+            .await.with_context(|| format!("Failed to add DEEP price point to PTB for pool: {}", target_pool_key))
+    }
+
+    /// Add a flash-loan arbitrage between two pools to the PTB.
+    /// Borrows the base asset of `pool_a`, swaps it through `pool_b` and back through `pool_a`,
+    /// repays the loan and aborts unless at least `min_profit` is left over.
+    /// See `FlashLoanContract::arb_between_pools` for the exact command sequence.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_a` - The key of the pool to borrow from.
+    /// * `pool_b` - The key of the pool trading the same two coins.
+    /// * `amount` - The amount of base asset of `pool_a` to borrow.
+    /// * `min_profit` - The minimum profit, in base asset of `pool_a`.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn arb_between_pools(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_a: &str,
+        pool_b: &str,
+        amount: f64,
+        min_profit: f64,
+    ) -> Result<()> {
+        with_timeout(self.request_timeout, self.flash_loans.arb_between_pools(ptb, pool_a, pool_b, amount, min_profit))
+            .await
+            .with_context(|| format!("Failed to add arbitrage between {} and {} to PTB", pool_a, pool_b))
+    }
+
+    /// Merge every owned coin of a coin type into one coin argument, e.g. to deposit or swap
+    /// a whole balance that is fragmented into dust. Coins are owned by the configured address.
+    /// For SUI, use coins that are not also used as gas payment.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `coin_key` - The key identifying the coin.
+    ///
+    /// # Returns
+    /// The `Argument` of the merged coin.
+    pub async fn consolidate_coins(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        coin_key: &str,
+    ) -> Result<Argument> {
+        let coin = self.config.require_coin(coin_key)?;
+        let owner = self.config.sender();
+
+        let coins = fetch_owned_coins(&self.client, owner, &coin.type_)
+            .await
+            .with_context(|| format!("Failed to fetch coins for key: {}", coin_key))?;
+        let coin_refs: Vec<_> = coins.iter().map(|coin| coin.object_ref()).collect();
+
+        merge_coin_arguments(ptb, &coin_refs)
+            .with_context(|| format!("Failed to merge coins for key: {}", coin_key))
+    }
+
+    /// Split a DEEP coin of exactly `amount` off the DEEP coins owned by the configured address,
+    /// e.g. to pay the DEEP fee of a swap computed with `estimate_batch_deep`.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `amount` - The amount of DEEP to split.
+    ///
+    /// # Returns
+    /// The `Argument` of the DEEP coin.
+    pub async fn prepare_deep_fee_coin(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        amount: f64,
+    ) -> Result<Argument> {
+        let deep_coin = self
+            .config
+            .require_deep_coin()?;
+        let owner = self.config.sender();
+
+        let coins = fetch_owned_coins(&self.client, owner, &deep_coin.type_)
+            .await
+            .with_context(|| "Failed to fetch DEEP coins")?;
+        if coins.is_empty() {
+            return Err(anyhow!("No DEEP coins owned by {} to pay {} DEEP of fees", owner, amount));
+        }
+        let required = deep_coin.to_units(amount);
+        let owned: u64 = coins.iter().map(|coin| coin.balance).sum();
+        if owned < required {
+            return Err(anyhow!(
+                "Insufficient DEEP owned by {}: need {} have {}",
+                owner,
+                format_coin_amount_trimmed(required, deep_coin),
+                format_coin_amount_trimmed(owned, deep_coin),
+            ));
+        }
+
+        let coin_refs: Vec<_> = coins.iter().map(|coin| coin.object_ref()).collect();
+        split_coin_argument(ptb, &coin_refs, required)
+            .with_context(|| "Failed to split DEEP fee coin")
+    }
+
+    /// Sign and execute the transaction block, waiting for it to be executed.
+    /// Unless `gas_coins` is given, a single SUI coin owned by the signer large enough
+    /// for the gas budget is selected as gas payment.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `signer` - The signer of the transaction, also used as the sender. Any `DeepBookSigner`
+    ///   works, including a boxed hardware or remote signer (`&Box<dyn DeepBookSigner>`).
+    /// * `gas_coins` - Optional gas coins to pay with, e.g. to keep other coins free as inputs.
+    ///
+    /// # Returns
+    /// The structured `ExecutionOutcome` of the transaction, or an error if it failed, e.g. on a
+    /// `DeepBookAbort`.
+    pub async fn execute_and_wait(
+        &self,
+        ptb: ProgrammableTransactionBuilder,
+        signer: &dyn DeepBookSigner,
+        gas_coins: Option<Vec<ObjectRef>>,
+    ) -> Result<ExecutionOutcome> {
+        let sender = signer.address();
+
+        let gas_coins = match gas_coins {
+            Some(gas_coins) => gas_coins,
+            None => {
+                let coins = fetch_owned_coins(&self.client, sender, SUI_COIN_TYPE)
+                    .await
+                    .with_context(|| "Failed to get coins for gas fee")?;
+                let coin = select_gas_coin(&coins, GAS_BUDGET as u64)
+                    .ok_or_else(|| anyhow!("No single SUI coin covers the gas budget of {}", GAS_BUDGET as u64))?;
+                vec![coin.object_ref()]
+            }
+        };
+        let gas_price = self
+            .client
+            .read_api()
+            .get_reference_gas_price()
+            .await
+            .with_context(|| "Failed to get reference gas price")?;
+
+        let tx_data = TransactionData::new_programmable(
+            sender,
+            gas_coins,
+            ptb.finish(),
+            GAS_BUDGET as u64,
+            gas_price,
+        );
+        let signature = signer
+            .sign_transaction(&tx_data)
+            .await
+            .with_context(|| "Failed to sign transaction")?;
+
+        let response = self
+            .client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                Transaction::from_data(tx_data, vec![signature]),
+                SuiTransactionBlockResponseOptions::full_content(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await
+            .with_context(|| "Failed to execute transaction block")?;
+        debug!("Transaction executed, digest: {}", response.digest);
+
+        ExecutionOutcome::from_response(&response)?.ensure_success()
+    }
+
+    /// Create and share a new BalanceManager funded with the listed deposits in one PTB, so a new
+    /// user can start trading after a single transaction. SUI is split off the gas coin; other
+    /// coins are split off the coins owned by the configured address.
+    /// The manager's id is only known once executed: recover it with `created_balance_manager_id`,
+    /// and trade in a later transaction, since the share command consumes the manager.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `deposits` - The `(coin_key, amount)` to deposit, in human units.
+    ///
+    /// # Returns
+    /// The `Argument` of the new manager.
+    pub async fn bootstrap_manager(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        deposits: Vec<(&str, f64)>,
+    ) -> Result<Argument> {
+        let coin_arguments = self.deposit_coin_arguments(ptb, &deposits).await?;
+
+        append_bootstrap_manager(ptb, &self.config.deepbook_package_id, coin_arguments)
+    }
+
+    /// Create a new BalanceManager and transfer it to `recipient` instead of sharing it, for
+    /// single-user setups that don't want a shared object. Only the owner can then use the manager,
+    /// and its id is recovered with `created_balance_manager_id` once executed.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `recipient` - The address that will own the new manager.
+    ///
+    /// # Returns
+    /// The `Argument` of the new manager.
+    pub fn create_and_transfer_balance_manager(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        recipient: SuiAddress,
+    ) -> Result<Argument> {
+        append_create_and_transfer_manager(ptb, &self.config.deepbook_package_id, recipient)
+    }
+
+    /// Deposit several coins into a balance manager in one PTB. The manager is resolved once, and
+    /// the owned coins of each type are merged once and every deposit of that type split off them.
+    /// SUI is split off the gas coin.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `deposits` - The `(coin_key, amount)` to deposit, in human units.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn deposit_multi(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        deposits: &[(&str, f64)],
+    ) -> Result<()> {
+        let manager_argument = with_timeout(
+            self.request_timeout,
+            prepare_balance_manager_argument(&self.client, &self.config, ptb, manager_key),
+        )
+            .await
+            .with_context(|| "Failed to prepare manager argument")?;
+        let coin_arguments = self.deposit_coin_arguments(ptb, deposits).await?;
+
+        append_deposits(ptb, &self.config.deepbook_package_id, manager_argument, coin_arguments)
+    }
+
+    /// Split a coin for each `(coin_key, amount)` deposit, merging the owned coins of each type once.
+    async fn deposit_coin_arguments(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        deposits: &[(&str, f64)],
+    ) -> Result<Vec<(TypeTag, Argument)>> {
+        let mut sources: HashMap<TypeTag, Argument> = HashMap::new();
+        let mut coin_arguments = Vec::with_capacity(deposits.len());
+        for &(coin_key, amount) in deposits {
+            let coin = self.config.require_coin(coin_key)?;
+            let coin_type = TypeTag::from_str(&coin.type_)
+                .with_context(|| format!("Failed to parse coin type: {}", coin.type_))?;
+
+            let source = if is_sui_coin_type(&coin_type) {
+                Argument::GasCoin
+            } else if let Some(source) = sources.get(&coin_type) {
+                *source
+            } else {
+                let coins = fetch_owned_coins(&self.client, self.config.sender(), &coin.type_)
+                    .await
+                    .with_context(|| format!("Failed to fetch coins for key: {}", coin_key))?;
+                let coin_refs: Vec<_> = coins.iter().map(|coin| coin.object_ref()).collect();
+                let source = merge_coin_arguments(ptb, &coin_refs)
+                    .with_context(|| format!("Failed to merge coins for key: {}", coin_key))?;
+                sources.insert(coin_type.clone(), source);
+                source
+            };
+            let amount_argument = ptb.pure(coin.to_units_floor(amount))
+                .with_context(|| "Failed to prepare deposit amount pure argument")?;
+            coin_arguments.push((coin_type, ptb.command(Command::SplitCoins(source, vec![amount_argument]))));
+        }
+
+        Ok(coin_arguments)
+    }
+
+    /// Get the id of the BalanceManager created by an executed transaction,
+    /// e.g. one built with `create_and_share_balance_manager`, `create_and_transfer_balance_manager`
+    /// or `bootstrap_manager`.
+    ///
+    /// # Arguments
+    /// * `response` - The transaction response, fetched with object changes.
+    ///
+    /// # Returns
+    /// The `ObjectID` of the new BalanceManager, or None if the transaction created none.
+    pub fn created_balance_manager_id(response: &SuiTransactionBlockResponse) -> Option<ObjectID> {
+        find_created_balance_manager_id(response)
+    }
+
+    /// Decode the order events emitted by the DeepBook package in an executed transaction, e.g.
+    /// to confirm what a transaction sent with `execute_and_wait` placed, filled or canceled.
+    ///
+    /// # Arguments
+    /// * `response` - The transaction response, fetched with events.
+    ///
+    /// # Returns
+    /// The order events in emission order. Empty if the configured package id is invalid.
+    pub fn parse_order_events(&self, response: &SuiTransactionBlockResponse) -> Vec<OrderEvent> {
+        match ObjectID::from_hex_literal(&self.config.deepbook_package_id) {
+            Ok(package_id) => parse_order_events(response, package_id),
+            Err(_) => {
+                warn!("Invalid DeepBook package id: {}", self.config.deepbook_package_id);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Resolve the base and quote coins of a pool from the configuration.
+    fn pool_coins(&self, pool_key: &str) -> Result<(&Coin, &Coin)> {
+        let pool = self.config.require_pool(pool_key)?;
+        let base_coin = self.config.require_coin(&pool.base_coin)?;
+        let quote_coin = self.config.require_coin(&pool.quote_coin)?;
+
+        Ok((base_coin, quote_coin))
+    }
+
+    /// Return the inspection of the transaction block, or an error upon failure.
+    /// Use this method to inspect the results of a transaction before executing it.
+    /// It does not execute the transaction.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    async fn dev_inspect_transaction_results(
+        &self,
+        ptb: ProgrammableTransactionBuilder,
+    ) -> Result<DevInspectResults> {
+        with_timeout(self.request_timeout, dev_inspect_ptb(&self.client, &self.config, ptb)).await
+    }
+
+    /// Dev-inspect a PTB as `sender`, or as the configured address if None.
+    async fn dev_inspect_with_sender(
+        &self,
+        ptb: ProgrammableTransactionBuilder,
+        sender: Option<SuiAddress>,
+    ) -> Result<DevInspectResults> {
+        self.dev_inspect_as(ptb, sender.unwrap_or_else(|| self.config.sender())).await
+    }
+
+    /// Dev-inspect a PTB as `sender` instead of the configured address, e.g. to read the
+    /// state of a balance manager owned by another address.
+    ///
+    /// # Arguments
+    /// * `ptb` - The PTB to inspect.
+    /// * `sender` - The address to inspect the PTB as.
+    ///
+    /// # Returns
+    /// The dev-inspect results.
+    pub async fn dev_inspect_as(
+        &self,
+        ptb: ProgrammableTransactionBuilder,
+        sender: SuiAddress,
+    ) -> Result<DevInspectResults> {
+        with_timeout(self.request_timeout, dev_inspect_ptb_as(&self.client, sender, ptb)).await
+    }
+}
+
+/// Parses data from the dev inspect results(Sui RPC response) and returns the deserialized data.
+/// Data type must implement serde::de::DeserializeOwned.
+fn parse_data_from_response<T: serde::de::DeserializeOwned>(response: &DevInspectResults) -> Result<T> {
+    parse_optional_data_from_response(response)?
+        .ok_or_else(|| anyhow::anyhow!("Transaction response missing return value"))
+}
+
+/// Parses data from the dev inspect results like `parse_data_from_response`, but returns None
+/// when the call returned no value, e.g. for a manager that has never traded in the pool.
+fn parse_optional_data_from_response<T: serde::de::DeserializeOwned>(response: &DevInspectResults) -> Result<Option<T>> {
+    decode_return_value(return_value_bytes(response, 0, 0))
+}
+
+/// Whether `coin_type` is SUI, the coin deposits split off the gas coin. Compares parsed types,
+/// so short and full-length addresses match and other packages' `sui::SUI` coins don't.
+fn is_sui_coin_type(coin_type: &TypeTag) -> bool {
+    TypeTag::from_str(SUI_COIN_TYPE).is_ok_and(|sui_type| &sui_type == coin_type)
+}
+
+/// Notional value in quote units of an order of the pool's min size at `price`, encoded by the pool's encoder.
+fn min_notional(
+    pool_params: &PoolBookParams,
+    price: f64,
+    base_coin: &Coin,
+    quote_coin: &Coin,
+    encoder: &dyn PriceEncoder,
+) -> f64 {
+    let input_price = encoder.encode_price(price, base_coin, quote_coin, RoundingMode::Nearest, true);
+
+    notional_value(input_price, pool_params.min_size, quote_coin)
+}
+
+/// Converts HTTP headers to the name-value pairs taken by `SuiClientBuilder::custom_headers`.
+/// Of a header given several times, only the last value is kept.
+fn rpc_header_strings(headers: &HeaderMap) -> Result<HashMap<String, String>> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .to_str()
+                .with_context(|| format!("RPC header is not valid text: {}", name))?;
+
+            Ok((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Decodes the `balance_manager::balance` return values of several managers, in command order.
+fn manager_balances(manager_keys: Vec<String>, balances: Vec<Option<&[u8]>>, coin: &Coin) -> Result<HashMap<String, f64>> {
+    manager_keys
+        .into_iter()
+        .zip(balances)
+        .map(|(manager_key, bytes)| {
+            let balance: u64 = decode_return_value(bytes)?
+                .ok_or_else(|| anyhow!("Transaction response missing balance of manager: {}", manager_key))?;
+
+            Ok((manager_key, coin.to_human(balance)))
+        })
+        .collect()
+}
+
+/// Reads the open orders of a manager in `pool_keys` with `inspect`, which dev-inspects one
+/// `account_open_orders` command per given pool and returns either the return values in command
+/// order or the execution error. A pool whose command aborts has no open orders and the other
+/// pools are inspected again, so a batch costs one dev-inspect plus one per aborting pool.
+async fn batched_open_orders<'a, F, Fut>(pool_keys: &[&'a str], mut inspect: F) -> Result<HashMap<String, Vec<u128>>>
+where
+    F: FnMut(Vec<&'a str>) -> Fut,
+    Fut: Future<Output = Result<Result<Vec<Option<Vec<u8>>>, String>>>,
+{
+    let mut open_orders = HashMap::new();
+    let mut remaining = pool_keys.to_vec();
+    while !remaining.is_empty() {
+        match inspect(remaining.clone()).await? {
+            Ok(values) => {
+                for (pool_key, bytes) in remaining.iter().zip(values) {
+                    let order_ids: Option<VecSet<u128>> = decode_return_value(bytes.as_deref())
+                        .with_context(|| format!("Failed to parse order IDs of pool: {}", pool_key))?;
+                    open_orders.insert(pool_key.to_string(), order_ids.map(|ids| ids.contents).unwrap_or_default());
+                }
+                break;
+            }
+            Err(error) => {
+                let command = aborted_command(&error)
+                    .filter(|command| *command < remaining.len())
+                    .ok_or_else(|| execution_error(&format!("account_open_orders failed: {}", error)))?;
+                open_orders.insert(remaining.remove(command).to_string(), vec![]);
+            }
+        }
+    }
+
+    Ok(open_orders)
+}
+
+/// Runs `query` immediately and then once per `period`, yielding each result.
+/// Ticks missed by a slow query are delayed rather than bursted. A zero `period` yields a
+/// single error instead of polling.
+fn poll_every<T, F, Fut>(period: Duration, query: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    if period.is_zero() {
+        return futures::stream::once(async { Err(anyhow!("Polling interval must be non-zero")) }).left_stream();
+    }
+
+    // The interval is created on first poll, as it needs the runtime's timer
+    futures::stream::unfold((None::<Interval>, query), move |(ticker, mut query)| async move {
+        let mut ticker = ticker.unwrap_or_else(|| {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker
+        });
+        ticker.tick().await;
+        let value = query().await;
+
+        Some((value, (Some(ticker), query)))
+    })
+        .right_stream()
+}
+
+/// Decodes a DEEP amount returned as a `u64` in DEEP base units.
+fn decode_deep_amount(bytes: Option<&[u8]>, deep_scalar: u64) -> Result<f64> {
+    let amount: u64 = decode_return_value(bytes)?
+        .ok_or_else(|| anyhow!("Transaction response missing return value"))?;
+
+    Ok(amount as f64 / deep_scalar as f64)
+}
+
+/// Finds the protocol order id of the open order placed with `client_order_id`.
+fn find_order_id_by_client_order_id(orders: &[Order], client_order_id: u64) -> Result<u128> {
+    orders
+        .iter()
+        .find(|order| order.client_order_id == client_order_id)
+        .map(|order| order.order_id)
+        .ok_or_else(|| anyhow!("No open order found for client_order_id: {}", client_order_id))
+}
+
+/// Caps a reduce-only order at the net resting exposure of `orders` on the opposite side,
+/// in on-chain base units. Errors if the order can't reduce the exposure at all.
+fn reduce_only_quantity(orders: &[Order], input_quantity: u64, is_bid: bool) -> Result<u64> {
+    let (resting_bids, resting_asks) = orders.iter().fold((0u64, 0u64), |(bids, asks), order| {
+        let remaining = order.quantity.saturating_sub(order.filled_quantity);
+        if order_id_is_bid(order.order_id) {
+            (bids + remaining, asks)
+        } else {
+            (bids, asks + remaining)
+        }
+    });
+    let reducible = if is_bid {
+        resting_asks.saturating_sub(resting_bids)
+    } else {
+        resting_bids.saturating_sub(resting_asks)
+    };
+    if reducible == 0 {
+        return Err(anyhow!(
+            "Reduce-only {} would increase net exposure: {} resting on bids, {} on asks",
+            if is_bid { "bid" } else { "ask" },
+            resting_bids,
+            resting_asks,
+        ));
+    }
+
+    Ok(input_quantity.min(reducible))
+}
+
+/// Converts an order deadline into the protocol's millisecond expiration timestamp,
+/// refusing a deadline that isn't after `now`.
+fn deadline_expiration(deadline: SystemTime, now: SystemTime) -> Result<u64> {
+    if deadline <= now {
+        return Err(anyhow!("Order deadline {:?} is not in the future", deadline));
+    }
+
+    let expiration = deadline
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| "Order deadline is before the Unix epoch")?
+        .as_millis();
+
+    u64::try_from(expiration).with_context(|| format!("Order deadline {:?} is out of range", deadline))
+}
+
+/// Ids of the orders expiring strictly before `cutoff_ms`, in the order given.
+fn order_ids_expiring_before(orders: &[Order], cutoff_ms: u64) -> Vec<u128> {
+    orders
+        .iter()
+        .filter(|order| order.expire_timestamp < cutoff_ms)
+        .map(|order| order.order_id)
+        .collect()
+}
+
+/// The `check_manager_balance` output for a raw balance, formatted to the coin's own decimals.
+fn manager_balance_json(raw: u64, coin: &Coin) -> serde_json::Value {
+    json!({
+        "coin_type": coin.type_,
+        "balance": coin.to_human(raw),
+        "balance_formatted": format_coin_amount(raw, coin),
+    })
+}
+
+/// Decodes the open orders returned by the last command of a simulated placement.
+/// `inspect_error` is the dev-inspect execution error, if the placement aborted.
+fn simulated_open_orders(inspect_error: Option<&str>, bytes: Option<&[u8]>) -> Result<Vec<Order>> {
+    if let Some(error) = inspect_error {
+        return Err(execution_error(&format!("Simulated order placement failed: {}", error)));
+    }
+    let orders: Option<Vec<Order>> = decode_return_value(bytes)
+        .with_context(|| "Failed to parse simulated open orders")?;
+
+    Ok(orders.unwrap_or_default())
+}
+
+/// Maps the outcome of a `get_order` dev-inspect to whether the order exists.
+/// `inspect_error` is the dev-inspect execution error, or the RPC error itself.
+fn order_exists_from_inspect(inspect_error: Result<Option<String>>) -> Result<bool> {
+    match inspect_error.with_context(|| "Failed to inspect transaction results")? {
+        None => Ok(true),
+        Some(error) if is_order_not_found_abort(&error) => Ok(false),
+        Some(error) => Err(execution_error(&format!("get_order failed: {}", error))),
+    }
+}
+
+/// Checks the execution error of a `get_order` dev-inspect. An order that is not on the book
+/// is reported as not found; any other abort is returned as an execution error.
+fn check_get_order_inspect(inspect_error: Option<&str>, pool_key: &str, order_id: u128) -> Result<()> {
+    match inspect_error {
+        None => Ok(()),
+        Some(error) if is_order_not_found_abort(error) => {
+            Err(anyhow!("Order {} not found in pool {}", order_id, pool_key))
+        }
+        Some(error) => Err(execution_error(&format!("get_order failed: {}", error))),
+    }
+}
+
+/// Lists why the configured package and registry ids don't point to a package and a
+/// `registry::Registry`, given the types of the fetched objects (None if an object doesn't exist).
+/// The registry type keeps the original package id across upgrades, so only its module and name are checked.
+fn package_id_problems(config: &DeepBookConfig, package_type: Option<&str>, registry_type: Option<&str>) -> Vec<String> {
+    let mut problems = vec![];
+    match package_type {
+        Some("package") => {}
+        Some(other) => problems.push(format!("package id {} is a {}, not a package", config.deepbook_package_id, other)),
+        None => problems.push(format!("package id {} does not exist", config.deepbook_package_id)),
+    }
+    match registry_type {
+        Some(registry_type) if registry_type.ends_with("::registry::Registry") => {}
+        Some(other) => problems.push(format!("registry id {} is a {}, not a registry", config.registry_id, other)),
+        None => problems.push(format!("registry id {} does not exist", config.registry_id)),
+    }
+
+    problems
+}
+
+/// Whether a dev-inspect error is the abort raised when an order id is not on the book.
+/// Orders live in a `big_vector`, which aborts from `borrow`/`slice` lookups on a missing key.
+fn is_order_not_found_abort(error: &str) -> bool {
+    error.contains("MoveAbort") && error.contains("Identifier(\"big_vector\")")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::config::DEEP_SCALAR;
+    use crate::utils::constants::TESTNET_COINS;
+    use crate::utils::error::DeepBookError;
+    use crate::transactions::deepbook::DefaultPriceEncoder;
+    use crate::utils::fixtures::{self, order};
+
+    #[test]
+    fn test_find_order_id_by_client_order_id() {
+        let orders = vec![order(10, 1), order(20, 2)];
+
+        assert_eq!(find_order_id_by_client_order_id(&orders, 2).unwrap(), 20);
+    }
+
+    #[test]
+    fn test_find_order_id_by_client_order_id_no_match() {
+        let orders = vec![order(10, 1), order(20, 2)];
+
+        let err = find_order_id_by_client_order_id(&orders, 3).unwrap_err();
+        assert!(err.to_string().contains("No open order found for client_order_id: 3"));
+    }
+
+    #[test]
+    fn test_reduce_only_quantity_is_clamped() {
+        let resting = |side: u128, sequence: u128, quantity: u64, filled_quantity: u64| Order {
+            quantity,
+            filled_quantity,
+            ..order(side << 127 | 2_500_000 << 64 | sequence, 0)
+        };
+        // 3 base resting on bids, 10 - 2 base on asks: net short 5
+        let orders = vec![resting(0, 1, 3_000_000, 0), resting(1, 2, 10_000_000, 2_000_000)];
+
+        assert_eq!(reduce_only_quantity(&orders, 8_000_000, true).unwrap(), 5_000_000);
+        assert_eq!(reduce_only_quantity(&orders, 1_000_000, true).unwrap(), 1_000_000);
+        let err = reduce_only_quantity(&orders, 1_000_000, false).unwrap_err().to_string();
+        assert!(err.contains("would increase net exposure"));
+        assert!(reduce_only_quantity(&[], 1_000_000, true).is_err());
+    }
+
+    #[test]
+    fn test_order_ids_expiring_before() {
+        let cutoff = 1_700_000_000_000;
+        let expiring = |order_id: u128, expire_timestamp: u64| Order { expire_timestamp, ..order(order_id, 0) };
+        let orders = vec![
+            expiring(1, cutoff - 60_000),
+            expiring(2, cutoff),
+            expiring(3, u64::MAX),
+            expiring(4, cutoff - 1),
+        ];
+
+        assert_eq!(order_ids_expiring_before(&orders, cutoff), vec![1, 4]);
+        assert!(order_ids_expiring_before(&orders, 0).is_empty());
+    }
+
+    #[test]
+    fn test_deadline_expiration_must_be_in_the_future() {
+        let now = UNIX_EPOCH + Duration::from_millis(1_700_000_000_000);
+
+        assert_eq!(deadline_expiration(now + Duration::from_secs(3_600), now).unwrap(), 1_700_003_600_000);
+        let err = deadline_expiration(now - Duration::from_secs(1), now).unwrap_err().to_string();
+        assert!(err.contains("not in the future"));
+        assert!(deadline_expiration(now, now).is_err());
+    }
+
+    #[test]
+    fn test_manager_balance_uses_coin_decimals() {
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+
+        let usdc_balance = manager_balance_json(12_500_000, usdc);
+        assert_eq!(usdc_balance["balance_formatted"], "12.500000");
+        assert_eq!(usdc_balance["balance"], 12.5);
+        assert_eq!(manager_balance_json(12_500_000_000, sui)["balance_formatted"], "12.500000000");
+    }
+
+    #[test]
+    fn test_manager_balance_of_inline_coin() {
+        // A token with 8 decimals that is not in the configuration
+        let coin_type = "0x5d4b302506645c37ff133b98c4b50a5ae14841659738d6d733d59d0d217a93bf::token::TOKEN";
+        let coin = Coin::from_type(coin_type, 100_000_000).unwrap();
+        assert_eq!(coin.address, "0x5d4b302506645c37ff133b98c4b50a5ae14841659738d6d733d59d0d217a93bf");
+        assert!(coin.validate().is_ok());
+
+        let balance = manager_balance_json(123_450_000, &coin);
+        assert_eq!(balance["coin_type"], coin_type);
+        assert_eq!(balance["balance"], 1.2345);
+        assert_eq!(balance["balance_formatted"], "1.23450000");
+
+        assert!(Coin::from_type("u64", 1).is_err());
+    }
+
+    #[test]
+    fn test_simulated_open_orders_of_empty_and_aborted_placements() {
+        // A manager with no orders left after a fully matched placement
+        assert!(simulated_open_orders(None, None).unwrap().is_empty());
+        assert!(simulated_open_orders(None, Some(&[][..])).unwrap().is_empty());
+
+        // An aborted placement fails even though the open orders read returned a value
+        let no_orders = [0u8];
+        let err = simulated_open_orders(Some("MoveAbort(..., 3) in command 2"), Some(&no_orders[..])).unwrap_err();
+        assert!(err.to_string().contains("in command 2"));
+        // A vector length prefix of 2 with no orders following
+        let err = simulated_open_orders(None, Some(&[2u8][..])).unwrap_err();
+        assert!(err.to_string().contains("Failed to parse simulated open orders"));
+    }
+
+    #[test]
+    fn test_order_exists_from_inspect() {
+        let not_found = "MoveAbort(MoveLocation { module: ModuleId { address: 2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809, name: Identifier(\"big_vector\") }, function: 11, instruction: 22, function_name: Some(\"slice_following\") }, 2) in command 0";
+        let other_abort = "MoveAbort(MoveLocation { module: ModuleId { address: 2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809, name: Identifier(\"pool\") }, function: 3, instruction: 10, function_name: Some(\"load_inner\") }, 1) in command 0";
+
+        assert!(order_exists_from_inspect(Ok(None)).unwrap());
+        assert!(!order_exists_from_inspect(Ok(Some(not_found.to_string()))).unwrap());
+        assert!(order_exists_from_inspect(Ok(Some(other_abort.to_string()))).is_err());
+    }
+
+    #[test]
+    fn test_get_order_reports_only_missing_orders_as_not_found() {
+        let not_found = "MoveAbort(MoveLocation { module: ModuleId { address: 2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809, name: Identifier(\"big_vector\") }, function: 11, instruction: 22, function_name: Some(\"slice_following\") }, 2) in command 0";
+        let other_abort = "MoveAbort(MoveLocation { module: ModuleId { address: 2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809, name: Identifier(\"pool\") }, function: 3, instruction: 10, function_name: Some(\"load_inner\") }, 1) in command 0";
+
+        assert!(check_get_order_inspect(None, "SUI_DBUSDC", 7).is_ok());
+        let err = check_get_order_inspect(Some(not_found), "SUI_DBUSDC", 7).unwrap_err();
+        assert_eq!(err.to_string(), "Order 7 not found in pool SUI_DBUSDC");
+        let err = check_get_order_inspect(Some(other_abort), "SUI_DBUSDC", 7).unwrap_err();
+        assert!(!format!("{:#}", err).contains("not found"));
+        assert!(format!("{:#}", err).contains("load_inner"));
+    }
+
+    #[test]
+    fn test_order_exists_propagates_rpc_errors() {
+        let err = order_exists_from_inspect(Err(anyhow!("connection refused"))).unwrap_err();
+
+        assert!(format!("{:#}", err).contains("connection refused"));
+    }
+
+    #[test]
+    fn test_decode_pool_creation_fee() {
+        // BCS encoding of the u64 500_000_000, i.e. 500 DEEP
+        let bytes = 500_000_000u64.to_le_bytes();
+
+        assert_eq!(decode_deep_amount(Some(&bytes[..]), DEEP_SCALAR).unwrap(), 500.0);
+        assert!(decode_deep_amount(None, DEEP_SCALAR).is_err());
+    }
+
+    #[test]
+    fn test_min_notional() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        // min size of 1 SUI
+        let pool_params = PoolBookParams { tick_size: 1_000, lot_size: 100_000_000, min_size: 1_000_000_000 };
+
+        assert_eq!(min_notional(&pool_params, 2.5, sui, usdc, &DefaultPriceEncoder), 2.5);
+        assert_eq!(min_notional(&pool_params, 0.8, sui, usdc, &DefaultPriceEncoder), 0.8);
+    }
+
+    #[test]
+    fn test_is_sui_coin_type() {
+        let coin_type = |coin_type: &str| TypeTag::from_str(coin_type).unwrap();
+
+        assert!(is_sui_coin_type(&coin_type("0x2::sui::SUI")));
+        assert!(is_sui_coin_type(&coin_type(&TESTNET_COINS.get("SUI").unwrap().type_)));
+        assert!(!is_sui_coin_type(&coin_type("0xabc::sui::SUI")));
+        assert!(!is_sui_coin_type(&coin_type(&TESTNET_COINS.get("DEEP").unwrap().type_)));
+    }
+
+    /// Encodes every price at twice its value, to tell its results from `DefaultPriceEncoder`'s.
+    struct DoublePriceEncoder;
+
+    impl PriceEncoder for DoublePriceEncoder {
+        fn encode_price(&self, price: f64, base: &Coin, quote: &Coin, rounding: RoundingMode, is_bid: bool) -> u64 {
+            DefaultPriceEncoder.encode_price(price * 2.0, base, quote, rounding, is_bid)
+        }
+
+        fn encode_quantity(&self, quantity: f64, base_coin: &Coin, rounding_mode: RoundingMode) -> u64 {
+            DefaultPriceEncoder.encode_quantity(quantity, base_coin, rounding_mode)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_min_order_notional_uses_pool_price_encoder() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let client = DeepBookClient::new(fixtures::offline_sui_client().await, Arc::new(config))
+            .with_price_encoder("SUI_DBUSDC", DoublePriceEncoder);
+        let pool_params = PoolBookParams { tick_size: 1_000, lot_size: 100_000_000, min_size: 1_000_000_000 };
+        client.deep_book.cache_pool_params(HashMap::from([("SUI_DBUSDC".to_string(), pool_params)]));
+
+        // A min size of 1 SUI at 2.5, priced by the pool's encoder at 5.0
+        assert_eq!(client.min_order_notional("SUI_DBUSDC", 2.5).await.unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_manager_balances_decode_into_slots() {
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        let first = bcs::to_bytes(&12_500_000u64).unwrap();
+        let second = bcs::to_bytes(&3_000_000u64).unwrap();
+
+        let balances = manager_balances(
+            vec!["MANAGER_1".to_string(), "MANAGER_2".to_string()],
+            vec![Some(first.as_slice()), Some(second.as_slice())],
+            usdc,
+        ).unwrap();
+
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances["MANAGER_1"], 12.5);
+        assert_eq!(balances["MANAGER_2"], 3.0);
+        assert!(manager_balances(vec!["MANAGER_1".to_string()], vec![None], usdc).is_err());
+    }
+
+    #[test]
+    fn test_client_order_ids_strictly_increase() {
+        let ids = ClientOrderIds::new(1_000);
+        let issued: Vec<u64> = (0..5).map(|_| ids.next()).collect();
+        assert_eq!(issued, vec![1_000, 1_001, 1_002, 1_003, 1_004]);
+
+        let ids = ClientOrderIds::from_clock();
+        let first = ids.next();
+        let mut previous = first;
+        let unique: std::collections::HashSet<u64> = (0..1_000)
+            .map(|_| {
+                let id = ids.next();
+                assert!(id > previous);
+                previous = id;
+                id
+            })
+            .collect();
+        assert_eq!(unique.len(), 1_000);
+        assert!(first > 0);
+    }
+
+    #[test]
+    fn test_unknown_package_id_is_flagged() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let registry_type = "0x2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809::registry::Registry";
+
+        assert!(package_id_problems(&config, Some("package"), Some(registry_type)).is_empty());
+
+        let problems = package_id_problems(&config, None, Some(registry_type));
+        assert_eq!(problems, vec![format!("package id {} does not exist", config.deepbook_package_id)]);
+
+        let problems = package_id_problems(&config, Some("0x2::coin::Coin<0x2::sui::SUI>"), None);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("not a package"));
+        assert!(problems[1].contains("does not exist"));
+    }
+
+    #[tokio::test]
+    async fn test_poll_every_ticks_immediately_then_per_interval() {
+        use std::sync::atomic::AtomicUsize;
+
+        let query_count = AtomicUsize::new(0);
+        let queries = &query_count;
+        let period = Duration::from_millis(100);
+        let started = tokio::time::Instant::now();
+
+        let stream = poll_every(period, move || async move {
+            let query = queries.fetch_add(1, Ordering::SeqCst);
+            if query == 1 {
+                Err(anyhow!("RPC unavailable"))
+            } else {
+                Ok(query as f64 * 10.0)
+            }
+        });
+        futures::pin_mut!(stream);
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), 0.0);
+        assert!(started.elapsed() < period);
+        // A failed query is yielded and polling carries on
+        assert!(stream.next().await.unwrap().is_err());
+        assert_eq!(stream.next().await.unwrap().unwrap(), 20.0);
+        assert!(started.elapsed() >= period * 2);
+        assert_eq!(query_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_poll_every_rejects_zero_period() {
+        let stream = poll_every(Duration::ZERO, || async { anyhow::Ok(1.0) });
+        futures::pin_mut!(stream);
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "Polling interval must be non-zero");
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batched_open_orders_maps_aborting_pool_to_empty() {
+        use std::sync::Mutex;
+
+        let inspected: Mutex<Vec<Vec<&str>>> = Mutex::new(vec![]);
+        let inspected_ref = &inspected;
+        let inspect = |never_traded: &'static str| move |pool_keys: Vec<&'static str>| async move {
+            inspected_ref.lock().unwrap().push(pool_keys.clone());
+            let values: Result<Vec<Option<Vec<u8>>>, String> =
+                match pool_keys.iter().position(|pool_key| *pool_key == never_traded) {
+                    Some(command) => Err(format!("MoveAbort(MoveLocation {{ .. }}, 2) in command {}", command)),
+                    None => Ok(pool_keys
+                        .iter()
+                        .map(|pool_key| Some(fixtures::vec_set_bytes(&[pool_key.len() as u128])))
+                        .collect()),
+                };
+            anyhow::Ok(values)
+        };
+        let pool_keys = ["DEEP_SUI", "SUI_DBUSDC", "DEEP_DBUSDC"];
+
+        // Every pool read in a single dev-inspect
+        let open_orders = batched_open_orders(&pool_keys, inspect("NONE")).await.unwrap();
+        assert_eq!(open_orders["SUI_DBUSDC"], vec![10]);
+        assert_eq!(open_orders.len(), 3);
+        assert_eq!(inspected.lock().unwrap().len(), 1);
+
+        // The aborting pool has no open orders and the others are inspected again
+        inspected.lock().unwrap().clear();
+        let open_orders = batched_open_orders(&pool_keys, inspect("SUI_DBUSDC")).await.unwrap();
+        assert_eq!(open_orders["DEEP_SUI"], vec![8]);
+        assert_eq!(open_orders["SUI_DBUSDC"], Vec::<u128>::new());
+        assert_eq!(open_orders["DEEP_DBUSDC"], vec![11]);
+        assert_eq!(*inspected.lock().unwrap(), vec![pool_keys.to_vec(), vec!["DEEP_SUI", "DEEP_DBUSDC"]]);
+
+        let err = batched_open_orders(&pool_keys, |_| async { anyhow::Ok(Err("InsufficientGas".to_string())) })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("account_open_orders failed: InsufficientGas"));
+    }
+
+    #[test]
+    fn test_rpc_header_strings() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", "secret".parse().unwrap());
+        let expected = HashMap::from([("x-api-key".to_string(), "secret".to_string())]);
+        assert_eq!(rpc_header_strings(&headers).unwrap(), expected);
+
+        headers.insert("authorization", reqwest::header::HeaderValue::from_bytes(b"\xff").unwrap());
+        let err = rpc_header_strings(&headers).unwrap_err();
+        assert!(err.to_string().contains("RPC header is not valid text: authorization"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_keys_are_typed_errors_at_call_sites() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let client = DeepBookClient::new(fixtures::offline_sui_client().await, Arc::new(config));
+        let not_found = |err: anyhow::Error| err.root_cause().downcast_ref::<DeepBookError>().cloned();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        let err = client.deep_book
+            .place_limit_order(&mut ptb, "NOPE", "MANAGER_1", "1", 2.5, 1.0, true, None, None, None, None)
+            .await.unwrap_err();
+        assert_eq!(not_found(err), Some(DeepBookError::PoolNotFound("NOPE".to_string())));
+        let err = client.deep_book
+            .place_limit_order(&mut ptb, "SUI_DBUSDC", "NOPE", "1", 2.5, 1.0, true, None, None, None, None)
+            .await.unwrap_err();
+        assert_eq!(not_found(err), Some(DeepBookError::ManagerNotFound("NOPE".to_string())));
+
+        let err = client.balance_manager
+            .deposit_into_manager(&mut ptb, "MANAGER_1", "NOPE", 1.0)
+            .await.unwrap_err();
+        assert_eq!(not_found(err), Some(DeepBookError::CoinNotFound("NOPE".to_string())));
+        let err = client.balance_manager
+            .deposit_into_manager(&mut ptb, "NOPE", "SUI", 1.0)
+            .await.unwrap_err();
+        assert_eq!(not_found(err), Some(DeepBookError::ManagerNotFound("NOPE".to_string())));
+
+        let err = client.check_manager_balance("MANAGER_1", "NOPE").await.unwrap_err();
+        assert_eq!(not_found(err), Some(DeepBookError::CoinNotFound("NOPE".to_string())));
+        let err = client.check_manager_balance("NOPE", "SUI").await.unwrap_err();
+        assert_eq!(not_found(err), Some(DeepBookError::ManagerNotFound("NOPE".to_string())));
+
+        let err = client.mint_and_transfer_trade_cap(&mut ptb, "NOPE", SuiAddress::ZERO).await.unwrap_err();
+        assert_eq!(not_found(err), Some(DeepBookError::ManagerNotFound("NOPE".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_connect_attaches_rpc_headers() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A full node that records the first request it receives and fails it
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let rpc_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            socket.write_all(b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+            String::from_utf8_lossy(&request).to_lowercase()
+        });
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", "secret".parse().unwrap());
+        let config = DeepBookConfig::new("testnet", "0x0".to_string(), None, None, None, None).unwrap();
+        let connected = DeepBookClient::builder(&rpc_url, config)
+            .with_rpc_headers(headers)
+            .with_request_timeout(Duration::from_secs(5))
+            .connect()
+            .await;
+
+        assert!(connected.is_err());
+        assert!(server.await.unwrap().contains("x-api-key: secret"));
+    }
 }
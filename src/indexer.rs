@@ -0,0 +1,243 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+use std::collections::HashMap;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use crate::utils::types::{json_number, BookLevel, OrderBook, Ticker, Trade};
+
+pub const MAINNET_INDEXER_URL: &str = "https://deepbook-indexer.mainnet.mystenlabs.com";
+pub const TESTNET_INDEXER_URL: &str = "https://deepbook-indexer.testnet.mystenlabs.com";
+
+/// Client for the DeepBook indexer REST API, an alternative to dev-inspecting a full node
+/// for order books, trades and volumes. The indexer lags the chain by a few seconds.
+/// Pools are identified by their indexer names, which are the default pool keys.
+#[derive(Clone, Debug)]
+pub struct IndexerClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl IndexerClient {
+    /// Creates a new `IndexerClient` for an indexer deployment.
+    ///
+    /// # Arguments
+    /// * `base_url` - The indexer URL, e.g. `MAINNET_INDEXER_URL`.
+    pub fn new(base_url: &str) -> Self {
+        IndexerClient {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Creates a new `IndexerClient` for the public indexer of an environment.
+    ///
+    /// # Arguments
+    /// * `env` - The environment (`mainnet` or `testnet`).
+    pub fn for_env(env: &str) -> Self {
+        match env {
+            "mainnet" => IndexerClient::new(MAINNET_INDEXER_URL),
+            _ => IndexerClient::new(TESTNET_INDEXER_URL),
+        }
+    }
+
+    /// Get the level-2 order book of a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The indexer name of the pool, e.g. `SUI_USDC`.
+    ///
+    /// # Returns
+    /// The order book, in the same form as `DeepBookClient::order_book`.
+    pub async fn order_book(&self, pool_key: &str) -> Result<OrderBook> {
+        let response = self.get(&format!("orderbook/{}?level=2", pool_key)).await?;
+
+        order_book_from_json(&response)
+            .with_context(|| format!("Failed to decode order book of pool: {}", pool_key))
+    }
+
+    /// Get the last price and 24h volumes of every pool.
+    ///
+    /// # Returns
+    /// The tickers by pool name.
+    pub async fn ticker(&self) -> Result<HashMap<String, Ticker>> {
+        let response = self.get("ticker").await?;
+
+        tickers_from_json(&response).with_context(|| "Failed to decode tickers")
+    }
+
+    /// Get the most recent trades of a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The indexer name of the pool, e.g. `SUI_USDC`.
+    ///
+    /// # Returns
+    /// The trades, most recent first.
+    pub async fn trades(&self, pool_key: &str) -> Result<Vec<Trade>> {
+        let response = self.get(&format!("trades/{}", pool_key)).await?;
+
+        trades_from_json(&response)
+            .with_context(|| format!("Failed to decode trades of pool: {}", pool_key))
+    }
+
+    async fn get(&self, path: &str) -> Result<serde_json::Value> {
+        let url = format!("{}/{}", self.base_url, path);
+        debug!("Querying DeepBook indexer: {}", url);
+
+        self.http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to query indexer: {}", url))?
+            .error_for_status()
+            .with_context(|| format!("Indexer returned an error for: {}", url))?
+            .json()
+            .await
+            .with_context(|| format!("Failed to read indexer response: {}", url))
+    }
+}
+
+/// Decodes an `orderbook` response, whose levels are `[price, quantity]` string pairs.
+pub(crate) fn order_book_from_json(response: &serde_json::Value) -> Result<OrderBook> {
+    let levels = |side: &str| -> Result<Vec<BookLevel>> {
+        response
+            .get(side)
+            .and_then(|levels| levels.as_array())
+            .ok_or_else(|| anyhow!("Order book is missing {}", side))?
+            .iter()
+            .map(|level| match level.as_array().map(Vec::as_slice) {
+                Some([price, quantity]) => Ok(BookLevel {
+                    price: json_number(price).ok_or_else(|| anyhow!("Invalid price: {}", price))?,
+                    quantity: json_number(quantity).ok_or_else(|| anyhow!("Invalid quantity: {}", quantity))?,
+                }),
+                _ => Err(anyhow!("Invalid order book level: {}", level)),
+            })
+            .collect()
+    };
+
+    Ok(OrderBook { bids: levels("bids")?, asks: levels("asks")? })
+}
+
+/// Decodes a `ticker` response, an object of tickers by pool name.
+pub(crate) fn tickers_from_json(response: &serde_json::Value) -> Result<HashMap<String, Ticker>> {
+    response
+        .as_object()
+        .ok_or_else(|| anyhow!("Ticker response is not an object"))?
+        .iter()
+        .map(|(pool_name, ticker)| {
+            let field = |name: &str| ticker
+                .get(name)
+                .and_then(json_number::<f64>)
+                .ok_or_else(|| anyhow!("Ticker of {} is missing {}", pool_name, name));
+            let ticker = Ticker {
+                last_price: field("last_price")?,
+                base_volume: field("base_volume")?,
+                quote_volume: field("quote_volume")?,
+                is_frozen: field("isFrozen")? != 0.0,
+            };
+            Ok((pool_name.clone(), ticker))
+        })
+        .collect()
+}
+
+/// Decodes a `trades` response, an array of fills in human units.
+pub(crate) fn trades_from_json(response: &serde_json::Value) -> Result<Vec<Trade>> {
+    response
+        .as_array()
+        .ok_or_else(|| anyhow!("Trades response is not an array"))?
+        .iter()
+        .map(|trade| {
+            let number = |name: &str| trade
+                .get(name)
+                .and_then(json_number::<f64>)
+                .ok_or_else(|| anyhow!("Trade is missing {}", name));
+            Ok(Trade {
+                trade_id: trade
+                    .get("trade_id")
+                    .and_then(|trade_id| trade_id.as_str())
+                    .ok_or_else(|| anyhow!("Trade is missing trade_id"))?
+                    .to_string(),
+                price: number("price")?,
+                base_volume: number("base_volume")?,
+                quote_volume: number("quote_volume")?,
+                taker_is_bid: match trade.get("type").and_then(|side| side.as_str()) {
+                    Some("buy") => true,
+                    Some("sell") => false,
+                    other => return Err(anyhow!("Invalid trade type: {:?}", other)),
+                },
+                timestamp: trade
+                    .get("timestamp")
+                    .and_then(json_number::<u64>)
+                    .ok_or_else(|| anyhow!("Trade is missing timestamp"))?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_order_book() {
+        let response = serde_json::json!({
+            "timestamp": "1733202460637",
+            "bids": [["3.715", "2.7"], ["3.713", "1"]],
+            "asks": [["3.72", "10.5"]]
+        });
+
+        let book = order_book_from_json(&response).unwrap();
+        assert_eq!(book.bids, vec![
+            BookLevel { price: 3.715, quantity: 2.7 },
+            BookLevel { price: 3.713, quantity: 1.0 },
+        ]);
+        assert_eq!(book.asks, vec![BookLevel { price: 3.72, quantity: 10.5 }]);
+        assert!(order_book_from_json(&serde_json::json!({ "bids": [["3.715"]], "asks": [] })).is_err());
+    }
+
+    #[test]
+    fn test_decode_tickers_and_trades() {
+        let tickers = serde_json::json!({
+            "SUI_USDC": { "last_price": 3.7155, "base_volume": 10_230_481.4, "quote_volume": 37_940_214.93, "isFrozen": 0 },
+            "DEEP_SUI": { "last_price": 0.0511, "base_volume": 0.0, "quote_volume": 0.0, "isFrozen": 1 }
+        });
+
+        let tickers = tickers_from_json(&tickers).unwrap();
+        assert_eq!(tickers["SUI_USDC"].last_price, 3.7155);
+        assert_eq!(tickers["SUI_USDC"].quote_volume, 37_940_214.93);
+        assert!(!tickers["SUI_USDC"].is_frozen);
+        assert!(tickers["DEEP_SUI"].is_frozen);
+
+        let trades = serde_json::json!([
+            {
+                "trade_id": "136321457151457660152049680",
+                "maker_order_id": "68160737799100866923792791",
+                "taker_order_id": "170141183460537392451039660509112362617",
+                "price": 3.7155,
+                "base_volume": 405.0,
+                "quote_volume": 1504.7775,
+                "timestamp": 1738704071994u64,
+                "type": "sell",
+                "taker_fee_is_deep": true
+            },
+            {
+                "trade_id": "136321457151457660152049679",
+                "price": 3.716,
+                "base_volume": 12.5,
+                "quote_volume": 46.45,
+                "timestamp": 1738704069112u64,
+                "type": "buy"
+            }
+        ]);
+
+        let trades = trades_from_json(&trades).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].trade_id, "136321457151457660152049680");
+        assert_eq!(trades[0].price, 3.7155);
+        assert_eq!(trades[0].quote_volume, 1504.7775);
+        assert!(!trades[0].taker_is_bid);
+        assert!(trades[1].taker_is_bid);
+        assert_eq!(trades[1].timestamp, 1_738_704_069_112);
+    }
+}
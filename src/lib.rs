@@ -4,15 +4,22 @@
 // This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
 
 pub mod client;
+pub mod indexer;
+pub mod prelude;
 pub mod utils;
 mod transactions;
 
-pub use client::DeepBookClient;
+pub use client::{DeepBookClient, DeepBookClientBuilder};
+pub use indexer::IndexerClient;
 pub use transactions::{
     balance_manager::BalanceManagerContract,
-    deepbook::DeepBookContract,
+    deepbook::{
+        DeepBookContract, DefaultPriceEncoder, FeeMode, OrderSpec, OrderType, PlaceOrderOptions, PriceEncoder,
+        RoundingMode, SelfMatchingOptions, StablePriceEncoder,
+    },
     deepbook_admin::DeepBookAdminContract,
     flash_loan::FlashLoanContract,
     governance::GovernanceContract,
 };
 pub use utils::config::DeepBookConfig;
+pub use utils::error::{DeepBookAbort, DeepBookError};
@@ -0,0 +1,19 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+//! The commonly used types of the SDK, for a single `use deepbook::prelude::*;`.
+
+pub use crate::client::DeepBookClient;
+pub use crate::indexer::IndexerClient;
+pub use crate::transactions::deepbook::{
+    FeeMode, OrderSpec, OrderType, PlaceOrderOptions, PriceEncoder, RoundingMode, SelfMatchingOptions,
+};
+pub use crate::utils::config::DeepBookConfig;
+pub use crate::utils::constants::{BalanceManager, Coin, Pool};
+pub use crate::utils::error::{DeepBookAbort, DeepBookError};
+pub use crate::utils::execution::DeepBookSigner;
+pub use crate::utils::keys::{CoinKey, ManagerKey, PoolKey};
+pub use crate::utils::pricing::PriceFeed;
+pub use crate::utils::types::{Account, Balances, FeeRate, Order, OrderBook, OrderEvent, OrderInfo};
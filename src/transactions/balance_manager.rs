@@ -9,14 +9,16 @@ use crate::utils::config::DeepBookConfig;
 use anyhow::{Context, Result};
 use sui_sdk::SuiClient;
 use sui_sdk::types::{
-    base_types::{ObjectID},
+    base_types::{ObjectID, SuiAddress},
     programmable_transaction_builder::ProgrammableTransactionBuilder,
     Identifier,
     TypeTag,
 };
 use sui_sdk::types::transaction::{Argument};
 use sui_types::transaction::Command;
-use crate::utils::transactions::prepare_balance_manager_argument;
+use crate::utils::transactions::{
+    prepare_available_shared_object_arguments, prepare_balance_manager_argument, prepare_imm_or_owned_object_argument,
+};
 
 /// BalanceManagerContract is a struct for managing BalanceManager smart contract operations.
 pub struct BalanceManagerContract {
@@ -38,35 +40,26 @@ impl BalanceManagerContract {
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
     ) -> Result<()> {
-        let manager = ptb.programmable_move_call(
-            ObjectID::from_hex_literal(&self.config.deepbook_package_id)
-                .with_context(|| format!("Invalid package ID for deepbook_package_id: {}", self.config.deepbook_package_id))?,
-            Identifier::new("balance_manager")
-                .with_context(|| "Invalid identifier for 'balance_manager'")?,
-            Identifier::new("new")
-                .with_context(|| "Invalid identifier for 'new'")?,
-            vec![],
-            vec![],
-        );
-
-        let balance_manager_type = TypeTag::from_str(
-            format!("{}::balance_manager::BalanceManager", self.config.deepbook_package_id).as_str()
-        ).with_context(|| "Failed to parse balance manager type")?;
-
-        ptb.programmable_move_call(
-            ObjectID::from_hex_literal("0x2")
-                .with_context(|| "Invalid package ID for sui framework: 0x2")?,
-            Identifier::new("transfer")
-                .with_context(|| "Invalid identifier for 'transfer'")?,
-            Identifier::new("public_share_object")
-                .with_context(|| "Invalid identifier for 'public_share_object'")?,
-            vec![balance_manager_type],
-            vec![manager],
-        );
+        append_bootstrap_manager(ptb, &self.config.deepbook_package_id, vec![])?;
 
         Ok(())
     }
 
+    /// Create a new BalanceManager object and transfer it to an address instead of sharing it,
+    /// for single-user setups that don't need a shared object.
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `recipient` - The address that will own the new manager.
+    /// # Returns
+    /// The `Argument` of the new manager, or an error.
+    pub fn create_and_transfer_balance_manager(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        recipient: SuiAddress,
+    ) -> Result<Argument> {
+        append_create_and_transfer_manager(ptb, &self.config.deepbook_package_id, recipient)
+    }
+
     /// Deposit funds into a balance manager.
     /// # Arguments
     /// * `ptb` - ProgrammableTransactionBuilder instance.
@@ -82,11 +75,8 @@ impl BalanceManagerContract {
         coin_key: &str,
         amount_to_deposit: f64,
     ) -> Result<()> {
-        let coin = self
-            .config
-            .get_coin(coin_key)
-            .with_context(|| format!("Coin not found for key {}", coin_key))?;
-        let deposit_input = (amount_to_deposit * coin.scalar as f64) as u64;
+        let coin = self.config.require_coin(coin_key)?;
+        let deposit_input = coin.to_units_floor(amount_to_deposit);
         let deposit_argument = ptb.pure(deposit_input)
             .with_context(|| "Failed to prepare deposit input")?;
         let coin_argument = ptb.command(Command::SplitCoins(
@@ -131,12 +121,28 @@ impl BalanceManagerContract {
         manager_key: &str,
         coin_key: &str,
     ) -> Result<()> {
-        let coin = self
-            .config
-            .get_coin(coin_key)
-            .with_context(|| format!("Coin not found for key: {}", coin_key))?;
-        let coin_type = TypeTag::from_str(&coin.type_)
-            .with_context(|| format!("Failed to parse coin type: {}", coin.type_))?;
+        let coin = self.config.require_coin(coin_key)?;
+
+        self.check_manager_balance_by_type(ptb, manager_key, &coin.type_).await
+    }
+
+    /// Check the balance of the BalanceManager for a coin given by its type,
+    /// e.g. a coin that is not in the configuration.
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `manager_key` - The key identifying the balance manager.
+    /// * `coin_type` - The coin type, e.g. `0x2::sui::SUI`.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn check_manager_balance_by_type(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        coin_type: &str,
+    ) -> Result<()> {
+        let coin_type = TypeTag::from_str(coin_type)
+            .with_context(|| format!("Failed to parse coin type: {}", coin_type))?;
 
         let manager_argument = prepare_balance_manager_argument(
             &self.client,
@@ -159,6 +165,82 @@ impl BalanceManagerContract {
         Ok(())
     }
 
+    /// Check the balance of a coin in several BalanceManagers, one command per manager.
+    /// All managers are resolved with a single RPC; managers whose object can't be resolved are skipped.
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `manager_keys` - The keys identifying the balance managers.
+    /// * `coin_key` - The key identifying the coin.
+    ///
+    /// # Returns
+    /// The keys of the managers a command was added for, in command order, or an error.
+    pub async fn check_managers_balance(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_keys: &[&str],
+        coin_key: &str,
+    ) -> Result<Vec<String>> {
+        let coin = self.config.require_coin(coin_key)?;
+        let coin_type = TypeTag::from_str(&coin.type_)
+            .with_context(|| format!("Failed to parse coin type: {}", coin.type_))?;
+        let manager_addresses = manager_keys
+            .iter()
+            .map(|manager_key| self.config
+                .require_balance_manager(manager_key)
+                .map(|manager| (manager.address.as_str(), true)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let manager_arguments = prepare_available_shared_object_arguments(&*self.client, ptb, &manager_addresses)
+            .await.with_context(|| "Failed to prepare manager arguments")?;
+
+        let mut included = Vec::new();
+        for (manager_key, manager_argument) in manager_keys.iter().zip(manager_arguments) {
+            let Some(manager_argument) = manager_argument else {
+                continue;
+            };
+
+            ptb.programmable_move_call(
+                ObjectID::from_hex_literal(&self.config.deepbook_package_id)
+                    .with_context(|| "Invalid package ID for deepbook_package_id")?,
+                Identifier::new("balance_manager")
+                    .with_context(|| "Invalid identifier for 'balance_manager'")?,
+                Identifier::new("balance")
+                    .with_context(|| "Invalid identifier for 'balance'")?,
+                vec![coin_type.clone()],
+                vec![manager_argument],
+            );
+            included.push(manager_key.to_string());
+        }
+
+        Ok(included)
+    }
+
+    /// Generate a trade proof for the balance manager.
+    /// Uses the manager's trade cap when one is configured, otherwise generates the proof as the owner.
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `manager_key` - The key identifying the balance manager.
+    /// * `manager_argument` - The prepared BalanceManager argument.
+    /// # Returns
+    /// The trade proof argument, or an error.
+    pub async fn generate_proof(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        manager_argument: Argument,
+    ) -> Result<Argument> {
+        let manager = self.config.require_balance_manager(manager_key)?;
+
+        if let Some(trade_cap_id) = &manager.trade_cap {
+            let trade_cap_argument = prepare_imm_or_owned_object_argument(&self.client, ptb, trade_cap_id)
+                .await.with_context(|| format!("Failed to prepare trade cap argument for key: {}", trade_cap_id))?;
+
+            Ok(self.generate_proof_as_trader(ptb, manager_argument, trade_cap_argument))
+        } else {
+            Ok(self.generate_proof_as_owner(ptb, manager_argument))
+        }
+    }
+
     /// Generate a trade proof as the owner.
     pub fn generate_proof_as_owner(
         &self,
@@ -194,3 +276,220 @@ impl BalanceManagerContract {
         trade_proof
     }
 }
+
+/// Appends the creation of a BalanceManager, a `balance_manager::deposit` of each `(coin type, coin)`
+/// into it, and the share of the manager, in that order: once shared, the manager is consumed.
+///
+/// # Returns
+/// The `Argument` of the new manager.
+pub(crate) fn append_bootstrap_manager(
+    ptb: &mut ProgrammableTransactionBuilder,
+    deepbook_package_id: &str,
+    deposits: Vec<(TypeTag, Argument)>,
+) -> Result<Argument> {
+    let manager = append_new_manager(ptb, deepbook_package_id)?;
+    append_deposits(ptb, deepbook_package_id, manager, deposits)?;
+
+    let balance_manager_type = balance_manager_type(deepbook_package_id)?;
+
+    ptb.programmable_move_call(
+        ObjectID::from_hex_literal("0x2")
+            .with_context(|| "Invalid package ID for sui framework: 0x2")?,
+        Identifier::new("transfer")
+            .with_context(|| "Invalid identifier for 'transfer'")?,
+        Identifier::new("public_share_object")
+            .with_context(|| "Invalid identifier for 'public_share_object'")?,
+        vec![balance_manager_type],
+        vec![manager],
+    );
+
+    Ok(manager)
+}
+
+/// Appends a `balance_manager::new` followed by a `transfer::public_transfer` of the new manager
+/// to `recipient`, leaving it an owned object.
+pub(crate) fn append_create_and_transfer_manager(
+    ptb: &mut ProgrammableTransactionBuilder,
+    deepbook_package_id: &str,
+    recipient: SuiAddress,
+) -> Result<Argument> {
+    let manager = append_new_manager(ptb, deepbook_package_id)?;
+    let balance_manager_type = balance_manager_type(deepbook_package_id)?;
+    let recipient_argument = ptb.pure(recipient)
+        .with_context(|| "Failed to prepare recipient pure argument")?;
+
+    ptb.programmable_move_call(
+        ObjectID::from_hex_literal("0x2")
+            .with_context(|| "Invalid package ID for sui framework: 0x2")?,
+        Identifier::new("transfer")
+            .with_context(|| "Invalid identifier for 'transfer'")?,
+        Identifier::new("public_transfer")
+            .with_context(|| "Invalid identifier for 'public_transfer'")?,
+        vec![balance_manager_type],
+        vec![manager, recipient_argument],
+    );
+
+    Ok(manager)
+}
+
+/// Appends a `balance_manager::new` and returns the new manager.
+fn append_new_manager(ptb: &mut ProgrammableTransactionBuilder, deepbook_package_id: &str) -> Result<Argument> {
+    let package_id = ObjectID::from_hex_literal(deepbook_package_id)
+        .with_context(|| format!("Invalid package ID for deepbook_package_id: {}", deepbook_package_id))?;
+
+    Ok(ptb.programmable_move_call(
+        package_id,
+        Identifier::new("balance_manager")
+            .with_context(|| "Invalid identifier for 'balance_manager'")?,
+        Identifier::new("new")
+            .with_context(|| "Invalid identifier for 'new'")?,
+        vec![],
+        vec![],
+    ))
+}
+
+fn balance_manager_type(deepbook_package_id: &str) -> Result<TypeTag> {
+    TypeTag::from_str(format!("{}::balance_manager::BalanceManager", deepbook_package_id).as_str())
+        .with_context(|| "Failed to parse balance manager type")
+}
+
+/// Appends a `balance_manager::deposit` of each `(coin type, coin)` into `manager`.
+pub(crate) fn append_deposits(
+    ptb: &mut ProgrammableTransactionBuilder,
+    deepbook_package_id: &str,
+    manager: Argument,
+    deposits: Vec<(TypeTag, Argument)>,
+) -> Result<()> {
+    let package_id = ObjectID::from_hex_literal(deepbook_package_id)
+        .with_context(|| format!("Invalid package ID for deepbook_package_id: {}", deepbook_package_id))?;
+
+    for (coin_type, coin_argument) in deposits {
+        ptb.programmable_move_call(
+            package_id,
+            Identifier::new("balance_manager")
+                .with_context(|| "Invalid identifier for 'balance_manager'")?,
+            Identifier::new("deposit")
+                .with_context(|| "Invalid identifier for 'deposit'")?,
+            vec![coin_type],
+            vec![manager, coin_argument],
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{ObjectRef, SequenceNumber};
+    use sui_types::digests::ObjectDigest;
+    use sui_types::transaction::{CallArg, ObjectArg};
+
+    fn owned_coin(ptb: &mut ProgrammableTransactionBuilder) -> Argument {
+        let coin_ref: ObjectRef = (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random());
+        ptb.obj(ObjectArg::ImmOrOwnedObject(coin_ref)).unwrap()
+    }
+
+    #[test]
+    fn test_deposits_share_one_manager_argument() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let manager = ptb.obj(ObjectArg::SharedObject {
+            id: ObjectID::random(),
+            initial_shared_version: SequenceNumber::from_u64(1),
+            mutable: true,
+        }).unwrap();
+        let deposits = ["DEEP", "DBUSDC"]
+            .iter()
+            .map(|coin_key| {
+                let coin_type = TypeTag::from_str(&config.get_coin(coin_key).unwrap().type_).unwrap();
+                (coin_type, owned_coin(&mut ptb))
+            })
+            .collect();
+
+        append_deposits(&mut ptb, &config.deepbook_package_id, manager, deposits).unwrap();
+
+        let transaction = ptb.finish();
+        let shared_inputs = transaction.inputs
+            .iter()
+            .filter(|input| matches!(input, CallArg::Object(ObjectArg::SharedObject { .. })))
+            .count();
+        assert_eq!(shared_inputs, 1);
+        assert_eq!(transaction.commands.len(), 2);
+        for command in &transaction.commands {
+            let Command::MoveCall(call) = command else {
+                panic!("expected a Move call, got {:?}", command);
+            };
+            assert_eq!(format!("{}::{}", call.module, call.function), "balance_manager::deposit");
+            assert_eq!(call.arguments[0], manager);
+        }
+    }
+
+    #[test]
+    fn test_bootstrap_manager_creates_deposits_and_shares() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let deposits = ["SUI", "DEEP", "DBUSDC"]
+            .iter()
+            .map(|coin_key| {
+                let coin_type = TypeTag::from_str(&config.get_coin(coin_key).unwrap().type_).unwrap();
+                (coin_type, owned_coin(&mut ptb))
+            })
+            .collect();
+
+        let manager = append_bootstrap_manager(&mut ptb, &config.deepbook_package_id, deposits).unwrap();
+
+        let commands = ptb.finish().commands;
+        let names: Vec<String> = commands
+            .iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => format!("{}::{}", call.module, call.function),
+                other => format!("{:?}", other),
+            })
+            .collect();
+        assert_eq!(names, vec![
+            "balance_manager::new",
+            "balance_manager::deposit",
+            "balance_manager::deposit",
+            "balance_manager::deposit",
+            "transfer::public_share_object",
+        ]);
+        assert_eq!(manager, Argument::Result(0));
+        // Every deposit goes into the new manager, which is shared last
+        assert!(commands[1..].iter().all(|command| matches!(
+            command,
+            Command::MoveCall(call) if call.arguments[0] == manager
+        )));
+    }
+
+    #[test]
+    fn test_create_and_transfer_manager_transfers_instead_of_sharing() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let recipient = SuiAddress::random_for_testing_only();
+
+        let manager = append_create_and_transfer_manager(&mut ptb, &config.deepbook_package_id, recipient).unwrap();
+
+        let transaction = ptb.finish();
+        let names: Vec<String> = transaction.commands
+            .iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => format!("{}::{}", call.module, call.function),
+                other => format!("{:?}", other),
+            })
+            .collect();
+        assert_eq!(names, vec!["balance_manager::new", "transfer::public_transfer"]);
+        assert_eq!(manager, Argument::Result(0));
+        let Command::MoveCall(transfer) = &transaction.commands[1] else {
+            panic!("expected a Move call");
+        };
+        assert_eq!(transfer.arguments[0], manager);
+        let Argument::Input(recipient_index) = transfer.arguments[1] else {
+            panic!("expected the recipient input, got {:?}", transfer.arguments[1]);
+        };
+        assert_eq!(
+            transaction.inputs[recipient_index as usize],
+            CallArg::Pure(bcs::to_bytes(&recipient).unwrap())
+        );
+    }
+}
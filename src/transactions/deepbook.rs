@@ -4,17 +4,28 @@
 // This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
 
 use std::{str::FromStr};
-use std::sync::Arc;
-use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use anyhow::{anyhow, bail, Context, Result};
 use sui_sdk::SuiClient;
+#[cfg(feature = "decimal")]
+use rust_decimal::{prelude::ToPrimitive, Decimal, RoundingStrategy};
 use sui_sdk::types::{programmable_transaction_builder::ProgrammableTransactionBuilder, Identifier, TypeTag};
+use sui_sdk::types::transaction::{Argument, CallArg, ObjectArg};
 use sui_sdk::types::base_types::{ObjectID};
 use crate::DeepBookConfig;
 use crate::transactions::balance_manager::BalanceManagerContract;
-use crate::utils::config::{FLOAT_SCALAR, MAX_TIMESTAMP};
-use crate::utils::transactions::{prepare_balance_manager_argument, prepare_imm_or_owned_object_argument, prepare_pool_argument, prepare_sui_clock_argument};
+use crate::utils::config::{DEEPBOOK_VERSION, DEEP_DEPOSIT_SAFETY_MARGIN, FEE_PENALTY_MULTIPLIER, FLOAT_SCALAR};
+use crate::utils::constants::Coin;
+use crate::utils::transactions::{
+    prepare_order_arguments, prepare_order_arguments_for, prepare_pool_argument,
+    deepbook_move_call, dev_inspect_ptb, fetch_pool_inner_fields, nested_result, parse_return_value_at,
+    prepare_available_shared_object_arguments, prepare_read_arguments, prepare_shared_object_arguments,
+    shared_clock_argument, verify_pool_object, ObjectFetcher,
+};
+use crate::utils::types::{allowed_versions_from_json, Order, OrderDeepPrice, PoolBookParams};
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum OrderType {
     NoRestriction,
     ImmediateOrCancel,
@@ -33,8 +44,9 @@ impl OrderType {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum SelfMatchingOptions {
+    #[default]
     SelfMatchingAllowed,
     CancelTaker,
     CancelMaker
@@ -50,159 +62,2217 @@ impl SelfMatchingOptions {
     }
 }
 
+/// How an order pays its trading fee when `PlaceOrderOptions::pay_with_deep` is unset.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FeeMode {
+    /// Pay in DEEP if the manager holds enough DEEP to pay the order's fee as a taker,
+    /// otherwise in the input coin. Costs a dev-inspect per order; ladders placed with
+    /// `requote` pay in DEEP. Pays in the input coin if no DEEP coin is configured.
+    #[default]
+    Auto,
+    /// Always pay in DEEP.
+    Deep,
+    /// Always pay in the input coin, at `FEE_PENALTY_MULTIPLIER` times the DEEP fee.
+    InputCoin,
+}
+
+impl FeeMode {
+    /// Whether the mode pays in DEEP without looking at the manager's balance, if it can tell.
+    pub fn fixed(&self) -> Option<bool> {
+        match self {
+            FeeMode::Auto => None,
+            FeeMode::Deep => Some(true),
+            FeeMode::InputCoin => Some(false),
+        }
+    }
+}
+
+/// Whether an order pays its fee in DEEP under `FeeMode::Auto`: only if the manager's DEEP
+/// balance covers the DEEP the order would pay, both in on-chain units.
+pub(crate) fn auto_pays_with_deep(deep_balance: u64, deep_required: u64) -> bool {
+    deep_balance >= deep_required
+}
+
+/// Resolves the expiration of an order, defaulting to the configured "never expires" timestamp.
+pub(crate) fn resolve_expiration(expiration: Option<u64>, config: &DeepBookConfig) -> u64 {
+    expiration.unwrap_or_else(|| config.no_expiration())
+}
+
+/// Resolves the self-matching option of an order, defaulting to the configured policy.
+pub(crate) fn resolve_self_matching(
+    self_matching_option: Option<SelfMatchingOptions>,
+    default: SelfMatchingOptions,
+) -> SelfMatchingOptions {
+    self_matching_option.unwrap_or(default)
+}
+
+fn cached_params(pool_params: &RwLock<HashMap<String, PoolBookParams>>, pool_key: &str) -> Option<PoolBookParams> {
+    pool_params
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(pool_key)
+        .copied()
+}
+
+/// Rounds an encoded price and quantity to the tick and lot sizes cached for their pool with
+/// `rounding_mode`, so an order priced between ticks doesn't abort on chain.
+/// Pools without cached parameters are left unchanged; no RPC is issued either way.
+fn round_to_cached_params(
+    pool_params: &RwLock<HashMap<String, PoolBookParams>>,
+    pool_key: &str,
+    rounding_mode: RoundingMode,
+    (input_price, input_quantity): (u64, u64),
+    is_bid: bool,
+) -> (u64, u64) {
+    match cached_params(pool_params, pool_key) {
+        Some(params) => (
+            rounding_mode.round_price_to_tick(input_price, params.tick_size, is_bid),
+            rounding_mode.round_quantity_to_lot(input_quantity, params.lot_size),
+        ),
+        None => (input_price, input_quantity),
+    }
+}
+
+/// Validates an encoded order against the cached book parameters of its pool.
+/// Pools without cached parameters are not validated; no RPC is issued either way.
+fn validate_with_cached_params(
+    pool_params: &RwLock<HashMap<String, PoolBookParams>>,
+    pool_key: &str,
+    input_price: u64,
+    input_quantity: u64,
+) -> Result<()> {
+    match cached_params(pool_params, pool_key) {
+        Some(params) => params
+            .validate(input_price, input_quantity)
+            .with_context(|| format!("Invalid order for pool: {}", pool_key)),
+        None => Ok(()),
+    }
+}
+
+/// How a price or quantity that doesn't fall on an on-chain unit is rounded.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RoundingMode {
+    /// Round to the nearest unit.
+    #[default]
+    Nearest,
+    /// Round away from the other side of the book: bids down, asks up.
+    /// Quantities are rounded down. Use this to never cross the spread because of rounding.
+    TowardMaker,
+    /// Round toward the other side of the book: bids up, asks down.
+    /// Quantities are rounded down.
+    TowardTaker,
+}
+
+impl RoundingMode {
+    /// Rounds a raw price for an order on the given side.
+    pub fn round_price(&self, raw_price: f64, is_bid: bool) -> u64 {
+        match (self, is_bid) {
+            (RoundingMode::Nearest, _) => raw_price.round() as u64,
+            (RoundingMode::TowardMaker, true) | (RoundingMode::TowardTaker, false) => floor_units(raw_price),
+            (RoundingMode::TowardMaker, false) | (RoundingMode::TowardTaker, true) => ceil_units(raw_price),
+        }
+    }
+
+    /// Rounds a raw quantity.
+    pub fn round_quantity(&self, raw_quantity: f64) -> u64 {
+        match self {
+            RoundingMode::Nearest => raw_quantity.round() as u64,
+            RoundingMode::TowardMaker | RoundingMode::TowardTaker => floor_units(raw_quantity),
+        }
+    }
+
+    /// Rounds an encoded price to a multiple of the pool's tick size, for an order on the given side.
+    /// A zero tick size leaves the price unchanged.
+    pub fn round_price_to_tick(&self, input_price: u64, tick_size: u64, is_bid: bool) -> u64 {
+        let round = match (self, is_bid) {
+            (RoundingMode::Nearest, _) => nearest_multiple,
+            (RoundingMode::TowardMaker, true) | (RoundingMode::TowardTaker, false) => floor_multiple,
+            (RoundingMode::TowardMaker, false) | (RoundingMode::TowardTaker, true) => ceil_multiple,
+        };
+
+        round(input_price, tick_size)
+    }
+
+    /// Rounds an encoded quantity to a multiple of the pool's lot size.
+    /// A zero lot size leaves the quantity unchanged.
+    pub fn round_quantity_to_lot(&self, input_quantity: u64, lot_size: u64) -> u64 {
+        match self {
+            RoundingMode::Nearest => nearest_multiple(input_quantity, lot_size),
+            RoundingMode::TowardMaker | RoundingMode::TowardTaker => floor_multiple(input_quantity, lot_size),
+        }
+    }
+}
+
+fn floor_multiple(value: u64, step: u64) -> u64 {
+    if step == 0 { value } else { value - value % step }
+}
+
+fn ceil_multiple(value: u64, step: u64) -> u64 {
+    match step {
+        0 => value,
+        _ if value % step == 0 => value,
+        _ => floor_multiple(value, step).saturating_add(step),
+    }
+}
+
+fn nearest_multiple(value: u64, step: u64) -> u64 {
+    if step == 0 || value % step < step - value % step {
+        floor_multiple(value, step)
+    } else {
+        ceil_multiple(value, step)
+    }
+}
+
+/// Tolerance under which a raw value is considered to already be a whole unit,
+/// so `f64` noise such as `1999.9999999999998` isn't floored to the unit below.
+const UNIT_EPSILON: f64 = 1e-6;
+
+fn floor_units(raw: f64) -> u64 {
+    let nearest = raw.round();
+    if (raw - nearest).abs() < UNIT_EPSILON { nearest as u64 } else { raw.floor() as u64 }
+}
+
+fn ceil_units(raw: f64) -> u64 {
+    let nearest = raw.round();
+    if (raw - nearest).abs() < UNIT_EPSILON { nearest as u64 } else { raw.ceil() as u64 }
+}
+
+/// Optional parameters for placing a limit order. Unset fields fall back to their defaults.
+#[derive(Debug, Default)]
+pub struct PlaceOrderOptions {
+    /// Expiration timestamp for the order, defaults to never expiring.
+    pub expiration: Option<u64>,
+    /// Order type, defaults to `OrderType::NoRestriction`.
+    pub order_type: Option<OrderType>,
+    /// Self-matching option, defaults to the contract's default self-matching option.
+    pub self_matching_option: Option<SelfMatchingOptions>,
+    /// Whether fees are paid in DEEP, defaults to the choice of `fee_mode`.
+    pub pay_with_deep: Option<bool>,
+    /// How fees are paid when `pay_with_deep` is unset, defaults to `FeeMode::Auto`.
+    pub fee_mode: FeeMode,
+    /// How price and quantity are rounded to on-chain units and, if the pool's book parameters
+    /// are cached (e.g. with `DeepBookClient::load_all_pool_params`), to its tick and lot sizes.
+    pub rounding_mode: RoundingMode,
+    /// Whether to fetch the pool object and check its type against the configured coins
+    /// before building the order. Costs one extra RPC.
+    pub verify_pool: bool,
+    /// Whether to check that the manager has enough free balance for the order
+    /// (quote for bids, base for asks) before building it. Costs a dev-inspect.
+    pub check_balance: bool,
+    /// Whether to refuse building the order if the pool is disabled, i.e. no longer allows
+    /// the DeepBook version of this SDK. Costs two RPCs.
+    pub require_enabled_pool: bool,
+}
+
+/// One order of a ladder placed by `requote`. Unset fields fall back to the options of the
+/// ladder, so a batch can mix e.g. DEEP-paid and input-paid orders. Built from a
+/// `(price, quantity, is_bid)` tuple for an order that only uses the ladder's options.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrderSpec {
+    pub price: f64,
+    pub quantity: f64,
+    pub is_bid: bool,
+    /// Order type of this order, defaults to the ladder's.
+    pub order_type: Option<OrderType>,
+    /// Whether this order pays fees in DEEP, defaults to the ladder's.
+    pub pay_with_deep: Option<bool>,
+}
+
+impl OrderSpec {
+    pub fn new(price: f64, quantity: f64, is_bid: bool) -> Self {
+        OrderSpec { price, quantity, is_bid, order_type: None, pay_with_deep: None }
+    }
+}
+
+impl From<(f64, f64, bool)> for OrderSpec {
+    fn from((price, quantity, is_bid): (f64, f64, bool)) -> Self {
+        OrderSpec::new(price, quantity, is_bid)
+    }
+}
+
+/// Encodes a human-readable price into the on-chain price representation of a pool.
+pub(crate) fn encode_price(price: f64, base_coin: &Coin, quote_coin: &Coin, rounding_mode: RoundingMode, is_bid: bool) -> u64 {
+    let raw_price = (price * FLOAT_SCALAR as f64 * quote_coin.scalar as f64) / base_coin.scalar as f64;
+    rounding_mode.round_price(raw_price, is_bid)
+}
+
+/// Encodes a human-readable base quantity into on-chain base units.
+pub(crate) fn encode_quantity(quantity: f64, base_coin: &Coin, rounding_mode: RoundingMode) -> u64 {
+    rounding_mode.round_quantity(quantity * base_coin.scalar as f64)
+}
+
+/// Scales human prices and quantities into the on-chain units of a pool.
+///
+/// Implement this trait to encode a family of pools differently, and select it per pool with
+/// `DeepBookClient::with_price_encoder`. Pools without one use `DefaultPriceEncoder`.
+pub trait PriceEncoder: Send + Sync {
+    /// Encodes a price in quote per base into the on-chain price of an order on the given side.
+    fn encode_price(
+        &self,
+        price: f64,
+        base_coin: &Coin,
+        quote_coin: &Coin,
+        rounding_mode: RoundingMode,
+        is_bid: bool,
+    ) -> u64;
+
+    /// Encodes a base quantity into on-chain base units.
+    fn encode_quantity(&self, quantity: f64, base_coin: &Coin, rounding_mode: RoundingMode) -> u64;
+}
+
+/// Encodes with `f64` arithmetic, ignoring noise below `UNIT_EPSILON` units when rounding.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultPriceEncoder;
+
+impl PriceEncoder for DefaultPriceEncoder {
+    fn encode_price(
+        &self,
+        price: f64,
+        base_coin: &Coin,
+        quote_coin: &Coin,
+        rounding_mode: RoundingMode,
+        is_bid: bool,
+    ) -> u64 {
+        encode_price(price, base_coin, quote_coin, rounding_mode, is_bid)
+    }
+
+    fn encode_quantity(&self, quantity: f64, base_coin: &Coin, rounding_mode: RoundingMode) -> u64 {
+        encode_quantity(quantity, base_coin, rounding_mode)
+    }
+}
+
+/// Encodes the shortest decimal form of a price or quantity with exact integer arithmetic.
+/// Meant for stable pools, whose prices sit near 1 with ticks down to the last on-chain unit:
+/// there, an `f64` product like `1.0066628125 * 10^9` lands a hair below the half unit and
+/// rounds the wrong way. Values that don't fit the exact arithmetic use `DefaultPriceEncoder`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StablePriceEncoder;
+
+impl PriceEncoder for StablePriceEncoder {
+    fn encode_price(
+        &self,
+        price: f64,
+        base_coin: &Coin,
+        quote_coin: &Coin,
+        rounding_mode: RoundingMode,
+        is_bid: bool,
+    ) -> u64 {
+        let numerator = FLOAT_SCALAR as u128 * quote_coin.scalar as u128;
+        match ExactUnits::scale(price, numerator, base_coin.scalar as u128) {
+            Some(units) => match (rounding_mode, is_bid) {
+                (RoundingMode::Nearest, _) => units.nearest(),
+                (RoundingMode::TowardMaker, true) | (RoundingMode::TowardTaker, false) => units.floor(),
+                (RoundingMode::TowardMaker, false) | (RoundingMode::TowardTaker, true) => units.ceil(),
+            },
+            None => encode_price(price, base_coin, quote_coin, rounding_mode, is_bid),
+        }
+    }
+
+    fn encode_quantity(&self, quantity: f64, base_coin: &Coin, rounding_mode: RoundingMode) -> u64 {
+        match ExactUnits::scale(quantity, base_coin.scalar as u128, 1) {
+            Some(units) => match rounding_mode {
+                RoundingMode::Nearest => units.nearest(),
+                RoundingMode::TowardMaker | RoundingMode::TowardTaker => units.floor(),
+            },
+            None => encode_quantity(quantity, base_coin, rounding_mode),
+        }
+    }
+}
+
+/// A value scaled into on-chain units exactly, as `whole + remainder / divisor` units.
+struct ExactUnits {
+    whole: u128,
+    remainder: u128,
+    divisor: u128,
+}
+
+impl ExactUnits {
+    /// Scales the shortest decimal form of `value` by `numerator / denominator`, or None if
+    /// `value` is negative, not finite or too precise for `u128` arithmetic.
+    fn scale(value: f64, numerator: u128, denominator: u128) -> Option<ExactUnits> {
+        if !value.is_finite() || value < 0.0 || denominator == 0 {
+            return None;
+        }
+        // `f64`'s `Display` is the shortest form that round-trips, never in exponent notation
+        let decimal = value.to_string();
+        let (integer, fraction) = decimal.split_once('.').unwrap_or((&decimal, ""));
+        let digits: u128 = format!("{}{}", integer, fraction).parse().ok()?;
+        let divisor = 10u128.checked_pow(fraction.len() as u32)?.checked_mul(denominator)?;
+        let scaled = digits.checked_mul(numerator)?;
+
+        Some(ExactUnits { whole: scaled / divisor, remainder: scaled % divisor, divisor })
+    }
+
+    fn floor(&self) -> u64 {
+        u64::try_from(self.whole).unwrap_or(u64::MAX)
+    }
+
+    fn ceil(&self) -> u64 {
+        u64::try_from(self.whole + u128::from(self.remainder > 0)).unwrap_or(u64::MAX)
+    }
+
+    /// Rounds half away from zero, like `f64::round`.
+    fn nearest(&self) -> u64 {
+        u64::try_from(self.whole + u128::from(self.remainder >= self.divisor - self.remainder)).unwrap_or(u64::MAX)
+    }
+}
+
+/// Converts a quote amount into the base quantity it buys or sells at `price`,
+/// rounded down to a multiple of the pool's lot size (in on-chain base units).
+pub(crate) fn quote_to_base_quantity(quote_amount: f64, price: f64, base_coin: &Coin, lot_size: u64) -> f64 {
+    let raw_quantity = floor_units(quote_amount / price * base_coin.scalar as f64);
+    let lot_aligned = match lot_size {
+        0 => raw_quantity,
+        lot_size => raw_quantity - raw_quantity % lot_size,
+    };
+
+    base_coin.to_human(lot_aligned)
+}
+
+/// Balance an order needs in on-chain units: quote for a bid, base for an ask. Fees are not included.
+pub(crate) fn required_balance(input_price: u64, input_quantity: u64, is_bid: bool) -> u64 {
+    if is_bid {
+        ((input_price as u128 * input_quantity as u128).div_ceil(FLOAT_SCALAR as u128)) as u64
+    } else {
+        input_quantity
+    }
+}
+
+/// DEEP fee in on-chain units of an order at `fee_rate`, mirroring `order_deep_price::deep_quantity`:
+/// the DEEP equivalent of the order's base (or quote) quantity, times the fee rate.
+pub(crate) fn order_deep_required(input_price: u64, input_quantity: u64, fee_rate: u64, deep_price: &OrderDeepPrice) -> u64 {
+    let mul = |x: u64, y: u64| (x as u128 * y as u128 / FLOAT_SCALAR as u128) as u64;
+    let deep_quantity = if deep_price.asset_is_base {
+        mul(input_quantity, deep_price.deep_per_asset)
+    } else {
+        mul(mul(input_quantity, input_price), deep_price.deep_per_asset)
+    };
+
+    mul(deep_quantity, fee_rate)
+}
+
+/// DEEP in on-chain units to deposit for `quote_notional` of taker trading at `taker_fee`, plus
+/// `DEEP_DEPOSIT_SAFETY_MARGIN`. A DEEP price per base unit converts the notional at `mid_price`.
+pub(crate) fn recommended_deep_deposit(
+    quote_notional: u64,
+    mid_price: u64,
+    taker_fee: u64,
+    deep_price: &OrderDeepPrice,
+) -> u64 {
+    let mul = |x: u64, y: u64| (x as u128 * y as u128 / FLOAT_SCALAR as u128) as u64;
+    let deep_quantity = if deep_price.asset_is_base {
+        let base_quantity = (quote_notional as u128 * FLOAT_SCALAR as u128 / mid_price.max(1) as u128) as u64;
+        mul(base_quantity, deep_price.deep_per_asset)
+    } else {
+        mul(quote_notional, deep_price.deep_per_asset)
+    };
+
+    (mul(deep_quantity, taker_fee) as f64 * (1.0 + DEEP_DEPOSIT_SAFETY_MARGIN)).ceil() as u64
+}
+
+/// All-in price of a taker fill at `price` with the pool's `taker_fee` rate in on-chain units:
+/// a bid pays the fee on top, an ask receives the price minus the fee. A DEEP fee is charged at
+/// the pool's DEEP price, so converted back to quote it costs the same rate; a fee paid in the
+/// input coin costs `FEE_PENALTY_MULTIPLIER` times more.
+pub(crate) fn effective_taker_price(price: f64, taker_fee: u64, is_bid: bool, pay_with_deep: bool) -> f64 {
+    let multiplier = if pay_with_deep { 1.0 } else { FEE_PENALTY_MULTIPLIER };
+    let fee_rate = taker_fee as f64 / FLOAT_SCALAR as f64 * multiplier;
+
+    if is_bid {
+        price * (1.0 + fee_rate)
+    } else {
+        price * (1.0 - fee_rate)
+    }
+}
+
+/// Fails with a readable error when the free balance can't cover the required balance.
+pub(crate) fn ensure_free_balance(required: u64, free: u64, locked: u64, coin_key: &str, coin: &Coin) -> Result<()> {
+    if free < required {
+        return Err(anyhow!(
+            "Insufficient free balance of {}: need {} have {} ({} locked in open orders)",
+            coin_key,
+            coin.to_human(required),
+            coin.to_human(free),
+            coin.to_human(locked),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fails with a readable error when a pool's allowed versions don't include the SDK's version,
+/// e.g. after the pool was disabled.
+pub(crate) fn ensure_pool_enabled(pool_label: &str, allowed_versions: &[u64]) -> Result<()> {
+    if !allowed_versions.contains(&DEEPBOOK_VERSION) {
+        return Err(anyhow!(
+            "Pool {} is disabled: it allows versions {:?}, not {}",
+            pool_label,
+            allowed_versions,
+            DEEPBOOK_VERSION,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Price encoded in bits 64..127 of an order id, in on-chain units.
+pub(crate) fn order_id_price(order_id: u128) -> u64 {
+    ((order_id >> 64) as u64) & !(1u64 << 63)
+}
+
+/// Side encoded in bit 127 of an order id: clear for bids, set for asks.
+pub(crate) fn order_id_is_bid(order_id: u128) -> bool {
+    order_id >> 127 == 0
+}
+
+/// The order re-placing an open order with a new expiration: same client order id, price,
+/// side, remaining quantity and fee asset. It's post-only so it rests like the original did.
+pub(crate) fn replacement_order(order: &Order, new_expiration: u64, self_matching_option: SelfMatchingOptions) -> EncodedLimitOrder {
+    EncodedLimitOrder {
+        client_order_id: order.client_order_id,
+        order_type: OrderType::PostOnly,
+        self_matching_option,
+        input_price: order_id_price(order.order_id),
+        input_quantity: order.quantity - order.filled_quantity,
+        is_bid: order_id_is_bid(order.order_id),
+        pay_with_deep: order.fee_is_deep,
+        expiration: new_expiration,
+    }
+}
+
+/// Notional value in human quote units of `quantity` raw base units at the on-chain `price`.
+pub(crate) fn notional_value(price: u64, quantity: u64, quote_coin: &Coin) -> f64 {
+    let quote_units = price as f64 * quantity as f64 / FLOAT_SCALAR as f64;
+    quote_units / quote_coin.scalar as f64
+}
+
+/// Encodes a `Decimal` price into the on-chain price representation of a pool.
+#[cfg(feature = "decimal")]
+pub(crate) fn encode_price_decimal(price: Decimal, base_coin: &Coin, quote_coin: &Coin) -> Result<u64> {
+    price
+        .checked_mul(Decimal::from(FLOAT_SCALAR))
+        .and_then(|p| p.checked_mul(Decimal::from(quote_coin.scalar)))
+        .and_then(|p| p.checked_div(Decimal::from(base_coin.scalar)))
+        .and_then(|p| p.round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero).to_u64())
+        .ok_or_else(|| anyhow!("Price {} is out of range for the pool", price))
+}
+
+/// Encodes a `Decimal` base quantity into on-chain base units.
+#[cfg(feature = "decimal")]
+pub(crate) fn encode_quantity_decimal(quantity: Decimal, base_coin: &Coin) -> Result<u64> {
+    quantity
+        .checked_mul(Decimal::from(base_coin.scalar))
+        .and_then(|q| q.round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero).to_u64())
+        .ok_or_else(|| anyhow!("Quantity {} is out of range for the base coin", quantity))
+}
+
+/// A pool an order is placed in, with its coins resolved.
+pub(crate) struct OrderPool {
+    /// The configuration key of the pool, None for a pool given inline.
+    pub key: Option<String>,
+    pub address: String,
+    pub base_coin: Coin,
+    pub quote_coin: Coin,
+}
+
+impl OrderPool {
+    /// Resolves a configured pool and its coins.
+    pub fn from_config(config: &DeepBookConfig, pool_key: &str) -> Result<Self> {
+        let pool = config.require_pool(pool_key)?;
+        let base_coin = config.require_coin(&pool.base_coin)?;
+        let quote_coin = config.require_coin(&pool.quote_coin)?;
+
+        Ok(Self {
+            key: Some(pool_key.to_string()),
+            address: pool.address.clone(),
+            base_coin: base_coin.clone(),
+            quote_coin: quote_coin.clone(),
+        })
+    }
+
+    /// Builds a pool that isn't in the configuration from its id and coin details.
+    pub fn from_parts(pool_id: &str, base_type: &str, quote_type: &str, base_scalar: u64, quote_scalar: u64) -> Self {
+        let coin = |type_: &str, scalar: u64| Coin {
+            address: type_.split("::").next().unwrap_or_default().to_string(),
+            type_: type_.to_string(),
+            scalar,
+        };
+
+        Self {
+            key: None,
+            address: pool_id.to_string(),
+            base_coin: coin(base_type, base_scalar),
+            quote_coin: coin(quote_type, quote_scalar),
+        }
+    }
+
+    /// Parses the base and quote coin types.
+    pub fn coin_types(&self) -> Result<(TypeTag, TypeTag)> {
+        let base_coin_type = TypeTag::from_str(&self.base_coin.type_)
+            .with_context(|| format!("Failed to parse base coin type: {}", self.base_coin.type_))?;
+        let quote_coin_type = TypeTag::from_str(&self.quote_coin.type_)
+            .with_context(|| format!("Failed to parse quote coin type: {}", self.quote_coin.type_))?;
+
+        Ok((base_coin_type, quote_coin_type))
+    }
+
+    /// The key of the pool, or its address for a pool given inline.
+    fn label(&self) -> &str {
+        self.key.as_deref().unwrap_or(&self.address)
+    }
+}
+
+/// A limit order with its defaults resolved and price/quantity in on-chain units.
+pub(crate) struct EncodedLimitOrder {
+    pub client_order_id: u64,
+    pub order_type: OrderType,
+    pub self_matching_option: SelfMatchingOptions,
+    pub input_price: u64,
+    pub input_quantity: u64,
+    pub is_bid: bool,
+    pub pay_with_deep: bool,
+    pub expiration: u64,
+}
+
+/// Encodes a ladder order with `client_order_id`, resolving its unset fields from the ladder's
+/// `options`. Orders without a fee mode pay in DEEP, unless no DEEP coin is configured.
+pub(crate) fn encode_ladder_order(
+    client_order_id: u64,
+    spec: &OrderSpec,
+    pool: &OrderPool,
+    options: &PlaceOrderOptions,
+    encoder: &dyn PriceEncoder,
+    default_self_matching: SelfMatchingOptions,
+    config: &DeepBookConfig,
+) -> EncodedLimitOrder {
+    EncodedLimitOrder {
+        client_order_id,
+        order_type: spec.order_type.or(options.order_type).unwrap_or(OrderType::NoRestriction),
+        self_matching_option: resolve_self_matching(options.self_matching_option, default_self_matching),
+        input_price: encoder
+            .encode_price(spec.price, &pool.base_coin, &pool.quote_coin, options.rounding_mode, spec.is_bid),
+        input_quantity: encoder.encode_quantity(spec.quantity, &pool.base_coin, options.rounding_mode),
+        is_bid: spec.is_bid,
+        pay_with_deep: spec
+            .pay_with_deep
+            .or(options.pay_with_deep)
+            .or(options.fee_mode.fixed())
+            .unwrap_or(config.deep_coin().is_some()),
+        expiration: resolve_expiration(options.expiration, config),
+    }
+}
+
+/// Appends the `pool::place_limit_order` call to the PTB, returning its `OrderInfo` result.
+/// `objects` are the pool, balance manager, trade proof and clock arguments.
+pub(crate) fn append_place_limit_order(
+    ptb: &mut ProgrammableTransactionBuilder,
+    package_id: &str,
+    pool: &OrderPool,
+    objects: [Argument; 4],
+    order: &EncodedLimitOrder,
+) -> Result<Argument> {
+    let [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument] = objects;
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+
+    let client_order_id_pure = ptb.pure(order.client_order_id)
+        .with_context(|| "Failed to prepare client_order_id pure argument")?;
+    let order_type_pure = ptb.pure(order.order_type.as_u8())
+        .with_context(|| "Failed to prepare order_type pure argument")?;
+    let self_matching_option_pure = ptb.pure(order.self_matching_option.as_u8())
+        .with_context(|| "Failed to prepare self_matching_option pure argument")?;
+    let input_price_pure = ptb.pure(order.input_price)
+        .with_context(|| "Failed to prepare input_price pure argument")?;
+    let input_quantity_pure = ptb.pure(order.input_quantity)
+        .with_context(|| "Failed to prepare input_quantity pure argument")?;
+    let is_bid_pure = ptb.pure(order.is_bid)
+        .with_context(|| "Failed to prepare is_bid pure argument")?;
+    let pay_with_deep_pure = ptb.pure(order.pay_with_deep)
+        .with_context(|| "Failed to prepare pay_with_deep pure argument")?;
+    let expiration_pure = ptb.pure(order.expiration)
+        .with_context(|| "Failed to prepare expiration pure argument")?;
+
+    // Add the programmable Move call
+    Ok(ptb.programmable_move_call(
+        ObjectID::from_hex_literal(package_id)?,
+        Identifier::new("pool")?,
+        Identifier::new("place_limit_order")?,
+        vec![base_coin_type, quote_coin_type],
+        vec![
+            pool_argument,
+            manager_argument,
+            trade_proof_argument,
+            client_order_id_pure,
+            order_type_pure,
+            self_matching_option_pure,
+            input_price_pure,
+            input_quantity_pure,
+            is_bid_pure,
+            pay_with_deep_pure,
+            expiration_pure,
+            sui_clock_argument,
+        ],
+    ))
+}
+
+/// The `CallArg`s of a `pool::place_limit_order` call, in call order, for PTBs built by another library.
+/// `objects` are the pool, balance manager and clock object arguments. The trade proof is the
+/// result of a Move call, not a `CallArg`, so it is left out: it goes right after the manager.
+pub(crate) fn place_limit_order_call_args(objects: [ObjectArg; 3], order: &EncodedLimitOrder) -> Result<Vec<CallArg>> {
+    let [pool_object, manager_object, sui_clock_object] = objects;
+    let pure = |bytes: bcs::Result<Vec<u8>>, name: &str| {
+        bytes.map(CallArg::Pure).with_context(|| format!("Failed to serialize {} pure argument", name))
+    };
+
+    Ok(vec![
+        CallArg::Object(pool_object),
+        CallArg::Object(manager_object),
+        pure(bcs::to_bytes(&order.client_order_id), "client_order_id")?,
+        pure(bcs::to_bytes(&order.order_type.as_u8()), "order_type")?,
+        pure(bcs::to_bytes(&order.self_matching_option.as_u8()), "self_matching_option")?,
+        pure(bcs::to_bytes(&order.input_price), "input_price")?,
+        pure(bcs::to_bytes(&order.input_quantity), "input_quantity")?,
+        pure(bcs::to_bytes(&order.is_bid), "is_bid")?,
+        pure(bcs::to_bytes(&order.pay_with_deep), "pay_with_deep")?,
+        pure(bcs::to_bytes(&order.expiration), "expiration")?,
+        CallArg::Object(sui_clock_object),
+    ])
+}
+
+/// Appends a `pool::pool_book_params` call per pool, resolving all pools with a single RPC.
+/// Pools that are missing or not shared are skipped and get no command.
+///
+/// # Returns
+/// The keys of the pools a command was added for, in command order.
+pub(crate) async fn append_available_pool_book_params<F: ObjectFetcher + ?Sized>(
+    fetcher: &F,
+    config: &DeepBookConfig,
+    ptb: &mut ProgrammableTransactionBuilder,
+    pool_keys: &[&str],
+) -> Result<Vec<String>> {
+    let pools = pool_keys
+        .iter()
+        .map(|pool_key| OrderPool::from_config(config, pool_key))
+        .collect::<Result<Vec<_>>>()?;
+    let pool_addresses: Vec<_> = pools.iter().map(|pool| (pool.address.as_str(), false)).collect();
+    let pool_arguments = prepare_available_shared_object_arguments(fetcher, ptb, &pool_addresses)
+        .await.with_context(|| "Failed to prepare pool arguments")?;
+
+    let mut included = Vec::new();
+    for ((pool_key, pool), pool_argument) in pool_keys.iter().zip(&pools).zip(pool_arguments) {
+        let Some(pool_argument) = pool_argument else {
+            continue;
+        };
+        let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+
+        let type_arguments = vec![base_coin_type, quote_coin_type];
+        deepbook_move_call(ptb, config, "pool", "pool_book_params", type_arguments, vec![pool_argument])?;
+        included.push(pool_key.to_string());
+    }
+
+    Ok(included)
+}
+
+/// Appends a requote to the PTB: `pool::cancel_all_orders`, `pool::withdraw_settled_amounts`
+/// and one `pool::place_limit_order` per order, all sharing the same pool, manager, trade proof
+/// and clock arguments. Returns the `OrderInfo` result of each placement, in order.
+pub(crate) fn append_requote(
+    ptb: &mut ProgrammableTransactionBuilder,
+    package_id: &str,
+    pool: &OrderPool,
+    objects: [Argument; 4],
+    orders: &[EncodedLimitOrder],
+) -> Result<Vec<Argument>> {
+    let [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument] = objects;
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+    let package_id_object = ObjectID::from_hex_literal(package_id)?;
+
+    ptb.programmable_move_call(
+        package_id_object,
+        Identifier::new("pool")?,
+        Identifier::new("cancel_all_orders")?,
+        vec![base_coin_type.clone(), quote_coin_type.clone()],
+        vec![pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+    );
+    ptb.programmable_move_call(
+        package_id_object,
+        Identifier::new("pool")?,
+        Identifier::new("withdraw_settled_amounts")?,
+        vec![base_coin_type, quote_coin_type],
+        vec![pool_argument, manager_argument, trade_proof_argument],
+    );
+
+    orders
+        .iter()
+        .map(|order| append_place_limit_order(ptb, package_id, pool, objects, order))
+        .collect()
+}
+
+/// Appends the `pool::cancel_order` call to the PTB.
+/// `objects` are the pool, balance manager, trade proof and clock arguments.
+pub(crate) fn append_cancel_order(
+    ptb: &mut ProgrammableTransactionBuilder,
+    package_id: &str,
+    pool: &OrderPool,
+    objects: [Argument; 4],
+    order_id: u128,
+) -> Result<()> {
+    let [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument] = objects;
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+
+    let order_id_pure = ptb.pure(order_id)
+        .with_context(|| "Failed to prepare order_id pure argument")?;
+
+    ptb.programmable_move_call(
+        ObjectID::from_hex_literal(package_id)?,
+        Identifier::new("pool")?,
+        Identifier::new("cancel_order")?,
+        vec![base_coin_type, quote_coin_type],
+        vec![
+            pool_argument,
+            manager_argument,
+            trade_proof_argument,
+            order_id_pure,
+            sui_clock_argument,
+        ],
+    );
+
+    Ok(())
+}
+
+/// Appends the `pool::modify_order` call to the PTB, reducing the quantity of an order to
+/// `new_quantity`. `objects` are the pool, balance manager, trade proof and clock arguments.
+pub(crate) fn append_modify_order(
+    ptb: &mut ProgrammableTransactionBuilder,
+    package_id: &str,
+    pool: &OrderPool,
+    objects: [Argument; 4],
+    order_id: u128,
+    new_quantity: u64,
+) -> Result<()> {
+    let [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument] = objects;
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+
+    let order_id_pure = ptb.pure(order_id)
+        .with_context(|| "Failed to prepare order_id pure argument")?;
+    let new_quantity_pure = ptb.pure(new_quantity)
+        .with_context(|| "Failed to prepare new_quantity pure argument")?;
+
+    ptb.programmable_move_call(
+        ObjectID::from_hex_literal(package_id)?,
+        Identifier::new("pool")?,
+        Identifier::new("modify_order")?,
+        vec![base_coin_type, quote_coin_type],
+        vec![
+            pool_argument,
+            manager_argument,
+            trade_proof_argument,
+            order_id_pure,
+            new_quantity_pure,
+            sui_clock_argument,
+        ],
+    );
+
+    Ok(())
+}
+
+/// Appends the resize of an open order to `new_quantity`, its new total quantity filled part
+/// included: a `pool::modify_order` when shrinking, otherwise a `pool::cancel_order` followed by
+/// a `pool::place_limit_order` of the unfilled `new_quantity` at the same price and side.
+/// `objects` are the pool, balance manager, trade proof and clock arguments.
+///
+/// # Returns
+/// The `OrderInfo` result of the replacement when growing, None when shrinking.
+pub(crate) fn append_resize_order(
+    ptb: &mut ProgrammableTransactionBuilder,
+    package_id: &str,
+    pool: &OrderPool,
+    objects: [Argument; 4],
+    order: &Order,
+    new_quantity: u64,
+    self_matching_option: SelfMatchingOptions,
+) -> Result<Option<Argument>> {
+    if new_quantity <= order.filled_quantity {
+        bail!(
+            "New quantity {} of order {} must exceed its filled quantity {}",
+            new_quantity, order.order_id, order.filled_quantity,
+        );
+    }
+    if new_quantity == order.quantity {
+        bail!("Order {} already has quantity {}", order.order_id, new_quantity);
+    }
+
+    if new_quantity < order.quantity {
+        append_modify_order(ptb, package_id, pool, objects, order.order_id, new_quantity)?;
+        return Ok(None);
+    }
+
+    let replacement = EncodedLimitOrder {
+        input_quantity: new_quantity - order.filled_quantity,
+        ..replacement_order(order, order.expire_timestamp, self_matching_option)
+    };
+    append_cancel_order(ptb, package_id, pool, objects, order.order_id)?;
+    append_place_limit_order(ptb, package_id, pool, objects, &replacement).map(Some)
+}
+
+/// Appends a `pool::swap_exact_*` call, returning its `(base, quote, deep)` output coins.
+/// `arguments` are the pool, input coin, DEEP coin, minimum output and clock arguments.
+pub(crate) fn append_swap(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    function: &str,
+    base_coin: &Coin,
+    quote_coin: &Coin,
+    arguments: [Argument; 5],
+) -> Result<(Argument, Argument, Argument)> {
+    let base_coin_type = TypeTag::from_str(&base_coin.type_)
+        .with_context(|| format!("Failed to parse base coin type: {}", base_coin.type_))?;
+    let quote_coin_type = TypeTag::from_str(&quote_coin.type_)
+        .with_context(|| format!("Failed to parse quote coin type: {}", quote_coin.type_))?;
+
+    let result = deepbook_move_call(ptb, config, "pool", function, vec![base_coin_type, quote_coin_type], arguments.to_vec())?;
+
+    Ok((nested_result(result, 0)?, nested_result(result, 1)?, nested_result(result, 2)?))
+}
+
+/// Appends a call to `pool::<function>` with the pool's coin type arguments.
+/// The call takes the pool, then `extra_args` in order, then the clock if given.
+pub(crate) fn append_raw_pool_call(
+    ptb: &mut ProgrammableTransactionBuilder,
+    package_id: &str,
+    pool: &OrderPool,
+    function: &str,
+    pool_argument: Argument,
+    extra_args: Vec<CallArg>,
+    sui_clock_argument: Option<Argument>,
+) -> Result<Argument> {
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+
+    let mut arguments = vec![pool_argument];
+    for extra_arg in extra_args {
+        arguments.push(ptb.input(extra_arg).with_context(|| format!("Failed to prepare argument for '{}'", function))?);
+    }
+    arguments.extend(sui_clock_argument);
+
+    Ok(ptb.programmable_move_call(
+        ObjectID::from_hex_literal(package_id)?,
+        Identifier::new("pool")?,
+        Identifier::new(function).with_context(|| format!("Invalid identifier for '{}'", function))?,
+        vec![base_coin_type, quote_coin_type],
+        arguments,
+    ))
+}
+
+/// Builds the DeepBook pool calls of a PTB.
+///
+/// Builders append their commands to the caller's PTB and return the result `Argument`s of
+/// the calls that have one, e.g. the `OrderInfo` of a placed order or the output coins of a
+/// swap, so DeepBook calls can be interleaved with other Move calls of the same PTB. Coins
+/// returned by a builder must be consumed by a later command, e.g. transferred or deposited,
+/// before the PTB ends.
 pub struct DeepBookContract {
     client: Arc<SuiClient>,
     config: Arc<DeepBookConfig>,
-    balance_manager_contract: Arc<BalanceManagerContract>
+    balance_manager_contract: Arc<BalanceManagerContract>,
+    default_self_matching: SelfMatchingOptions,
+    /// Book parameters loaded with `DeepBookClient::load_all_pool_params`, by pool key.
+    pool_params: RwLock<HashMap<String, PoolBookParams>>,
+    /// Encoders of the pools not encoded with `DefaultPriceEncoder`, by pool key.
+    price_encoders: HashMap<String, Arc<dyn PriceEncoder>>,
 }
 
-impl DeepBookContract {
-    pub fn new(client: Arc<SuiClient>, config: Arc<DeepBookConfig>, balance_manager_contract: Arc<BalanceManagerContract>) -> Self {
-        DeepBookContract { client, config, balance_manager_contract }
+impl DeepBookContract {
+    pub fn new(client: Arc<SuiClient>, config: Arc<DeepBookConfig>, balance_manager_contract: Arc<BalanceManagerContract>) -> Self {
+        DeepBookContract {
+            client,
+            config,
+            balance_manager_contract,
+            default_self_matching: SelfMatchingOptions::default(),
+            pool_params: RwLock::new(HashMap::new()),
+            price_encoders: HashMap::new(),
+        }
+    }
+
+    /// Cache pool book parameters, used to validate orders before they are built.
+    pub fn cache_pool_params(&self, pool_params: HashMap<String, PoolBookParams>) {
+        self.pool_params
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .extend(pool_params);
+    }
+
+    /// The cached book parameters of a pool, if loaded.
+    pub fn cached_pool_params(&self, pool_key: &str) -> Option<PoolBookParams> {
+        cached_params(&self.pool_params, pool_key)
+    }
+
+    /// Set the self-matching option used when an order is placed without one.
+    pub fn set_default_self_matching(&mut self, self_matching_option: SelfMatchingOptions) {
+        self.default_self_matching = self_matching_option;
+    }
+
+    /// Encode the prices and quantities of the orders of a pool with `encoder`.
+    pub fn set_price_encoder(&mut self, pool_key: &str, encoder: Arc<dyn PriceEncoder>) {
+        self.price_encoders.insert(pool_key.to_string(), encoder);
+    }
+
+    /// The encoder of the prices and quantities of a pool, `DefaultPriceEncoder` unless set.
+    pub fn price_encoder(&self, pool_key: &str) -> &dyn PriceEncoder {
+        match self.price_encoders.get(pool_key) {
+            Some(encoder) => encoder.as_ref(),
+            None => &DefaultPriceEncoder,
+        }
+    }
+
+    /// The encoder of a resolved pool. Pools given inline use `DefaultPriceEncoder`.
+    fn pool_encoder(&self, pool: &OrderPool) -> &dyn PriceEncoder {
+        match pool.key.as_deref() {
+            Some(pool_key) => self.price_encoder(pool_key),
+            None => &DefaultPriceEncoder,
+        }
+    }
+
+    /// The self-matching option used when an order is placed without one.
+    pub fn default_self_matching(&self) -> SelfMatchingOptions {
+        self.default_self_matching
+    }
+
+    /// Place a limit order in the given pool with specified parameters.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `expiration` - Expiration timestamp for the order.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the `pool::place_limit_order` call, to pass to later commands.
+    pub async fn place_limit_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        expiration: Option<u64>,
+        order_type: Option<OrderType>,
+        self_matching_option: Option<SelfMatchingOptions>,
+        pay_with_deep: Option<bool>,
+    ) -> Result<Argument> {
+        let options = PlaceOrderOptions {
+            expiration,
+            order_type,
+            self_matching_option,
+            pay_with_deep,
+            ..Default::default()
+        };
+
+        self.place_limit_order_with_options(
+            ptb,
+            pool_key,
+            manager_key,
+            client_order_id,
+            price,
+            quantity,
+            is_bid,
+            options,
+        ).await
+    }
+
+    /// Place a limit order in the given pool, with the optional parameters given as `PlaceOrderOptions`.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `options` - Optional order parameters, including the rounding mode.
+    ///
+    /// The processed order is reported in `ExecutionOutcome::order_info` once executed; for an
+    /// `ImmediateOrCancel` order it holds the executed quantity and the `Canceled` status of the remainder.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the `pool::place_limit_order` call, to pass to later commands.
+    pub async fn place_limit_order_with_options(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<Argument> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+
+        self.place_limit_order_on(
+            ptb,
+            &pool,
+            manager_key,
+            client_order_id,
+            price,
+            quantity,
+            is_bid,
+            options,
+        ).await
+    }
+
+    /// Place a limit order in a pool that isn't in the configuration, e.g. a newly created one.
+    /// The pool and coin details are given inline instead of being looked up by key.
+    ///
+    /// # Arguments
+    /// * `pool_id` - The object id of the pool.
+    /// * `base_type` - The base coin type, e.g. `0x2::sui::SUI`.
+    /// * `quote_type` - The quote coin type.
+    /// * `base_scalar` - The scalar of the base coin, e.g. `1_000_000_000` for 9 decimals.
+    /// * `quote_scalar` - The scalar of the quote coin.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `options` - Optional order parameters. `check_balance` needs a configured pool.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the `pool::place_limit_order` call, to pass to later commands.
+    pub async fn place_limit_order_raw(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_id: &str,
+        base_type: &str,
+        quote_type: &str,
+        base_scalar: u64,
+        quote_scalar: u64,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<Argument> {
+        let pool = OrderPool::from_parts(pool_id, base_type, quote_type, base_scalar, quote_scalar);
+
+        self.place_limit_order_on(
+            ptb,
+            &pool,
+            manager_key,
+            client_order_id,
+            price,
+            quantity,
+            is_bid,
+            options,
+        ).await
+    }
+
+    /// Encodes the price and quantity of an order with the pool's encoder, then rounds them to the
+    /// pool's tick and lot sizes if its book parameters are cached.
+    fn encode_order_units(
+        &self,
+        pool: &OrderPool,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: &PlaceOrderOptions,
+    ) -> (u64, u64) {
+        let encoder = self.pool_encoder(pool);
+        let input_price = encoder.encode_price(price, &pool.base_coin, &pool.quote_coin, options.rounding_mode, is_bid);
+        let input_quantity = encoder.encode_quantity(quantity, &pool.base_coin, options.rounding_mode);
+
+        match &pool.key {
+            Some(pool_key) => round_to_cached_params(
+                &self.pool_params,
+                pool_key,
+                options.rounding_mode,
+                (input_price, input_quantity),
+                is_bid,
+            ),
+            None => (input_price, input_quantity),
+        }
+    }
+
+    /// Encode, check and place a limit order in a resolved pool.
+    async fn place_limit_order_on(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool: &OrderPool,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<Argument> {
+        // Calculate input price and quantity
+        let (input_price, input_quantity) = self.encode_order_units(pool, price, quantity, is_bid, &options);
+
+        if options.verify_pool {
+            let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+            verify_pool_object(&self.client, &pool.address, &base_coin_type, &quote_coin_type)
+                .await.with_context(|| format!("Pool {} does not match the configuration", pool.label()))?;
+        }
+
+        if options.require_enabled_pool {
+            let pool_key = pool.key.as_deref()
+                .ok_or_else(|| anyhow!("Pool enabled check needs a configured pool, got {}", pool.address))?;
+            let pool_inner = fetch_pool_inner_fields(&self.client, &self.config, pool_key)
+                .await.with_context(|| format!("Failed to fetch state of pool: {}", pool_key))?;
+            let allowed_versions = allowed_versions_from_json(&pool_inner)
+                .with_context(|| format!("Failed to decode allowed versions of pool: {}", pool_key))?;
+            ensure_pool_enabled(pool_key, &allowed_versions)?;
+        }
+
+        if options.check_balance {
+            let pool_key = pool.key.as_deref()
+                .ok_or_else(|| anyhow!("Balance check needs a configured pool, got {}", pool.address))?;
+            self.check_free_balance(pool_key, manager_key, input_price, input_quantity, is_bid)
+                .await.with_context(|| format!("Balance check failed for manager: {}", manager_key))?;
+        }
+
+        self.place_encoded_limit_order(
+            ptb,
+            pool,
+            manager_key,
+            client_order_id,
+            input_price,
+            input_quantity,
+            is_bid,
+            options,
+        ).await
+    }
+
+    /// Check that a balance manager can fund an order: quote for a bid, base for an ask.
+    /// Funds locked in open orders have already left the manager, so its balance is the free balance.
+    async fn check_free_balance(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        input_price: u64,
+        input_quantity: u64,
+        is_bid: bool,
+    ) -> Result<()> {
+        let pool = self.config.require_pool(pool_key)?;
+        let coin_key = if is_bid { &pool.quote_coin } else { &pool.base_coin };
+        let coin = self.config.require_coin(coin_key)?;
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        self.balance_manager_contract
+            .check_manager_balance(&mut ptb, manager_key, coin_key)
+            .await.with_context(|| "Failed to add check_manager_balance command to PTB")?;
+        self.locked_balance(&mut ptb, pool_key, manager_key)
+            .await.with_context(|| "Failed to add locked_balance command to PTB")?;
+
+        let response = dev_inspect_ptb(&self.client, &self.config, ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let free = parse_return_value_at::<u64>(&response, 0, 0)
+            .with_context(|| "Failed to parse manager balance")?;
+        let locked = parse_return_value_at::<u64>(&response, 1, if is_bid { 1 } else { 0 })
+            .with_context(|| "Failed to parse locked balance")?;
+
+        ensure_free_balance(required_balance(input_price, input_quantity, is_bid), free, locked, coin_key, coin)
+    }
+
+    /// Place a limit order with `rust_decimal::Decimal` price and quantity.
+    /// Identical to `place_limit_order`, but the scaling is done without `f64` rounding drift.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `expiration` - Expiration timestamp for the order.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the `pool::place_limit_order` call, to pass to later commands.
+    #[cfg(feature = "decimal")]
+    pub async fn place_limit_order_decimal(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: Decimal,
+        quantity: Decimal,
+        is_bid: bool,
+        expiration: Option<u64>,
+        order_type: Option<OrderType>,
+        self_matching_option: Option<SelfMatchingOptions>,
+        pay_with_deep: Option<bool>,
+    ) -> Result<Argument> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+
+        // Calculate input price and quantity
+        let input_price = encode_price_decimal(price, &pool.base_coin, &pool.quote_coin)?;
+        let input_quantity = encode_quantity_decimal(quantity, &pool.base_coin)?;
+        let options = PlaceOrderOptions {
+            expiration,
+            order_type,
+            self_matching_option,
+            pay_with_deep,
+            ..Default::default()
+        };
+
+        self.place_encoded_limit_order(
+            ptb,
+            &pool,
+            manager_key,
+            client_order_id,
+            input_price,
+            input_quantity,
+            is_bid,
+            options,
+        ).await
+    }
+
+    /// Decide whether an order pays its fee in DEEP: `pay_with_deep` if set, otherwise per
+    /// `fee_mode`. `FeeMode::Auto` pays in DEEP for pools that aren't in the configuration.
+    async fn resolve_pay_with_deep(
+        &self,
+        pool: &OrderPool,
+        manager_key: &str,
+        input_price: u64,
+        input_quantity: u64,
+        options: &PlaceOrderOptions,
+    ) -> Result<bool> {
+        if let Some(pay_with_deep) = options.pay_with_deep.or(options.fee_mode.fixed()) {
+            if pay_with_deep {
+                self.config.require_deep_coin()?;
+            }
+            return Ok(pay_with_deep);
+        }
+        // Without DEEP configured, `FeeMode::Auto` pays fees in the input coin
+        let Some(deep_key) = self.config.deep_coin_key() else {
+            return Ok(false);
+        };
+        let Some(pool_key) = pool.key.as_deref() else {
+            return Ok(true);
+        };
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        self.balance_manager_contract
+            .check_manager_balance(&mut ptb, manager_key, deep_key)
+            .await.with_context(|| "Failed to add check_manager_balance command to PTB")?;
+        self.pool_trade_params(&mut ptb, pool_key)
+            .await.with_context(|| "Failed to add pool_trade_params command to PTB")?;
+        self.get_order_deep_price(&mut ptb, pool_key)
+            .await.with_context(|| "Failed to add get_order_deep_price command to PTB")?;
+
+        let response = dev_inspect_ptb(&self.client, &self.config, ptb)
+            .await
+            .with_context(|| "Failed to inspect transaction results")?;
+        let deep_balance = parse_return_value_at::<u64>(&response, 0, 0)
+            .with_context(|| "Failed to parse manager DEEP balance")?;
+        let taker_fee = parse_return_value_at::<u64>(&response, 1, 0)
+            .with_context(|| "Failed to parse taker fee")?;
+        let deep_price = parse_return_value_at::<OrderDeepPrice>(&response, 2, 0)
+            .with_context(|| "Failed to parse order DEEP price")?;
+
+        let deep_required = order_deep_required(input_price, input_quantity, taker_fee, &deep_price);
+        Ok(auto_pays_with_deep(deep_balance, deep_required))
+    }
+
+    /// Resolve the defaults of an order encoded in on-chain units and validate it against the
+    /// cached book parameters of the pool, if loaded.
+    fn encode_limit_order(
+        &self,
+        pool: &OrderPool,
+        client_order_id: &str,
+        input_price: u64,
+        input_quantity: u64,
+        is_bid: bool,
+        options: &PlaceOrderOptions,
+    ) -> Result<EncodedLimitOrder> {
+        let order = EncodedLimitOrder {
+            client_order_id: client_order_id.parse::<u64>()
+                .map_err(|e| anyhow!("Failed to parse client_order_id: {}", e))?,
+            order_type: options.order_type.unwrap_or(OrderType::NoRestriction),
+            self_matching_option: resolve_self_matching(options.self_matching_option, self.default_self_matching),
+            input_price,
+            input_quantity,
+            is_bid,
+            pay_with_deep: options.pay_with_deep.unwrap_or(true),
+            expiration: resolve_expiration(options.expiration, &self.config),
+        };
+
+        if let Some(pool_key) = &pool.key {
+            validate_with_cached_params(&self.pool_params, pool_key, input_price, input_quantity)?;
+        }
+
+        Ok(order)
+    }
+
+    /// Resolve the arguments of a limit order as `CallArg`s, for PTBs built with another library.
+    /// The arguments are in `pool::place_limit_order` call order, without the trade proof: it is
+    /// the result of a `balance_manager::generate_proof_as_owner` (or `_as_trader`) call, and
+    /// goes right after the balance manager. The pool coin types are the call's type arguments.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `client_order_id` - Unique identifier for the order.
+    /// * `price` - Price of the order.
+    /// * `quantity` - Quantity of the order.
+    /// * `is_bid` - Whether this is a bid order.
+    /// * `options` - Optional order parameters. Only those encoded in the call are applied.
+    ///
+    /// # Returns
+    /// The pool, manager, pure order arguments and clock, or an error.
+    pub async fn place_limit_order_args(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        client_order_id: &str,
+        price: f64,
+        quantity: f64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<Vec<CallArg>> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let (input_price, input_quantity) = self.encode_order_units(&pool, price, quantity, is_bid, &options);
+        let pay_with_deep = self
+            .resolve_pay_with_deep(&pool, manager_key, input_price, input_quantity, &options)
+            .await?;
+        let options = PlaceOrderOptions { pay_with_deep: Some(pay_with_deep), ..options };
+        let order = self.encode_limit_order(&pool, client_order_id, input_price, input_quantity, is_bid, &options)?;
+
+        // Resolve the object arguments in a scratch PTB, then read them back as inputs
+        let manager = self.config.require_balance_manager(manager_key)?;
+        let mut scratch = ProgrammableTransactionBuilder::new();
+        let clock = self.config.clock_object_arg();
+        let (pool_argument, manager_argument, sui_clock_argument) =
+            prepare_order_arguments_for(&*self.client, &mut scratch, &pool.address, &manager.address, clock)
+                .await.with_context(|| "Failed to prepare order arguments")?;
+        let inputs = scratch.finish().inputs;
+        let object_arg = |argument: Argument| match argument {
+            Argument::Input(index) => match inputs.get(index as usize) {
+                Some(CallArg::Object(object)) => Ok(*object),
+                _ => Err(anyhow!("Input {} is not an object argument", index)),
+            },
+            other => Err(anyhow!("Expected an input argument, got {:?}", other)),
+        };
+
+        place_limit_order_call_args(
+            [object_arg(pool_argument)?, object_arg(manager_argument)?, object_arg(sui_clock_argument)?],
+            &order,
+        )
+    }
+
+    /// Place a limit order whose price and quantity are already encoded in on-chain units.
+    async fn place_encoded_limit_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool: &OrderPool,
+        manager_key: &str,
+        client_order_id: &str,
+        input_price: u64,
+        input_quantity: u64,
+        is_bid: bool,
+        options: PlaceOrderOptions,
+    ) -> Result<Argument> {
+        let pay_with_deep = self
+            .resolve_pay_with_deep(pool, manager_key, input_price, input_quantity, &options)
+            .await?;
+        let options = PlaceOrderOptions { pay_with_deep: Some(pay_with_deep), ..options };
+        let order = self.encode_limit_order(pool, client_order_id, input_price, input_quantity, is_bid, &options)?;
+
+        // Prepare arguments for PTB
+        let manager = self.config.require_balance_manager(manager_key)?;
+        let clock = self.config.clock_object_arg();
+        let (pool_argument, manager_argument, sui_clock_argument) =
+            prepare_order_arguments_for(&*self.client, ptb, &pool.address, &manager.address, clock)
+                .await.with_context(|| "Failed to prepare order arguments")?;
+
+        let trade_proof_argument = self.balance_manager_contract
+            .generate_proof(ptb, manager_key, manager_argument)
+            .await.with_context(|| "Failed to generate trade proof")?;
+
+        append_place_limit_order(
+            ptb,
+            &self.config.deepbook_package_id,
+            pool,
+            [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+            &order,
+        )
+    }
+
+    /// Swap an exact amount of the base coin of a pool for its quote coin, as a taker without a
+    /// balance manager. Fees are paid from `deep_in`, or from the input coin if the pool is whitelisted.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `base_in` - The base coin to sell, e.g. the output of an earlier command.
+    /// * `deep_in` - The DEEP coin paying the fees.
+    /// * `min_quote_out` - The minimum quote amount to receive, or the call aborts.
+    ///
+    /// # Returns
+    /// The `(base, quote, deep)` output coins: the unswapped base, the bought quote and the unused DEEP.
+    pub async fn swap_exact_base_for_quote(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        base_in: Argument,
+        deep_in: Argument,
+        min_quote_out: f64,
+    ) -> Result<(Argument, Argument, Argument)> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let min_out_input = pool.quote_coin.to_units(min_quote_out);
+
+        self.swap_exact(ptb, &pool, "swap_exact_base_for_quote", base_in, deep_in, min_out_input).await
+    }
+
+    /// Swap an exact amount of the quote coin of a pool for its base coin, as a taker without a
+    /// balance manager. Fees are paid from `deep_in`, or from the input coin if the pool is whitelisted.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `quote_in` - The quote coin to spend, e.g. the output of an earlier command.
+    /// * `deep_in` - The DEEP coin paying the fees.
+    /// * `min_base_out` - The minimum base amount to receive, or the call aborts.
+    ///
+    /// # Returns
+    /// The `(base, quote, deep)` output coins: the bought base, the unspent quote and the unused DEEP.
+    pub async fn swap_exact_quote_for_base(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        quote_in: Argument,
+        deep_in: Argument,
+        min_base_out: f64,
+    ) -> Result<(Argument, Argument, Argument)> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let min_out_input = pool.base_coin.to_units(min_base_out);
+
+        self.swap_exact(ptb, &pool, "swap_exact_quote_for_base", quote_in, deep_in, min_out_input).await
+    }
+
+    async fn swap_exact(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool: &OrderPool,
+        function: &str,
+        coin_in: Argument,
+        deep_in: Argument,
+        min_out_input: u64,
+    ) -> Result<(Argument, Argument, Argument)> {
+        let pool_argument = prepare_shared_object_arguments(&*self.client, ptb, &[(&pool.address, true)])
+            .await.with_context(|| "Failed to prepare pool argument")?[0];
+        let min_out = ptb.pure(min_out_input)
+            .with_context(|| "Failed to prepare min_out pure argument")?;
+        let sui_clock_argument = shared_clock_argument(ptb, self.config.clock_object_arg())?;
+
+        append_swap(
+            ptb,
+            &self.config,
+            function,
+            &pool.base_coin,
+            &pool.quote_coin,
+            [pool_argument, coin_in, deep_in, min_out, sui_clock_argument],
+        )
+    }
+
+    /// Cancel all open orders of a balance manager in a pool, withdraw its settled amounts
+    /// and place a fresh ladder of limit orders, atomically in the same PTB.
+    /// The pool and manager are prepared once and a single trade proof is generated.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `new_ladder` - The new orders, each with an optional order type and fee mode of its own.
+    /// * `client_order_ids` - The client order id of each order of the ladder, in ladder order.
+    /// * `options` - Optional order parameters applied to every order of the ladder.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of each placement, in ladder order.
+    pub async fn requote(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        new_ladder: &[OrderSpec],
+        client_order_ids: &[u64],
+        options: PlaceOrderOptions,
+    ) -> Result<Vec<Argument>> {
+        if client_order_ids.len() != new_ladder.len() {
+            bail!("Got {} client order ids for a ladder of {} orders", client_order_ids.len(), new_ladder.len());
+        }
+
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let orders = new_ladder
+            .iter()
+            .zip(client_order_ids)
+            .enumerate()
+            .map(|(position, (spec, client_order_id))| {
+                let mut order = encode_ladder_order(
+                    *client_order_id,
+                    spec,
+                    &pool,
+                    &options,
+                    self.pool_encoder(&pool),
+                    self.default_self_matching,
+                    &self.config,
+                );
+                (order.input_price, order.input_quantity) = round_to_cached_params(
+                    &self.pool_params,
+                    pool_key,
+                    options.rounding_mode,
+                    (order.input_price, order.input_quantity),
+                    order.is_bid,
+                );
+                if order.pay_with_deep {
+                    self.config.require_deep_coin()?;
+                }
+                validate_with_cached_params(&self.pool_params, pool_key, order.input_price, order.input_quantity)
+                    .with_context(|| format!("Invalid ladder order at position {}", position + 1))?;
+
+                Ok(order)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (pool_argument, manager_argument, sui_clock_argument) =
+            prepare_order_arguments(&*self.client, &self.config, ptb, pool_key, manager_key)
+                .await.with_context(|| "Failed to prepare order arguments")?;
+        let trade_proof_argument = self.balance_manager_contract
+            .generate_proof(ptb, manager_key, manager_argument)
+            .await.with_context(|| "Failed to generate trade proof")?;
+
+        append_requote(
+            ptb,
+            &self.config.deepbook_package_id,
+            &pool,
+            [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+            &orders,
+        )
+    }
+
+    /// Get open orders for a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// The transaction block containing the open orders.
+    pub async fn account_open_orders(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<()> {
+        let pool = self.config.require_pool(pool_key)?;
+        let base_coin = self.config.require_coin(&pool.base_coin)?;
+        let quote_coin = self.config.require_coin(&pool.quote_coin)?;
+        let base_coin_type = TypeTag::from_str(&base_coin.type_)?;
+        let quote_coin_type = TypeTag::from_str(&quote_coin.type_)?;
+
+        let arguments = prepare_read_arguments(&*self.client, &self.config, ptb, pool_key, Some(manager_key))
+            .await.with_context(|| "Failed to prepare pool and manager arguments")?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("account_open_orders")?,
+            vec![base_coin_type, quote_coin_type],
+            arguments,
+        );
+
+        Ok(())
+    }
+
+    /// Get open orders for a balance manager in several pools, with one `account_open_orders`
+    /// command per pool in the order of `pool_keys`. The pools and the manager are resolved with a single RPC.
+    ///
+    /// # Arguments
+    /// * `pool_keys` - The keys to identify the pools.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn accounts_open_orders(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_keys: &[&str],
+        manager_key: &str,
+    ) -> Result<()> {
+        let manager = self.config.require_balance_manager(manager_key)?;
+        let mut objects = pool_keys
+            .iter()
+            .map(|pool_key| self.config
+                .require_pool(pool_key)
+                .map(|pool| (pool.address.as_str(), false)))
+            .collect::<Result<Vec<_>, _>>()?;
+        objects.push((manager.address.as_str(), false));
+        let arguments = prepare_shared_object_arguments(&*self.client, ptb, &objects)
+            .await.with_context(|| "Failed to prepare pool and manager arguments")?;
+        let manager_argument = arguments[pool_keys.len()];
+
+        for (pool_key, pool_argument) in pool_keys.iter().zip(arguments) {
+            let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+            ptb.programmable_move_call(
+                ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+                Identifier::new("pool")?,
+                Identifier::new("account_open_orders")?,
+                vec![base_coin_type, quote_coin_type],
+                vec![pool_argument, manager_argument],
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get the account information of a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn account(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let arguments = prepare_read_arguments(&*self.client, &self.config, ptb, pool_key, Some(manager_key))
+            .await.with_context(|| "Failed to prepare pool and manager arguments")?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("account")?,
+            vec![base_coin_type, quote_coin_type],
+            arguments,
+        );
+
+        Ok(())
+    }
+
+    /// Cancel an order of a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `order_id` - The protocol order id to cancel.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn cancel_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        order_id: u128,
+    ) -> Result<()> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+
+        let (pool_argument, manager_argument, sui_clock_argument) =
+            prepare_order_arguments(&*self.client, &self.config, ptb, pool_key, manager_key)
+                .await.with_context(|| "Failed to prepare order arguments")?;
+        let trade_proof_argument = self.balance_manager_contract
+            .generate_proof(ptb, manager_key, manager_argument)
+            .await.with_context(|| "Failed to generate trade proof")?;
+
+        append_cancel_order(
+            ptb,
+            &self.config.deepbook_package_id,
+            &pool,
+            [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+            order_id,
+        )
+    }
+
+    /// Cancel several orders of a balance manager in one pool, sharing one trade proof.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `order_ids` - The protocol order ids to cancel.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn cancel_orders(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        order_ids: &[u128],
+    ) -> Result<()> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+
+        let (pool_argument, manager_argument, sui_clock_argument) =
+            prepare_order_arguments(&*self.client, &self.config, ptb, pool_key, manager_key)
+                .await.with_context(|| "Failed to prepare order arguments")?;
+        let trade_proof_argument = self.balance_manager_contract
+            .generate_proof(ptb, manager_key, manager_argument)
+            .await.with_context(|| "Failed to generate trade proof")?;
+
+        for &order_id in order_ids {
+            append_cancel_order(
+                ptb,
+                &self.config.deepbook_package_id,
+                &pool,
+                [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+                order_id,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Extend the expiry of an open order by canceling it and placing it again with the new
+    /// expiration. DeepBook can't change the expiry of an order in place, so this is a
+    /// cancel/replace, not a modify: the replacement gets a new order id and loses the
+    /// original's time priority. Price, side, remaining quantity, client order id and fee
+    /// asset are kept, and the replacement is post-only.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `order` - The open order, e.g. from `DeepBookClient::get_order`.
+    /// * `new_expiration` - The new expiration timestamp in milliseconds.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the `pool::place_limit_order` call, to pass to later commands.
+    pub async fn extend_order_expiry(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        order: &Order,
+        new_expiration: u64,
+    ) -> Result<Argument> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let replacement = replacement_order(order, new_expiration, self.default_self_matching);
+
+        let (pool_argument, manager_argument, sui_clock_argument) =
+            prepare_order_arguments(&*self.client, &self.config, ptb, pool_key, manager_key)
+                .await.with_context(|| "Failed to prepare order arguments")?;
+        let trade_proof_argument = self.balance_manager_contract
+            .generate_proof(ptb, manager_key, manager_argument)
+            .await.with_context(|| "Failed to generate trade proof")?;
+        let objects = [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument];
+
+        append_cancel_order(ptb, &self.config.deepbook_package_id, &pool, objects, order.order_id)?;
+        append_place_limit_order(ptb, &self.config.deepbook_package_id, &pool, objects, &replacement)
+    }
+
+    /// Resize an open order to `new_quantity`, its new total quantity in on-chain units, filled
+    /// part included. `pool::modify_order` can only reduce the quantity, so the two directions
+    /// differ: shrinking modifies the order atomically in place, keeping its id and time
+    /// priority, while growing cancels it and places a post-only replacement of the unfilled
+    /// quantity at the same price, side, expiry and fee asset, with a new order id.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `order` - The open order, e.g. from `DeepBookClient::get_order`.
+    /// * `new_quantity` - The new total quantity of the order in base units.
+    ///
+    /// # Returns
+    /// The `OrderInfo` result of the replacement order when growing, None when shrinking.
+    pub async fn resize_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        order: &Order,
+        new_quantity: u64,
+    ) -> Result<Option<Argument>> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+
+        let (pool_argument, manager_argument, sui_clock_argument) =
+            prepare_order_arguments(&*self.client, &self.config, ptb, pool_key, manager_key)
+                .await.with_context(|| "Failed to prepare order arguments")?;
+        let trade_proof_argument = self.balance_manager_contract
+            .generate_proof(ptb, manager_key, manager_argument)
+            .await.with_context(|| "Failed to generate trade proof")?;
+
+        append_resize_order(
+            ptb,
+            &self.config.deepbook_package_id,
+            &pool,
+            [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+            order,
+            new_quantity,
+            self.default_self_matching,
+        )
+    }
+
+    /// Get the balances of a balance manager locked in open orders of a pool.
+    /// Returns `(base, quote, deep)` in on-chain units.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn locked_balance(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let arguments = prepare_read_arguments(&*self.client, &self.config, ptb, pool_key, Some(manager_key))
+            .await.with_context(|| "Failed to prepare pool and manager arguments")?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("locked_balance")?,
+            vec![base_coin_type, quote_coin_type],
+            arguments,
+        );
+
+        Ok(())
+    }
+
+    /// Get the details of all open orders of a balance manager in a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn get_account_order_details(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let arguments = prepare_read_arguments(&*self.client, &self.config, ptb, pool_key, Some(manager_key))
+            .await.with_context(|| "Failed to prepare pool and manager arguments")?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("get_account_order_details")?,
+            vec![base_coin_type, quote_coin_type],
+            arguments,
+        );
+
+        Ok(())
+    }
+
+    /// Get an order of a pool by its protocol order id.
+    /// The call aborts if the order is not on the book.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `order_id` - The protocol order id.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn get_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        order_id: u128,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let pool_argument = prepare_pool_argument(&self.client, &self.config, ptb, pool_key)
+            .await.with_context(|| "Failed to prepare pool argument")?;
+        let order_id_pure = ptb.pure(order_id)
+            .with_context(|| "Failed to prepare order_id pure argument")?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("get_order")?,
+            vec![base_coin_type, quote_coin_type],
+            vec![pool_argument, order_id_pure],
+        );
+
+        Ok(())
+    }
+
+    /// Get the book parameters (tick size, lot size, min size) of several pools.
+    /// All pools are resolved with a single RPC and each gets its own command, in order.
+    ///
+    /// # Arguments
+    /// * `pool_keys` - The keys to identify the pools.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn pool_book_params(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_keys: &[&str],
+    ) -> Result<()> {
+        let pool_addresses = pool_keys
+            .iter()
+            .map(|pool_key| self.config
+                .require_pool(pool_key)
+                .map(|pool| (pool.address.as_str(), false)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pool_arguments = prepare_shared_object_arguments(&*self.client, ptb, &pool_addresses)
+            .await.with_context(|| "Failed to prepare pool arguments")?;
+
+        for (pool_key, pool_argument) in pool_keys.iter().zip(pool_arguments) {
+            let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+            ptb.programmable_move_call(
+                ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+                Identifier::new("pool")?,
+                Identifier::new("pool_book_params")?,
+                vec![base_coin_type, quote_coin_type],
+                vec![pool_argument],
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get the book parameters of several pools like `pool_book_params`, skipping pools whose
+    /// object is missing or not shared instead of failing.
+    ///
+    /// # Arguments
+    /// * `pool_keys` - The keys to identify the pools.
+    ///
+    /// # Returns
+    /// The keys of the pools a command was added for, in command order, or an error.
+    pub async fn available_pool_book_params(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_keys: &[&str],
+    ) -> Result<Vec<String>> {
+        append_available_pool_book_params(&*self.client, &self.config, ptb, pool_keys).await
+    }
+
+    /// Get the trade parameters (taker fee, maker fee, stake required) of a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn pool_trade_params(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let pool_argument = prepare_pool_argument(
+            &self.client,
+            &self.config,
+            ptb,
+            pool_key,
+        ).await.with_context(|| "Failed to prepare pool argument")?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("pool_trade_params")?,
+            vec![base_coin_type, quote_coin_type],
+            vec![pool_argument],
+        );
+
+        Ok(())
+    }
+
+    /// Get whether a pool is whitelisted, i.e. trades without fees and without DEEP.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn whitelisted(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let pool_argument = prepare_pool_argument(
+            &self.client,
+            &self.config,
+            ptb,
+            pool_key,
+        ).await.with_context(|| "Failed to prepare pool argument")?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("whitelisted")?,
+            vec![base_coin_type, quote_coin_type],
+            vec![pool_argument],
+        );
+
+        Ok(())
+    }
+
+    /// Add a DEEP price point to a pool from a reference pool trading DEEP, so the pool can
+    /// charge fees in DEEP. The reference pool defaults to `DeepBookConfig::reference_pool_for`.
+    ///
+    /// # Arguments
+    /// * `target_pool_key` - The key of the pool to add the price point to.
+    /// * `reference_pool_key` - The key of the DEEP reference pool, or None to resolve it.
+    ///
+    /// # Returns
+    /// The key of the reference pool used, or an error.
+    pub async fn add_deep_price_point(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        target_pool_key: &str,
+        reference_pool_key: Option<&str>,
+    ) -> Result<String> {
+        let reference_pool_key = match reference_pool_key {
+            Some(reference_pool_key) => reference_pool_key,
+            None => self.config
+                .reference_pool_for(target_pool_key)
+                .ok_or_else(|| anyhow!("No DEEP reference pool found for pool: {}", target_pool_key))?,
+        };
+        let target_pool = OrderPool::from_config(&self.config, target_pool_key)?;
+        let reference_pool = OrderPool::from_config(&self.config, reference_pool_key)?;
+        let (target_base_type, target_quote_type) = target_pool.coin_types()?;
+        let (reference_base_type, reference_quote_type) = reference_pool.coin_types()?;
+
+        let pool_arguments = prepare_shared_object_arguments(
+            &*self.client,
+            ptb,
+            &[(&target_pool.address, true), (&reference_pool.address, false)],
+        ).await.with_context(|| "Failed to prepare pool arguments")?;
+        let sui_clock_argument = shared_clock_argument(ptb, self.config.clock_object_arg())?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("add_deep_price_point")?,
+            vec![target_base_type, target_quote_type, reference_base_type, reference_quote_type],
+            vec![pool_arguments[0], pool_arguments[1], sui_clock_argument],
+        );
+
+        Ok(reference_pool_key.to_string())
+    }
+
+    /// Get the level-2 order book of a pool, `ticks` price levels away from the mid price on each side.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    /// * `ticks` - The number of ticks from the mid price to include on each side.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn get_level2_ticks_from_mid(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        ticks: u64,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let pool_argument = prepare_pool_argument(
+            &self.client,
+            &self.config,
+            ptb,
+            pool_key,
+        ).await.with_context(|| "Failed to prepare pool argument")?;
+        let ticks_pure = ptb.pure(ticks)
+            .with_context(|| "Failed to prepare ticks pure argument")?;
+        let sui_clock_argument = shared_clock_argument(ptb, self.config.clock_object_arg())?;
+
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("get_level2_ticks_from_mid")?,
+            vec![base_coin_type, quote_coin_type],
+            vec![pool_argument, ticks_pure, sui_clock_argument],
+        );
+
+        Ok(())
     }
 
-    /// Place a limit order in the given pool with specified parameters.
+    /// Get the mid price of a pool, the average of the best bid and ask.
+    /// The call aborts if either side of the book is empty.
     ///
     /// # Arguments
     /// * `pool_key` - The key to identify the pool.
-    /// * `manager_key` - The key of the balance manager.
-    /// * `client_order_id` - Unique identifier for the order.
-    /// * `price` - Price of the order.
-    /// * `quantity` - Quantity of the order.
-    /// * `is_bid` - Whether this is a bid order.
-    /// * `expiration` - Expiration timestamp for the order.
     ///
     /// # Returns
     /// Ok(()) on success, or an error.
-    pub async fn place_limit_order(
+    pub async fn mid_price(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
         pool_key: &str,
-        manager_key: &str,
-        client_order_id: &str,
-        price: f64,
-        quantity: f64,
-        is_bid: bool,
-        expiration: Option<u64>,
-        order_type: Option<OrderType>,
-        self_matching_option: Option<SelfMatchingOptions>,
-        pay_with_deep: Option<bool>,
     ) -> Result<()> {
-        // Resolve default values
-        let expiration = expiration.unwrap_or(MAX_TIMESTAMP);
-        let order_type = order_type.unwrap_or(OrderType::NoRestriction);
-        let self_matching_option = self_matching_option.unwrap_or(SelfMatchingOptions::SelfMatchingAllowed);
-        let pay_with_deep = pay_with_deep.unwrap_or(true);
-
-        // Resolve required configuration and types
-        let pool = self.config.get_pool(pool_key)
-            .with_context(|| format!("Pool not found for key: {}", pool_key))?;
-        let manager = self.config.get_balance_manager(manager_key)
-            .with_context(|| format!("BalanceManager not found for key: {}", manager_key))?;
-        let base_coin = self.config.get_coin(&pool.base_coin)
-            .with_context(|| format!("Base coin not found for key: {}", pool.base_coin))?;
-        let quote_coin = self.config.get_coin(&pool.quote_coin)
-            .with_context(|| format!("Quote coin not found for key: {}", pool.quote_coin))?;
-        let base_coin_type = TypeTag::from_str(&base_coin.type_)
-            .with_context(|| format!("Failed to parse base coin type: {}", base_coin.type_))?;
-        let quote_coin_type = TypeTag::from_str(&quote_coin.type_)
-            .with_context(|| format!("Failed to parse quote coin type: {}", quote_coin.type_))?;
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
 
-        // Calculate input price and quantity
-        let input_price = ((price * FLOAT_SCALAR as f64 * quote_coin.scalar as f64) / base_coin.scalar as f64).round() as u64;
-        let input_quantity = (quantity * base_coin.scalar as f64).round() as u64;
+        let pool_argument = prepare_read_arguments(&*self.client, &self.config, ptb, pool_key, None)
+            .await.with_context(|| "Failed to prepare pool argument")?[0];
+        let sui_clock_argument = shared_clock_argument(ptb, self.config.clock_object_arg())?;
 
-        // Prepare arguments for PTB
-        let pool_argument = prepare_pool_argument(&self.client, &self.config, ptb, pool_key)
-            .await.with_context(|| "Failed to prepare pool argument")?;
-        let manager_argument = prepare_balance_manager_argument(&self.client, &self.config, ptb, manager_key)
-            .await.with_context(|| "Failed to prepare manager argument")?;
-        let sui_clock_argument = prepare_sui_clock_argument(&self.client, ptb)
-            .await.with_context(|| "Failed to prepare SuiClock argument")?;
-
-        let trade_proof_argument = {
-            if let Some(trade_cap_id) = &manager.trade_cap {
-                let trade_cap_argument = prepare_imm_or_owned_object_argument(&self.client, ptb, trade_cap_id)
-                    .await.with_context(|| format!("Failed to prepare trade cap argument for key: {}", trade_cap_id))?;
-
-                self.balance_manager_contract.generate_proof_as_trader(ptb, manager_argument.clone(), trade_cap_argument)
-            } else {
-                self.balance_manager_contract.generate_proof_as_owner(ptb, manager_argument.clone())
-            }
-        };
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            Identifier::new("pool")?,
+            Identifier::new("mid_price")?,
+            vec![base_coin_type, quote_coin_type],
+            vec![pool_argument, sui_clock_argument],
+        );
+
+        Ok(())
+    }
+
+    /// Get the balances held by the vault of a pool.
+    ///
+    /// # Arguments
+    /// * `pool_key` - The key to identify the pool.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn vault_balances(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+    ) -> Result<()> {
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
+
+        let pool_argument = prepare_read_arguments(&*self.client, &self.config, ptb, pool_key, None)
+            .await.with_context(|| "Failed to prepare pool argument")?[0];
 
-        let client_order_id_u64: u64 = client_order_id.parse::<u64>()
-            .map_err(|e| anyhow!("Failed to parse client_order_id: {}", e))?;
-        let client_order_id_pure = ptb.pure(client_order_id_u64)
-            .with_context(|| "Failed to prepare client_order_id pure argument")?;
-        let order_type_pure = ptb.pure(order_type.as_u8())
-            .with_context(|| "Failed to prepare order_type pure argument")?;
-        let self_matching_option_pure = ptb.pure(self_matching_option.as_u8())
-            .with_context(|| "Failed to prepare self_matching_option pure argument")?;
-        let input_price_pure = ptb.pure(input_price)
-            .with_context(|| "Failed to prepare input_price pure argument")?;
-        let input_quantity_pure = ptb.pure(input_quantity)
-            .with_context(|| "Failed to prepare input_quantity pure argument")?;
-        let is_bid_pure = ptb.pure(is_bid)
-            .with_context(|| "Failed to prepare is_bid pure argument")?;
-        let pay_with_deep_pure = ptb.pure(pay_with_deep)
-            .with_context(|| "Failed to prepare pay_with_deep pure argument")?;
-        let expiration_pure = ptb.pure(expiration)
-            .with_context(|| "Failed to prepare expiration pure argument")?;
-
-        // Add the programmable Move call
         ptb.programmable_move_call(
             ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
             Identifier::new("pool")?,
-            Identifier::new("place_limit_order")?,
+            Identifier::new("vault_balances")?,
             vec![base_coin_type, quote_coin_type],
-            vec![
-                pool_argument,
-                manager_argument,
-                trade_proof_argument,
-                client_order_id_pure,
-                order_type_pure,
-                self_matching_option_pure,
-                input_price_pure,
-                input_quantity_pure,
-                is_bid_pure,
-                pay_with_deep_pure,
-                expiration_pure,
-                sui_clock_argument,
-            ],
+            vec![pool_argument],
         );
 
         Ok(())
     }
 
-    /// Get open orders for a balance manager in a pool.
+    /// Get the DEEP price of a pool used to compute order fees.
     ///
     /// # Arguments
     /// * `pool_key` - The key to identify the pool.
-    /// * `manager_key` - The key of the balance manager.
     ///
     /// # Returns
-    /// The transaction block containing the open orders.
-    pub async fn account_open_orders(
+    /// Ok(()) on success, or an error.
+    pub async fn get_order_deep_price(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
         pool_key: &str,
-        manager_key: &str,
     ) -> Result<()> {
-        let pool = self
-            .config
-            .get_pool(pool_key)
-            .with_context(|| format!("Pool not found for key: {}", pool_key))?;
-        let base_coin = self
-            .config
-            .get_coin(&pool.base_coin)
-            .with_context(|| format!("Base coin not found for key: {}", pool.base_coin))?;
-        let quote_coin = self
-            .config
-            .get_coin(&pool.quote_coin)
-            .with_context(|| format!("Quote coin not found for key: {}", pool.quote_coin))?;
-        let base_coin_type = TypeTag::from_str(&base_coin.type_)?;
-        let quote_coin_type = TypeTag::from_str(&quote_coin.type_)?;
+        let (base_coin_type, quote_coin_type) = self.pool_coin_types(pool_key)?;
 
         let pool_argument = prepare_pool_argument(
             &self.client,
@@ -210,21 +2280,860 @@ impl DeepBookContract {
             ptb,
             pool_key,
         ).await.with_context(|| "Failed to prepare pool argument")?;
-        let manager_argument = prepare_balance_manager_argument(
-            &self.client,
-            &self.config,
-            ptb,
-            manager_key,
-        ).await.with_context(|| "Failed to prepare manager argument")?;
 
         ptb.programmable_move_call(
             ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
             Identifier::new("pool")?,
-            Identifier::new("account_open_orders")?,
+            Identifier::new("get_order_deep_price")?,
             vec![base_coin_type, quote_coin_type],
-            vec![pool_argument, manager_argument],
+            vec![pool_argument],
         );
 
         Ok(())
     }
+
+    /// Call any `pool::<function>` that the SDK doesn't wrap yet.
+    ///
+    /// This is an escape hatch: nothing checks `function` or its arguments against the deployed
+    /// package, so a wrong name, argument order or argument type only fails when the transaction
+    /// is dev-inspected or executed. The pool is passed mutably as the first argument, followed by
+    /// `extra_pure_args` and, with `with_clock`, the clock. Functions that need a balance manager,
+    /// trade proof or coins can't be called this way.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key to identify the pool.
+    /// * `function` - The name of the function in the `pool` module.
+    /// * `extra_pure_args` - The arguments following the pool, in order.
+    /// * `with_clock` - Whether the clock is passed as the last argument.
+    ///
+    /// # Returns
+    /// The result of the call, or an error.
+    pub async fn raw_pool_call(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        function: &str,
+        extra_pure_args: Vec<CallArg>,
+        with_clock: bool,
+    ) -> Result<Argument> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+
+        let pool_argument = prepare_pool_argument(
+            &self.client,
+            &self.config,
+            ptb,
+            pool_key,
+        ).await.with_context(|| "Failed to prepare pool argument")?;
+        let sui_clock_argument = if with_clock {
+            Some(shared_clock_argument(ptb, self.config.clock_object_arg())?)
+        } else {
+            None
+        };
+
+        append_raw_pool_call(
+            ptb,
+            &self.config.deepbook_package_id,
+            &pool,
+            function,
+            pool_argument,
+            extra_pure_args,
+            sui_clock_argument,
+        )
+    }
+
+    /// Resolve the base and quote coin types of a pool from the configuration.
+    fn pool_coin_types(&self, pool_key: &str) -> Result<(TypeTag, TypeTag)> {
+        let pool = self.config.require_pool(pool_key)?;
+        let base_coin = self.config.require_coin(&pool.base_coin)?;
+        let quote_coin = self.config.require_coin(&pool.quote_coin)?;
+        let base_coin_type = TypeTag::from_str(&base_coin.type_)
+            .with_context(|| format!("Failed to parse base coin type: {}", base_coin.type_))?;
+        let quote_coin_type = TypeTag::from_str(&quote_coin.type_)
+            .with_context(|| format!("Failed to parse quote coin type: {}", quote_coin.type_))?;
+
+        Ok((base_coin_type, quote_coin_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::TESTNET_COINS;
+    use crate::transactions::balance_manager::append_deposits;
+    use crate::transactions::governance::append_withdraw_and_transfer;
+    use crate::utils::error::DeepBookError;
+    use crate::utils::fixtures;
+    use sui_sdk::types::base_types::SuiAddress;
+    use sui_sdk::types::base_types::SequenceNumber;
+    use sui_sdk::types::transaction::{Command, ProgrammableMoveCall};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use futures::future::BoxFuture;
+    use sui_sdk::rpc_types::SuiObjectResponse;
+    use sui_sdk::types::error::SuiObjectResponseError;
+
+    /// ObjectFetcher resolving every object as shared except `missing`, counting the RPCs it serves.
+    struct MissingObjectFetcher {
+        missing: ObjectID,
+        calls: AtomicUsize,
+    }
+
+    impl ObjectFetcher for MissingObjectFetcher {
+        fn multi_get_objects<'a>(
+            &'a self,
+            object_ids: &'a [ObjectID],
+        ) -> BoxFuture<'a, Result<Vec<SuiObjectResponse>>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let responses = object_ids
+                .iter()
+                .map(|id| if *id == self.missing {
+                    SuiObjectResponse::new_with_error(SuiObjectResponseError::NotExists { object_id: *id })
+                } else {
+                    fixtures::shared_object_response(*id)
+                })
+                .collect();
+
+            Box::pin(async move { Ok(responses) })
+        }
+    }
+
+    #[test]
+    fn test_default_expiration_is_no_expiration() {
+        let mut config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        config.max_timestamp = 1_844_674_407_370_955_161;
+
+        assert_eq!(resolve_expiration(None, &config), config.no_expiration());
+        assert_eq!(resolve_expiration(Some(1_700_000_000_000), &config), 1_700_000_000_000);
+    }
+
+    #[test]
+    fn test_configured_self_matching_default() {
+        let default = SelfMatchingOptions::CancelTaker;
+
+        assert_eq!(resolve_self_matching(None, default), SelfMatchingOptions::CancelTaker);
+        assert_eq!(
+            resolve_self_matching(Some(SelfMatchingOptions::CancelMaker), default),
+            SelfMatchingOptions::CancelMaker,
+        );
+        assert_eq!(SelfMatchingOptions::default(), SelfMatchingOptions::SelfMatchingAllowed);
+    }
+
+    #[test]
+    fn test_validate_with_cached_params() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        let pool_params = RwLock::new(HashMap::from([(
+            "SUI_DBUSDC".to_string(),
+            PoolBookParams { tick_size: 1_000, lot_size: 100_000_000, min_size: 1_000_000_000 },
+        )]));
+
+        let input_price = encode_price(2.5, sui, usdc, RoundingMode::Nearest, true);
+        assert!(validate_with_cached_params(&pool_params, "SUI_DBUSDC", input_price, encode_quantity(1.5, sui, RoundingMode::Nearest)).is_ok());
+        assert!(validate_with_cached_params(&pool_params, "SUI_DBUSDC", input_price, encode_quantity(0.5, sui, RoundingMode::Nearest)).is_err());
+        // Pools without cached parameters are left to the on-chain checks
+        assert!(validate_with_cached_params(&pool_params, "DEEP_SUI", 1, 1).is_ok());
+    }
+
+    #[test]
+    fn test_off_tick_price_rounds_to_cached_tick_and_lot() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        let pool_params = RwLock::new(HashMap::from([(
+            "SUI_DBUSDC".to_string(),
+            PoolBookParams { tick_size: 1_000, lot_size: 100_000_000, min_size: 1_000_000_000 },
+        )]));
+        // 2.50055 is between the ticks 2.500 and 2.501, 1.55 SUI between the lots 1.5 and 1.6
+        let units = |mode, is_bid| {
+            let encoded = (encode_price(2.50055, sui, usdc, mode, is_bid), encode_quantity(1.55, sui, mode));
+            round_to_cached_params(&pool_params, "SUI_DBUSDC", mode, encoded, is_bid)
+        };
+
+        assert_eq!(units(RoundingMode::TowardMaker, true), (2_500_000, 1_500_000_000));
+        assert_eq!(units(RoundingMode::TowardMaker, false), (2_501_000, 1_500_000_000));
+        assert_eq!(units(RoundingMode::TowardTaker, true), (2_501_000, 1_500_000_000));
+        assert_eq!(units(RoundingMode::Nearest, true), (2_501_000, 1_600_000_000));
+        for mode in [RoundingMode::TowardMaker, RoundingMode::Nearest] {
+            let (input_price, input_quantity) = units(mode, false);
+            assert!(validate_with_cached_params(&pool_params, "SUI_DBUSDC", input_price, input_quantity).is_ok());
+        }
+        // Pools without cached parameters are left to the on-chain checks
+        let encoded = (2_500_550, 1_550_000_000);
+        assert_eq!(round_to_cached_params(&pool_params, "DEEP_SUI", RoundingMode::TowardMaker, encoded, true), encoded);
+        assert_eq!(RoundingMode::Nearest.round_price_to_tick(2_500_550, 0, true), 2_500_550);
+    }
+
+    #[test]
+    fn test_encode_price_and_quantity() {
+        let deep = TESTNET_COINS.get("DEEP").unwrap();
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+
+        // DEEP_SUI at 0.02 SUI per DEEP
+        assert_eq!(encode_price(0.02, deep, sui, RoundingMode::Nearest, true), 20_000_000_000);
+        assert_eq!(encode_quantity(10.0, deep, RoundingMode::Nearest), 10_000_000);
+    }
+
+    #[test]
+    fn test_toward_maker_rounds_bid_down_and_ask_up() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+
+        // SUI_DBUSDC: 1.2345675 encodes to 1_234_567.5 raw units
+        let price = 1.2345675;
+        assert_eq!(encode_price(price, sui, usdc, RoundingMode::TowardMaker, true), 1_234_567);
+        assert_eq!(encode_price(price, sui, usdc, RoundingMode::TowardMaker, false), 1_234_568);
+        assert_eq!(encode_price(price, sui, usdc, RoundingMode::TowardTaker, true), 1_234_568);
+        assert_eq!(encode_price(price, sui, usdc, RoundingMode::TowardTaker, false), 1_234_567);
+    }
+
+    #[test]
+    fn test_directional_rounding_keeps_whole_units() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+
+        assert_eq!(encode_price(2.5, sui, usdc, RoundingMode::TowardMaker, true), 2_500_000);
+        assert_eq!(encode_price(2.5, sui, usdc, RoundingMode::TowardMaker, false), 2_500_000);
+        assert_eq!(encode_quantity(0.3, sui, RoundingMode::TowardMaker), 300_000_000);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_encoding_agrees_with_float_for_nice_values() {
+        let deep = TESTNET_COINS.get("DEEP").unwrap();
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+
+        for (price, quantity) in [("0.02", "10"), ("1.5", "250.25"), ("3", "0.001")] {
+            let price_decimal = Decimal::from_str(price).unwrap();
+            let quantity_decimal = Decimal::from_str(quantity).unwrap();
+            let price_float: f64 = price.parse().unwrap();
+            let quantity_float: f64 = quantity.parse().unwrap();
+
+            assert_eq!(
+                encode_price_decimal(price_decimal, deep, sui).unwrap(),
+                encode_price(price_float, deep, sui, RoundingMode::Nearest, true),
+            );
+            assert_eq!(
+                encode_quantity_decimal(quantity_decimal, deep).unwrap(),
+                encode_quantity(quantity_float, deep, RoundingMode::Nearest),
+            );
+        }
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_encoding_is_exact_where_float_drifts() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+
+        // 12345678.123456789 SUI needs 17 significant digits, more than f64 can hold
+        let quantity = "12345678.123456789";
+        let expected = 12_345_678_123_456_789u64;
+
+        assert_eq!(encode_quantity_decimal(Decimal::from_str(quantity).unwrap(), sui).unwrap(), expected);
+        assert_ne!(encode_quantity(quantity.parse().unwrap(), sui, RoundingMode::Nearest), expected);
+    }
+
+    #[test]
+    fn test_price_encoders_agree_except_on_f64_noise() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let dbusdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        let usdc = Coin::from_type("0x1::usdc::USDC", 1_000_000).unwrap();
+        let usdt = Coin::from_type("0x1::usdt::USDT", 1_000_000).unwrap();
+        let modes = [RoundingMode::Nearest, RoundingMode::TowardMaker, RoundingMode::TowardTaker];
+
+        // (base, quote, price, quantity)
+        let cases = [(sui, dbusdc, 2.5, 10.0), (sui, dbusdc, 1.2345678, 0.1), (&usdc, &usdt, 0.9999, 1_234_567.891)];
+        for (base, quote, price, quantity) in cases {
+            for mode in modes {
+                for is_bid in [true, false] {
+                    assert_eq!(
+                        StablePriceEncoder.encode_price(price, base, quote, mode, is_bid),
+                        DefaultPriceEncoder.encode_price(price, base, quote, mode, is_bid),
+                    );
+                }
+                assert_eq!(
+                    StablePriceEncoder.encode_quantity(quantity, base, mode),
+                    DefaultPriceEncoder.encode_quantity(quantity, base, mode),
+                );
+            }
+        }
+
+        // 1.0066628125 * 10^9 is 1006662812.4999999 in f64, half a unit exactly in decimal
+        let price = 1.0066628125;
+        let stable = |mode, is_bid| StablePriceEncoder.encode_price(price, &usdc, &usdt, mode, is_bid);
+        assert_eq!(DefaultPriceEncoder.encode_price(price, &usdc, &usdt, RoundingMode::Nearest, true), 1_006_662_812);
+        assert_eq!(stable(RoundingMode::Nearest, true), 1_006_662_813);
+        assert_eq!(stable(RoundingMode::TowardMaker, true), 1_006_662_812);
+        assert_eq!(stable(RoundingMode::TowardMaker, false), 1_006_662_813);
+        assert_eq!(StablePriceEncoder.encode_quantity(65.3771635, &usdc, RoundingMode::Nearest), 65_377_164);
+        assert_eq!(DefaultPriceEncoder.encode_quantity(65.3771635, &usdc, RoundingMode::Nearest), 65_377_163);
+
+        // Values the exact arithmetic can't hold fall back to the default encoding
+        assert_eq!(StablePriceEncoder.encode_quantity(f64::NAN, &usdc, RoundingMode::Nearest), 0);
+        assert_eq!(StablePriceEncoder.encode_quantity(1e-40, &usdc, RoundingMode::Nearest), 0);
+    }
+
+    #[test]
+    fn test_notional_value_across_scalar_ratios() {
+        let deep = TESTNET_COINS.get("DEEP").unwrap();
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+
+        // (base, quote, price, quantity, notional)
+        let cases = [(deep, sui, 0.02, 500.0, 10.0), (sui, usdc, 2.5, 4.0, 10.0), (sui, usdc, 1.25, 0.5, 0.625)];
+        for (base, quote, price, quantity, notional) in cases {
+            let price_raw = encode_price(price, base, quote, RoundingMode::Nearest, true);
+            let quantity_raw = encode_quantity(quantity, base, RoundingMode::Nearest);
+
+            assert!((notional_value(price_raw, quantity_raw, quote) - notional).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_quote_to_base_quantity_is_lot_aligned() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        // 0.1 SUI lots
+        let lot_size = 100_000_000;
+
+        assert_eq!(quote_to_base_quantity(100.0, 2.0, sui, lot_size), 50.0);
+        assert_eq!(quote_to_base_quantity(100.0, 3.0, sui, lot_size), 33.3);
+        assert_eq!(quote_to_base_quantity(0.05, 1.0, sui, lot_size), 0.0);
+    }
+
+    #[test]
+    fn test_insufficient_balance_for_bid() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        // Buying 10 SUI at 2.5 needs 25 DBUSDC
+        let required = required_balance(
+            encode_price(2.5, sui, usdc, RoundingMode::Nearest, true),
+            encode_quantity(10.0, sui, RoundingMode::Nearest),
+            true,
+        );
+        assert_eq!(required, 25_000_000);
+
+        let err = ensure_free_balance(required, 20_000_000, 5_000_000, "DBUSDC", usdc).unwrap_err();
+        assert_eq!(err.to_string(), "Insufficient free balance of DBUSDC: need 25 have 20 (5 locked in open orders)");
+        assert!(ensure_free_balance(required, 25_000_000, 0, "DBUSDC", usdc).is_ok());
+    }
+
+    #[test]
+    fn test_insufficient_balance_for_ask() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        // Selling 10 SUI needs 10 SUI, whatever the price
+        let required = required_balance(
+            encode_price(2.5, sui, usdc, RoundingMode::Nearest, false),
+            encode_quantity(10.0, sui, RoundingMode::Nearest),
+            false,
+        );
+        assert_eq!(required, 10_000_000_000);
+
+        let err = ensure_free_balance(required, 9_500_000_000, 0, "SUI", sui).unwrap_err();
+        assert_eq!(err.to_string(), "Insufficient free balance of SUI: need 10 have 9.5 (0 locked in open orders)");
+    }
+
+    #[tokio::test]
+    async fn test_available_pool_book_params_skips_missing_pool_in_one_rpc() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let missing = ObjectID::from_hex_literal(&config.require_pool("SUI_DBUSDC").unwrap().address).unwrap();
+        let fetcher = MissingObjectFetcher { missing, calls: AtomicUsize::new(0) };
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        let included = append_available_pool_book_params(
+            &fetcher,
+            &config,
+            &mut ptb,
+            &["DEEP_SUI", "SUI_DBUSDC", "DEEP_DBUSDC"],
+        ).await.unwrap();
+
+        assert_eq!(included, vec!["DEEP_SUI", "DEEP_DBUSDC"]);
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(ptb.finish().commands.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_pool_key_is_a_typed_error() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+
+        let err = OrderPool::from_config(&config, "SUI_DBUSDT").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeepBookError>(),
+            Some(&DeepBookError::PoolNotFound("SUI_DBUSDT".to_string())),
+        );
+    }
+
+    #[test]
+    fn test_raw_pool_builds_same_ptb_as_configured_pool() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let configured = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        let raw = OrderPool::from_parts(&configured.address, &sui.type_, &usdc.type_, sui.scalar, usdc.scalar);
+
+        let build = |pool: &OrderPool| {
+            let mut ptb = ProgrammableTransactionBuilder::new();
+            let objects = [0u8, 1, 2, 3].map(|i| ptb.pure(i).unwrap());
+            let order = EncodedLimitOrder {
+                client_order_id: 1,
+                order_type: OrderType::PostOnly,
+                self_matching_option: SelfMatchingOptions::CancelTaker,
+                input_price: encode_price(2.5, &pool.base_coin, &pool.quote_coin, RoundingMode::Nearest, true),
+                input_quantity: encode_quantity(10.0, &pool.base_coin, RoundingMode::Nearest),
+                is_bid: true,
+                pay_with_deep: true,
+                expiration: config.no_expiration(),
+            };
+            append_place_limit_order(&mut ptb, &config.deepbook_package_id, pool, objects, &order).unwrap();
+            ptb.finish()
+        };
+
+        assert_eq!(build(&raw), build(&configured));
+    }
+
+    #[test]
+    fn test_place_limit_order_call_args_match_builder() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+        let shared = |id: ObjectID, version: u64| ObjectArg::SharedObject {
+            id,
+            initial_shared_version: SequenceNumber::from_u64(version),
+            mutable: true,
+        };
+        let objects = [
+            shared(ObjectID::random(), 7),
+            shared(ObjectID::random(), 9),
+            shared(ObjectID::from_single_byte(6), 1),
+        ];
+        let order = EncodedLimitOrder {
+            client_order_id: 42,
+            order_type: OrderType::PostOnly,
+            self_matching_option: SelfMatchingOptions::CancelTaker,
+            input_price: encode_price(2.5, &pool.base_coin, &pool.quote_coin, RoundingMode::Nearest, true),
+            input_quantity: encode_quantity(10.0, &pool.base_coin, RoundingMode::Nearest),
+            is_bid: true,
+            pay_with_deep: true,
+            expiration: config.no_expiration(),
+        };
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let [pool_argument, manager_argument, sui_clock_argument] = objects.map(|object| ptb.obj(object).unwrap());
+        let trade_proof_argument = ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&config.deepbook_package_id).unwrap(),
+            Identifier::new("balance_manager").unwrap(),
+            Identifier::new("generate_proof_as_owner").unwrap(),
+            vec![],
+            vec![manager_argument],
+        );
+        append_place_limit_order(
+            &mut ptb,
+            &config.deepbook_package_id,
+            &pool,
+            [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+            &order,
+        ).unwrap();
+
+        let transaction = ptb.finish();
+        let Command::MoveCall(call) = &transaction.commands[1] else {
+            panic!("expected a Move call");
+        };
+        let builder_args: Vec<Vec<u8>> = call
+            .arguments
+            .iter()
+            .filter_map(|argument| match argument {
+                Argument::Input(index) => Some(bcs::to_bytes(&transaction.inputs[*index as usize]).unwrap()),
+                _ => None,
+            })
+            .collect();
+        let call_args: Vec<Vec<u8>> = place_limit_order_call_args(objects, &order)
+            .unwrap()
+            .iter()
+            .map(|call_arg| bcs::to_bytes(call_arg).unwrap())
+            .collect();
+
+        // Every argument but the trade proof, in the same order and encoding
+        assert_eq!(call_args.len(), call.arguments.len() - 1);
+        assert_eq!(call_args, builder_args);
+    }
+
+    #[test]
+    fn test_raw_pool_call_targets_any_function() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let [pool_argument, sui_clock_argument] = [0u8, 1].map(|i| ptb.pure(i).unwrap());
+
+        append_raw_pool_call(
+            &mut ptb,
+            &config.deepbook_package_id,
+            &pool,
+            "some_future_function",
+            pool_argument,
+            vec![CallArg::Pure(bcs::to_bytes(&42u64).unwrap()), CallArg::Pure(bcs::to_bytes(&true).unwrap())],
+            Some(sui_clock_argument),
+        ).unwrap();
+        let invalid = append_raw_pool_call(
+            &mut ptb,
+            &config.deepbook_package_id,
+            &pool,
+            "not an identifier",
+            pool_argument,
+            vec![],
+            None,
+        );
+        assert!(invalid.is_err());
+
+        let transaction = ptb.finish();
+        let Command::MoveCall(call) = &transaction.commands[0] else {
+            panic!("expected a Move call");
+        };
+        assert_eq!(call.module.as_str(), "pool");
+        assert_eq!(call.function.as_str(), "some_future_function");
+        assert_eq!(call.type_arguments.len(), 2);
+        // Pool first, then the extra arguments in order, then the clock
+        assert_eq!(call.arguments, vec![pool_argument, Argument::Input(2), Argument::Input(3), sui_clock_argument]);
+        assert!(transaction.inputs.contains(&CallArg::Pure(bcs::to_bytes(&42u64).unwrap())));
+    }
+
+    #[test]
+    fn test_requote_orders_calls_and_shares_one_proof() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+        let ladder = [(2.4, 10.0, true), (2.6, 10.0, false)];
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let [pool_argument, manager_argument, sui_clock_argument] = [0u8, 1, 2].map(|i| ptb.pure(i).unwrap());
+        let trade_proof_argument = ptb.programmable_move_call(
+            ObjectID::from_hex_literal(&config.deepbook_package_id).unwrap(),
+            Identifier::new("balance_manager").unwrap(),
+            Identifier::new("generate_proof_as_owner").unwrap(),
+            vec![],
+            vec![manager_argument],
+        );
+        let orders: Vec<_> = ladder
+            .iter()
+            .enumerate()
+            .map(|(position, &(price, quantity, is_bid))| EncodedLimitOrder {
+                client_order_id: position as u64 + 1,
+                order_type: OrderType::PostOnly,
+                self_matching_option: SelfMatchingOptions::CancelTaker,
+                input_price: encode_price(price, &pool.base_coin, &pool.quote_coin, RoundingMode::Nearest, is_bid),
+                input_quantity: encode_quantity(quantity, &pool.base_coin, RoundingMode::Nearest),
+                is_bid,
+                pay_with_deep: true,
+                expiration: config.no_expiration(),
+            })
+            .collect();
+        append_requote(
+            &mut ptb,
+            &config.deepbook_package_id,
+            &pool,
+            [pool_argument, manager_argument, trade_proof_argument, sui_clock_argument],
+            &orders,
+        ).unwrap();
+
+        let calls: Vec<_> = ptb
+            .finish()
+            .commands
+            .into_iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => call,
+                other => panic!("unexpected command: {:?}", other),
+            })
+            .collect();
+        let functions: Vec<_> = calls.iter().map(|call| call.function.as_str()).collect();
+        assert_eq!(
+            functions,
+            vec!["generate_proof_as_owner", "cancel_all_orders", "withdraw_settled_amounts", "place_limit_order", "place_limit_order"],
+        );
+        // Every pool call takes the trade proof produced by the only proof command
+        assert!(calls[1..].iter().all(|call| call.arguments[2] == trade_proof_argument));
+    }
+
+    #[test]
+    fn test_swap_output_feeds_a_later_move_call() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let [pool_argument, base_in, deep_in, min_out, sui_clock_argument] = [0u8, 1, 2, 3, 4].map(|i| ptb.pure(i).unwrap());
+        let (_, quote_out, _) = append_swap(
+            &mut ptb,
+            &config,
+            "swap_exact_base_for_quote",
+            &pool.base_coin,
+            &pool.quote_coin,
+            [pool_argument, base_in, deep_in, min_out, sui_clock_argument],
+        ).unwrap();
+        // Any later call, e.g. a deposit into another protocol, can consume the swap output
+        ptb.programmable_move_call(
+            ObjectID::from_hex_literal("0x2").unwrap(),
+            Identifier::new("other_protocol").unwrap(),
+            Identifier::new("deposit").unwrap(),
+            vec![],
+            vec![quote_out],
+        );
+
+        let commands = ptb.finish().commands;
+        assert_eq!(commands.len(), 2);
+        match &commands[1] {
+            Command::MoveCall(call) => assert_eq!(call.arguments, vec![Argument::NestedResult(0, 1)]),
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ladder_orders_carry_their_own_fee_mode() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+        let options = PlaceOrderOptions { pay_with_deep: Some(true), ..Default::default() };
+        let ladder = [
+            OrderSpec::from((2.4, 10.0, true)),
+            OrderSpec { pay_with_deep: Some(false), order_type: Some(OrderType::PostOnly), ..OrderSpec::new(2.6, 10.0, false) },
+        ];
+        let orders: Vec<_> = ladder
+            .iter()
+            .zip([100, 101])
+            .map(|(spec, id)| {
+                let self_matching = SelfMatchingOptions::CancelTaker;
+                encode_ladder_order(id, spec, &pool, &options, &DefaultPriceEncoder, self_matching, &config)
+            })
+            .collect();
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let objects = [0u8, 1, 2, 3].map(|i| ptb.pure(i).unwrap());
+        append_requote(&mut ptb, &config.deepbook_package_id, &pool, objects, &orders).unwrap();
+
+        let transaction = ptb.finish();
+        let pure_at = |call: &ProgrammableMoveCall, position: usize| match call.arguments[position] {
+            Argument::Input(index) => transaction.inputs[index as usize].clone(),
+            other => panic!("unexpected argument: {:?}", other),
+        };
+        let placements: Vec<_> = transaction
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                Command::MoveCall(call) if call.function.as_str() == "place_limit_order" => Some(call),
+                _ => None,
+            })
+            .collect();
+        // (order_type, pay_with_deep) of each call: the first order uses the ladder's options
+        let flags: Vec<_> = placements.iter().map(|call| (pure_at(call, 4), pure_at(call, 9))).collect();
+        let pure = |bytes: Vec<u8>| CallArg::Pure(bytes);
+        assert_eq!(flags, vec![
+            (pure(bcs::to_bytes(&OrderType::NoRestriction.as_u8()).unwrap()), pure(bcs::to_bytes(&true).unwrap())),
+            (pure(bcs::to_bytes(&OrderType::PostOnly.as_u8()).unwrap()), pure(bcs::to_bytes(&false).unwrap())),
+        ]);
+        let client_order_ids: Vec<_> = placements.iter().map(|call| pure_at(call, 3)).collect();
+        assert_eq!(client_order_ids, vec![
+            pure(bcs::to_bytes(&100u64).unwrap()),
+            pure(bcs::to_bytes(&101u64).unwrap()),
+        ]);
+    }
+
+    #[test]
+    fn test_deep_less_config_degrades_gracefully() {
+        let coins = TESTNET_COINS
+            .iter()
+            .filter(|(key, _)| key.as_str() != "DEEP")
+            .map(|(key, coin)| (key.clone(), coin.clone()))
+            .collect();
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, Some(coins), None).unwrap();
+        let pool = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let manager_argument = ptb.pure(0u8).unwrap();
+
+        // Non-DEEP operations keep working
+        let usdc_type = TypeTag::from_str(&config.require_coin("DBUSDC").unwrap().type_).unwrap();
+        let usdc_coin = ptb.pure(1u8).unwrap();
+        append_deposits(&mut ptb, &config.deepbook_package_id, manager_argument, vec![(usdc_type, usdc_coin)]).unwrap();
+        let options = PlaceOrderOptions::default();
+        let spec = OrderSpec::new(2.5, 10.0, true);
+        let self_matching = SelfMatchingOptions::CancelTaker;
+        let order = encode_ladder_order(1, &spec, &pool, &options, &DefaultPriceEncoder, self_matching, &config);
+        assert!(!order.pay_with_deep);
+
+        // DEEP operations fail with a typed error
+        assert_eq!(config.require_deep_coin(), Err(DeepBookError::DeepCoinUnavailable));
+        let err = append_withdraw_and_transfer(&mut ptb, &config, manager_argument, 1_000_000, SuiAddress::ZERO).unwrap_err();
+        assert_eq!(err.downcast_ref::<DeepBookError>(), Some(&DeepBookError::DeepCoinUnavailable));
+    }
+
+    #[test]
+    fn test_auto_fee_mode_pays_with_deep_only_if_covered() {
+        // 10 SUI at 2.5 DBUSDC with 0.1% taker fee, at 10 DEEP per SUI: 0.1 DEEP
+        let deep_price = OrderDeepPrice { asset_is_base: true, deep_per_asset: 10_000_000 };
+        let deep_required = order_deep_required(2_500_000, 10_000_000_000, 1_000_000, &deep_price);
+        assert_eq!(deep_required, 100_000);
+
+        // DEEP-sufficient: the order pays in DEEP
+        assert!(auto_pays_with_deep(100_000, deep_required));
+        assert!(auto_pays_with_deep(5_000_000, deep_required));
+        // DEEP-insufficient: the order falls back to the input coin
+        assert!(!auto_pays_with_deep(99_999, deep_required));
+        assert!(!auto_pays_with_deep(0, deep_required));
+
+        assert_eq!(FeeMode::default(), FeeMode::Auto);
+        assert_eq!(FeeMode::Auto.fixed(), None);
+        assert_eq!(FeeMode::Deep.fixed(), Some(true));
+        assert_eq!(FeeMode::InputCoin.fixed(), Some(false));
+    }
+
+    #[test]
+    fn test_recommended_deep_deposit() {
+        // 10,000 DBUSDC of trading with 0.1% taker fee at 20 DEEP per DBUSDC: 200 DEEP plus 10%
+        let taker_fee = 1_000_000;
+        let quote_deep_price = OrderDeepPrice { asset_is_base: false, deep_per_asset: 20_000_000_000 };
+        assert_eq!(recommended_deep_deposit(10_000_000_000, 2_500_000, taker_fee, &quote_deep_price), 220_000_000);
+
+        // The same notional is 4,000 SUI at 2.5 DBUSDC, at 50 DEEP per SUI: also 200 DEEP plus 10%
+        let base_deep_price = OrderDeepPrice { asset_is_base: true, deep_per_asset: 50_000_000 };
+        assert_eq!(recommended_deep_deposit(10_000_000_000, 2_500_000, taker_fee, &base_deep_price), 220_000_000);
+
+        assert_eq!(recommended_deep_deposit(0, 2_500_000, taker_fee, &base_deep_price), 0);
+    }
+
+    #[test]
+    fn test_effective_taker_price() {
+        // 0.1% taker fee
+        let taker_fee = 1_000_000;
+
+        assert!((effective_taker_price(2.0, taker_fee, true, true) - 2.002).abs() < 1e-12);
+        assert!((effective_taker_price(2.0, taker_fee, false, true) - 1.998).abs() < 1e-12);
+        // Paying in the input coin costs 1.25x the fee
+        assert!((effective_taker_price(2.0, taker_fee, true, false) - 2.0025).abs() < 1e-12);
+        assert!((effective_taker_price(2.0, taker_fee, false, false) - 1.9975).abs() < 1e-12);
+        assert_eq!(effective_taker_price(2.0, 0, true, false), 2.0);
+    }
+
+    #[test]
+    fn test_batch_deep_required() {
+        let sui = TESTNET_COINS.get("SUI").unwrap();
+        let usdc = TESTNET_COINS.get("DBUSDC").unwrap();
+        // 0.1% taker fee
+        let taker_fee = 1_000_000;
+        let orders = [(2.5, 10.0, true), (2.6, 20.0, false), (2.4, 5.0, true)];
+        let total = |deep_price: &OrderDeepPrice| -> u64 {
+            orders
+                .iter()
+                .map(|&(price, quantity, is_bid)| order_deep_required(
+                    encode_price(price, sui, usdc, RoundingMode::Nearest, is_bid),
+                    encode_quantity(quantity, sui, RoundingMode::Nearest),
+                    taker_fee,
+                    deep_price,
+                ))
+                .sum()
+        };
+
+        // 10 DEEP per SUI: 35 SUI is 350 DEEP, 0.35 DEEP in fees
+        let per_base = OrderDeepPrice { asset_is_base: true, deep_per_asset: 10_000_000 };
+        assert_eq!(total(&per_base), 350_000);
+        // 4 DEEP per DBUSDC: 25 + 52 + 12 DBUSDC is 356 DEEP, 0.356 DEEP in fees
+        let per_quote = OrderDeepPrice { asset_is_base: false, deep_per_asset: 4_000_000_000 };
+        assert_eq!(total(&per_quote), 356_000);
+    }
+
+    #[test]
+    fn test_replacement_order_keeps_price_quantity_and_side() {
+        let price = 2_500_000u64;
+        let order = |order_id: u128| Order {
+            quantity: 10_000_000_000,
+            filled_quantity: 4_000_000_000,
+            fee_is_deep: false,
+            status: 1,
+            expire_timestamp: 1_700_000_000_000,
+            ..fixtures::order(order_id, 42)
+        };
+        let bid = order(((price as u128) << 64) | 7);
+        let ask = order((1u128 << 127) | ((price as u128) << 64) | 8);
+
+        let replacement = replacement_order(&bid, 1_800_000_000_000, SelfMatchingOptions::CancelTaker);
+        assert_eq!(replacement.input_price, price);
+        assert_eq!(replacement.input_quantity, 6_000_000_000);
+        assert!(replacement.is_bid);
+        assert_eq!(replacement.client_order_id, 42);
+        assert!(!replacement.pay_with_deep);
+        assert_eq!(replacement.order_type, OrderType::PostOnly);
+        assert_eq!(replacement.expiration, 1_800_000_000_000);
+
+        let replacement = replacement_order(&ask, 1_800_000_000_000, SelfMatchingOptions::CancelTaker);
+        assert_eq!(replacement.input_price, price);
+        assert_eq!(replacement.input_quantity, 6_000_000_000);
+        assert!(!replacement.is_bid);
+    }
+
+    #[test]
+    fn test_resize_order_shrinks_in_place_and_grows_by_cancel_replace() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "SUI_DBUSDC").unwrap();
+        let price = 2_500_000u64;
+        let order = Order {
+            quantity: 10_000_000_000,
+            filled_quantity: 4_000_000_000,
+            expire_timestamp: 1_800_000_000_000,
+            ..fixtures::order(((price as u128) << 64) | 7, 42)
+        };
+        let resize = |new_quantity: u64| {
+            let mut ptb = ProgrammableTransactionBuilder::new();
+            let objects = [0u8, 1, 2, 3].map(|i| ptb.pure(i).unwrap());
+            let result = append_resize_order(
+                &mut ptb,
+                &config.deepbook_package_id,
+                &pool,
+                objects,
+                &order,
+                new_quantity,
+                SelfMatchingOptions::CancelTaker,
+            );
+            let transaction = ptb.finish();
+            let calls: Vec<ProgrammableMoveCall> = transaction.commands
+                .into_iter()
+                .map(|command| match command {
+                    Command::MoveCall(call) => *call,
+                    other => panic!("unexpected command: {:?}", other),
+                })
+                .collect();
+            (result, calls, transaction.inputs)
+        };
+
+        let (result, calls, inputs) = resize(6_000_000_000);
+        assert_eq!(result.unwrap(), None);
+        let functions: Vec<_> = calls.iter().map(|call| call.function.as_str()).collect();
+        assert_eq!(functions, vec!["modify_order"]);
+        assert!(inputs.contains(&CallArg::Pure(bcs::to_bytes(&6_000_000_000u64).unwrap())));
+
+        let (result, calls, inputs) = resize(15_000_000_000);
+        assert_eq!(result.unwrap(), Some(Argument::Result(1)));
+        let functions: Vec<_> = calls.iter().map(|call| call.function.as_str()).collect();
+        assert_eq!(functions, vec!["cancel_order", "place_limit_order"]);
+        // The replacement rests the unfilled 11 SUI at the original price
+        assert!(inputs.contains(&CallArg::Pure(bcs::to_bytes(&11_000_000_000u64).unwrap())));
+        assert!(inputs.contains(&CallArg::Pure(bcs::to_bytes(&price).unwrap())));
+        assert!(inputs.contains(&CallArg::Pure(bcs::to_bytes(&1_800_000_000_000u64).unwrap())));
+
+        assert!(resize(10_000_000_000).0.is_err());
+        let (result, calls, _) = resize(4_000_000_000);
+        assert!(result.is_err());
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn test_disabled_pool_refuses_order() {
+        assert!(ensure_pool_enabled("DEEP_SUI", &[DEEPBOOK_VERSION]).is_ok());
+
+        let err = ensure_pool_enabled("DEEP_SUI", &[]).unwrap_err();
+        assert_eq!(err.to_string(), format!("Pool DEEP_SUI is disabled: it allows versions [], not {}", DEEPBOOK_VERSION));
+        assert!(ensure_pool_enabled("DEEP_SUI", &[DEEPBOOK_VERSION + 1]).is_err());
+    }
+
+    #[test]
+    fn test_order_id_price() {
+        let price = 2_500_000u64;
+        // Bit 127 is the side (set for asks), bits 64 to 126 the price and the low bits the sequence number
+        let bid_order_id = ((price as u128) << 64) | 42;
+        let ask_order_id = (1u128 << 127) | ((price as u128) << 64) | 7;
+
+        assert_eq!(order_id_price(bid_order_id), price);
+        assert_eq!(order_id_price(ask_order_id), price);
+        assert!(order_id_is_bid(bid_order_id));
+        assert!(!order_id_is_bid(ask_order_id));
+    }
 }
@@ -3,9 +3,21 @@
 //
 // This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
 
+use std::str::FromStr;
 use std::sync::Arc;
+use anyhow::{anyhow, Context, Result};
 use sui_sdk::SuiClient;
+use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{Argument, Command};
+use sui_sdk::types::TypeTag;
 use crate::DeepBookConfig;
+use crate::transactions::deepbook::append_swap;
+use crate::utils::constants::{Coin, Pool};
+use crate::utils::transactions::{
+    deepbook_move_call, nested_result, prepare_shared_object_arguments, shared_clock_argument,
+    zero_coin,
+};
 
 pub struct FlashLoanContract {
     client: Arc<SuiClient>,
@@ -16,4 +28,315 @@ impl FlashLoanContract {
     pub fn new(client: Arc<SuiClient>, config: Arc<DeepBookConfig>) -> Self {
         FlashLoanContract { client, config }
     }
+
+    /// Borrow the base asset of a pool with a flash loan.
+    ///
+    /// The returned `FlashLoan` is a hot potato: it has no `drop` ability, so the same PTB
+    /// must hand it back to `return_base_asset` together with the borrowed amount.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key to identify the pool.
+    /// * `pool_argument` - The prepared pool argument.
+    /// * `amount` - The amount of base asset to borrow.
+    ///
+    /// # Returns
+    /// The `(borrowed_coin, flash_loan)` arguments.
+    pub fn borrow_base_asset(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        pool_argument: Argument,
+        amount: f64,
+    ) -> Result<(Argument, Argument)> {
+        let (_, base_coin, quote_coin) = resolve_pool(&self.config, pool_key)?;
+
+        borrow_flashloan_base(ptb, &self.config, base_coin, quote_coin, pool_argument, base_coin.to_units(amount))
+    }
+
+    /// Return a flash loan of the base asset of a pool.
+    /// `coin` must hold exactly the borrowed amount.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key to identify the pool.
+    /// * `pool_argument` - The prepared pool argument.
+    /// * `coin` - The coin repaying the loan.
+    /// * `flash_loan` - The `FlashLoan` returned by `borrow_base_asset`.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub fn return_base_asset(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        pool_argument: Argument,
+        coin: Argument,
+        flash_loan: Argument,
+    ) -> Result<()> {
+        let (_, base_coin, quote_coin) = resolve_pool(&self.config, pool_key)?;
+
+        return_flashloan_base(ptb, &self.config, base_coin, quote_coin, pool_argument, coin, flash_loan)
+    }
+
+    /// Flash-borrow the base asset of `pool_a`, swap it through `pool_b` and back through `pool_a`,
+    /// repay the loan, and require the leftover to be at least `min_profit`.
+    ///
+    /// `pool_a` trades `X/Y`; `pool_b` must trade `X` against the same `Y`, in either order.
+    /// The PTB is ordered around the `FlashLoan` hot potato: borrow, both swaps, then repay, all
+    /// within the same transaction. The profit check splits `min_profit` off the leftover coin,
+    /// which aborts the whole transaction (including the loan) if the arbitrage is not profitable.
+    /// Swap fees are paid in the input coin. All leftover coins are sent to the configured address.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_a` - The key of the pool to borrow from and swap back through.
+    /// * `pool_b` - The key of the pool to swap through first.
+    /// * `amount` - The amount of base asset of `pool_a` to borrow.
+    /// * `min_profit` - The minimum profit, in base asset of `pool_a`.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn arb_between_pools(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_a: &str,
+        pool_b: &str,
+        amount: f64,
+        min_profit: f64,
+    ) -> Result<()> {
+        let recipient = self.config.sender();
+        let (pool_a_info, _, _) = resolve_pool(&self.config, pool_a)?;
+        let (pool_b_info, _, _) = resolve_pool(&self.config, pool_b)?;
+
+        let arguments = prepare_shared_object_arguments(
+            &*self.client,
+            ptb,
+            &[(&pool_a_info.address, true), (&pool_b_info.address, true)],
+        ).await.with_context(|| "Failed to prepare pool arguments")?;
+        let clock_argument = shared_clock_argument(ptb, self.config.clock_object_arg())?;
+
+        build_arb_between_pools(
+            ptb,
+            &self.config,
+            ArbPools { pool_a, pool_b, pool_a_argument: arguments[0], pool_b_argument: arguments[1], clock_argument },
+            amount,
+            min_profit,
+            recipient,
+        )
+    }
+}
+
+/// Pools and prepared arguments of an arbitrage between two pools.
+pub(crate) struct ArbPools<'a> {
+    pub pool_a: &'a str,
+    pub pool_b: &'a str,
+    pub pool_a_argument: Argument,
+    pub pool_b_argument: Argument,
+    pub clock_argument: Argument,
+}
+
+/// Appends the commands of `FlashLoanContract::arb_between_pools` to the PTB.
+pub(crate) fn build_arb_between_pools(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    pools: ArbPools,
+    amount: f64,
+    min_profit: f64,
+    recipient: SuiAddress,
+) -> Result<()> {
+    let (pool_a, a_base, a_quote) = resolve_pool(config, pools.pool_a)?;
+    let (pool_b, b_base, b_quote) = resolve_pool(config, pools.pool_b)?;
+    let deep_coin = config
+        .require_deep_coin()?;
+    let deep_type = coin_type(deep_coin)?;
+
+    let borrowed_is_b_base = pool_b.base_coin == pool_a.base_coin && pool_b.quote_coin == pool_a.quote_coin;
+    let borrowed_is_b_quote = pool_b.quote_coin == pool_a.base_coin && pool_b.base_coin == pool_a.quote_coin;
+    if !borrowed_is_b_base && !borrowed_is_b_quote {
+        return Err(anyhow!(
+            "Pool {} must trade {} against {} to arbitrage with pool {}",
+            pools.pool_b, pool_a.base_coin, pool_a.quote_coin, pools.pool_a,
+        ));
+    }
+
+    let amount_input = a_base.to_units(amount);
+    let min_profit_input = a_base.to_units(min_profit);
+
+    // 1. Borrow X from pool A. The flash loan must be returned before the PTB ends.
+    let (borrowed, flash_loan) = borrow_flashloan_base(ptb, config, a_base, a_quote, pools.pool_a_argument, amount_input)?;
+
+    // 2. Swap X for Y in pool B.
+    let zero_deep = zero_coin(ptb, deep_type.clone())?;
+    let min_out = ptb.pure(0u64).with_context(|| "Failed to prepare min_out pure argument")?;
+    let (x_leftover, y_out, deep_leftover_b) = if borrowed_is_b_base {
+        let (base_out, quote_out, deep_out) = append_swap(ptb, config, "swap_exact_base_for_quote", b_base, b_quote, [pools.pool_b_argument, borrowed, zero_deep, min_out, pools.clock_argument])?;
+        (base_out, quote_out, deep_out)
+    } else {
+        let (base_out, quote_out, deep_out) = append_swap(ptb, config, "swap_exact_quote_for_base", b_base, b_quote, [pools.pool_b_argument, borrowed, zero_deep, min_out, pools.clock_argument])?;
+        (quote_out, base_out, deep_out)
+    };
+
+    // 3. Swap Y back for X in pool A.
+    let zero_deep = zero_coin(ptb, deep_type)?;
+    let (x_out, y_leftover, deep_leftover_a) = append_swap(ptb, config, "swap_exact_quote_for_base", a_base, a_quote, [pools.pool_a_argument, y_out, zero_deep, min_out, pools.clock_argument])?;
+    ptb.command(Command::MergeCoins(x_out, vec![x_leftover]));
+
+    // 4. Repay the flash loan with exactly the borrowed amount.
+    let amount_argument = ptb.pure(amount_input).with_context(|| "Failed to prepare amount pure argument")?;
+    let repayment = ptb.command(Command::SplitCoins(x_out, vec![amount_argument]));
+    return_flashloan_base(ptb, config, a_base, a_quote, pools.pool_a_argument, repayment, flash_loan)?;
+
+    // 5. Splitting `min_profit` off the leftover aborts the transaction if the profit is lower.
+    let min_profit_argument = ptb.pure(min_profit_input).with_context(|| "Failed to prepare min_profit pure argument")?;
+    let profit_check = ptb.command(Command::SplitCoins(x_out, vec![min_profit_argument]));
+    ptb.command(Command::MergeCoins(x_out, vec![profit_check]));
+
+    let recipient_argument = ptb.pure(recipient).with_context(|| "Failed to prepare recipient pure argument")?;
+    ptb.command(Command::TransferObjects(
+        vec![x_out, y_leftover, deep_leftover_a, deep_leftover_b],
+        recipient_argument,
+    ));
+
+    Ok(())
+}
+
+fn resolve_pool<'a>(config: &'a DeepBookConfig, pool_key: &str) -> Result<(&'a Pool, &'a Coin, &'a Coin)> {
+    let pool = config.require_pool(pool_key)?;
+    let base_coin = config.require_coin(&pool.base_coin)?;
+    let quote_coin = config.require_coin(&pool.quote_coin)?;
+
+    Ok((pool, base_coin, quote_coin))
+}
+
+fn coin_type(coin: &Coin) -> Result<TypeTag> {
+    TypeTag::from_str(&coin.type_).with_context(|| format!("Failed to parse coin type: {}", coin.type_))
+}
+
+fn borrow_flashloan_base(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    base_coin: &Coin,
+    quote_coin: &Coin,
+    pool_argument: Argument,
+    amount_input: u64,
+) -> Result<(Argument, Argument)> {
+    let amount_pure = ptb.pure(amount_input).with_context(|| "Failed to prepare amount pure argument")?;
+    let result = deepbook_move_call(
+        ptb,
+        config,
+        "pool",
+        "borrow_flashloan_base",
+        vec![coin_type(base_coin)?, coin_type(quote_coin)?],
+        vec![pool_argument, amount_pure],
+    )?;
+
+    Ok((nested_result(result, 0)?, nested_result(result, 1)?))
+}
+
+fn return_flashloan_base(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    base_coin: &Coin,
+    quote_coin: &Coin,
+    pool_argument: Argument,
+    coin: Argument,
+    flash_loan: Argument,
+) -> Result<()> {
+    deepbook_move_call(
+        ptb,
+        config,
+        "pool",
+        "return_flashloan_base",
+        vec![coin_type(base_coin)?, coin_type(quote_coin)?],
+        vec![pool_argument, coin, flash_loan],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::{ObjectID, SequenceNumber};
+    use sui_sdk::types::transaction::ObjectArg;
+    use crate::utils::constants::PoolMap;
+
+    fn shared_argument(ptb: &mut ProgrammableTransactionBuilder) -> Argument {
+        ptb.obj(ObjectArg::SharedObject {
+            id: ObjectID::random(),
+            initial_shared_version: SequenceNumber::from_u64(1),
+            mutable: true,
+        }).unwrap()
+    }
+
+    /// Commands of the PTB, rendered as `module::function` for Move calls.
+    fn command_names(ptb: ProgrammableTransactionBuilder) -> Vec<String> {
+        ptb.finish()
+            .commands
+            .iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => format!("{}::{}", call.module, call.function),
+                Command::SplitCoins(..) => "SplitCoins".to_string(),
+                Command::MergeCoins(..) => "MergeCoins".to_string(),
+                Command::TransferObjects(..) => "TransferObjects".to_string(),
+                other => format!("{:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arb_between_pools_call_sequence() {
+        let mut pools = PoolMap::new();
+        pools.insert("SUI_DBUSDC".to_string(), Pool {
+            address: ObjectID::random().to_string(),
+            base_coin: "SUI".to_string(),
+            quote_coin: "DBUSDC".to_string(),
+        });
+        pools.insert("DBUSDC_SUI".to_string(), Pool {
+            address: ObjectID::random().to_string(),
+            base_coin: "DBUSDC".to_string(),
+            quote_coin: "SUI".to_string(),
+        });
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, Some(pools)).unwrap();
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let arb_pools = ArbPools {
+            pool_a: "SUI_DBUSDC",
+            pool_b: "DBUSDC_SUI",
+            pool_a_argument: shared_argument(&mut ptb),
+            pool_b_argument: shared_argument(&mut ptb),
+            clock_argument: shared_argument(&mut ptb),
+        };
+        build_arb_between_pools(&mut ptb, &config, arb_pools, 10.0, 0.1, SuiAddress::ZERO).unwrap();
+
+        assert_eq!(command_names(ptb), vec![
+            "pool::borrow_flashloan_base",
+            "coin::zero",
+            "pool::swap_exact_quote_for_base",
+            "coin::zero",
+            "pool::swap_exact_quote_for_base",
+            "MergeCoins",
+            "SplitCoins",
+            "pool::return_flashloan_base",
+            "SplitCoins",
+            "MergeCoins",
+            "TransferObjects",
+        ]);
+    }
+
+    #[test]
+    fn test_arb_between_pools_rejects_unrelated_pools() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let arb_pools = ArbPools {
+            pool_a: "SUI_DBUSDC",
+            pool_b: "DEEP_SUI",
+            pool_a_argument: shared_argument(&mut ptb),
+            pool_b_argument: shared_argument(&mut ptb),
+            clock_argument: shared_argument(&mut ptb),
+        };
+
+        assert!(build_arb_between_pools(&mut ptb, &config, arb_pools, 10.0, 0.1, SuiAddress::ZERO).is_err());
+    }
 }
@@ -3,17 +3,369 @@
 //
 // This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
 
+use std::str::FromStr;
 use std::sync::Arc;
+use anyhow::{Context, Result};
 use sui_sdk::SuiClient;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{Argument, Command};
+use sui_sdk::types::TypeTag;
 use crate::DeepBookConfig;
+use crate::transactions::balance_manager::BalanceManagerContract;
+use crate::transactions::deepbook::OrderPool;
+use crate::utils::transactions::{deepbook_move_call, prepare_shared_object_arguments};
+use crate::utils::types::FeeRate;
 
 pub struct GovernanceContract {
     client: Arc<SuiClient>,
     config: Arc<DeepBookConfig>,
+    balance_manager_contract: Arc<BalanceManagerContract>,
 }
 
 impl GovernanceContract {
-    pub fn new(client: Arc<SuiClient>, config: Arc<DeepBookConfig>) -> Self {
-        GovernanceContract { client, config }
+    pub fn new(client: Arc<SuiClient>, config: Arc<DeepBookConfig>, balance_manager_contract: Arc<BalanceManagerContract>) -> Self {
+        GovernanceContract { client, config, balance_manager_contract }
+    }
+
+    /// Unstake all DEEP of a balance manager from a pool.
+    /// `pool::unstake` returns no coin: DeepBook settles the unstaked DEEP back into the manager.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    ///
+    /// # Returns
+    /// The prepared balance manager argument, or an error.
+    pub async fn unstake(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<Argument> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let objects = self.prepare_objects(ptb, &pool, manager_key).await?;
+
+        append_unstake(ptb, &self.config, &pool, objects)?;
+
+        Ok(objects[1])
+    }
+
+    /// Unstake all DEEP of a balance manager from a pool and transfer it out of the manager.
+    /// The withdrawal needs the manager's owner as the sender.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `stake` - The total (active and inactive) stake of the manager, in DEEP base units.
+    /// * `recipient` - The address receiving the DEEP.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn unstake_and_transfer(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        stake: u64,
+        recipient: SuiAddress,
+    ) -> Result<()> {
+        let manager_argument = self.unstake(ptb, pool_key, manager_key).await?;
+
+        append_withdraw_and_transfer(ptb, &self.config, manager_argument, stake, recipient)
+    }
+
+    /// Stake DEEP of a balance manager in a pool and vote for a proposal in the same PTB,
+    /// sharing one balance manager argument and one trade proof. The DEEP is taken from the
+    /// manager's balance. Stake only counts as voting power once it becomes active in the next
+    /// epoch, so the vote carries the manager's already active stake.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `stake_amount` - The amount of DEEP to stake, in DEEP base units.
+    /// * `proposal_id` - The id of the proposal to vote for.
+    ///
+    /// # Returns
+    /// The prepared balance manager argument, or an error.
+    pub async fn stake_and_vote(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        stake_amount: u64,
+        proposal_id: ObjectID,
+    ) -> Result<Argument> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let objects = self.prepare_objects(ptb, &pool, manager_key).await?;
+
+        append_stake(ptb, &self.config, &pool, objects, stake_amount)?;
+        append_vote(ptb, &self.config, &pool, objects, proposal_id)?;
+
+        Ok(objects[1])
+    }
+
+    /// Submit a governance proposal for new trade parameters of a pool. The balance manager
+    /// needs active stake in the pool.
+    ///
+    /// # Arguments
+    /// * `ptb` - ProgrammableTransactionBuilder instance.
+    /// * `pool_key` - The key to identify the pool.
+    /// * `manager_key` - The key of the balance manager.
+    /// * `taker_fee` - The proposed taker fee rate.
+    /// * `maker_fee` - The proposed maker fee rate.
+    /// * `stake_required` - The proposed stake required for reduced fees, in DEEP base units.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error.
+    pub async fn submit_proposal(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+        taker_fee: FeeRate,
+        maker_fee: FeeRate,
+        stake_required: u64,
+    ) -> Result<()> {
+        let pool = OrderPool::from_config(&self.config, pool_key)?;
+        let objects = self.prepare_objects(ptb, &pool, manager_key).await?;
+
+        append_submit_proposal(ptb, &self.config, &pool, objects, taker_fee, maker_fee, stake_required)
+    }
+
+    /// Prepares the pool, balance manager and trade proof arguments of a governance call.
+    async fn prepare_objects(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool: &OrderPool,
+        manager_key: &str,
+    ) -> Result<[Argument; 3]> {
+        let manager = self.config.require_balance_manager(manager_key)?;
+
+        let arguments = prepare_shared_object_arguments(
+            &*self.client,
+            ptb,
+            &[(&pool.address, true), (&manager.address, true)],
+        ).await.with_context(|| "Failed to prepare pool and manager arguments")?;
+        let trade_proof_argument = self.balance_manager_contract
+            .generate_proof(ptb, manager_key, arguments[1])
+            .await.with_context(|| "Failed to generate trade proof")?;
+
+        Ok([arguments[0], arguments[1], trade_proof_argument])
+    }
+}
+
+/// Appends the `pool::stake` call of `amount` DEEP base units to the PTB.
+/// `objects` are the pool, balance manager and trade proof arguments.
+pub(crate) fn append_stake(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    pool: &OrderPool,
+    objects: [Argument; 3],
+    amount: u64,
+) -> Result<()> {
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+    let amount_argument = ptb.pure(amount).with_context(|| "Failed to prepare stake amount pure argument")?;
+
+    let mut arguments = objects.to_vec();
+    arguments.push(amount_argument);
+    deepbook_move_call(ptb, config, "pool", "stake", vec![base_coin_type, quote_coin_type], arguments)?;
+
+    Ok(())
+}
+
+/// Appends the `pool::vote` call for `proposal_id` to the PTB.
+/// `objects` are the pool, balance manager and trade proof arguments.
+pub(crate) fn append_vote(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    pool: &OrderPool,
+    objects: [Argument; 3],
+    proposal_id: ObjectID,
+) -> Result<()> {
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+    let proposal_argument = ptb.pure(proposal_id).with_context(|| "Failed to prepare proposal id pure argument")?;
+
+    let mut arguments = objects.to_vec();
+    arguments.push(proposal_argument);
+    deepbook_move_call(ptb, config, "pool", "vote", vec![base_coin_type, quote_coin_type], arguments)?;
+
+    Ok(())
+}
+
+/// Appends the `pool::submit_proposal` call to the PTB, with the fee rates in their
+/// `FLOAT_SCALAR` encoding. `objects` are the pool, balance manager and trade proof arguments.
+pub(crate) fn append_submit_proposal(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    pool: &OrderPool,
+    objects: [Argument; 3],
+    taker_fee: FeeRate,
+    maker_fee: FeeRate,
+    stake_required: u64,
+) -> Result<()> {
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+
+    let mut arguments = objects.to_vec();
+    for value in [taker_fee.as_float_scalar(), maker_fee.as_float_scalar(), stake_required] {
+        arguments.push(ptb.pure(value).with_context(|| "Failed to prepare proposal pure argument")?);
+    }
+    deepbook_move_call(ptb, config, "pool", "submit_proposal", vec![base_coin_type, quote_coin_type], arguments)?;
+
+    Ok(())
+}
+
+/// Appends the `pool::unstake` call to the PTB.
+/// `objects` are the pool, balance manager and trade proof arguments.
+pub(crate) fn append_unstake(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    pool: &OrderPool,
+    objects: [Argument; 3],
+) -> Result<()> {
+    let (base_coin_type, quote_coin_type) = pool.coin_types()?;
+
+    deepbook_move_call(ptb, config, "pool", "unstake", vec![base_coin_type, quote_coin_type], objects.to_vec())?;
+
+    Ok(())
+}
+
+/// Appends a `balance_manager::withdraw<DEEP>` of `amount` and transfers the coin to `recipient`.
+pub(crate) fn append_withdraw_and_transfer(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    manager_argument: Argument,
+    amount: u64,
+    recipient: SuiAddress,
+) -> Result<()> {
+    let deep_coin = config
+        .require_deep_coin()?;
+    let deep_type = TypeTag::from_str(&deep_coin.type_)
+        .with_context(|| format!("Failed to parse coin type: {}", deep_coin.type_))?;
+
+    let amount_argument = ptb.pure(amount).with_context(|| "Failed to prepare amount pure argument")?;
+    let withdrawn = deepbook_move_call(
+        ptb,
+        config,
+        "balance_manager",
+        "withdraw",
+        vec![deep_type],
+        vec![manager_argument, amount_argument],
+    )?;
+
+    let recipient_argument = ptb.pure(recipient).with_context(|| "Failed to prepare recipient pure argument")?;
+    ptb.command(Command::TransferObjects(vec![withdrawn], recipient_argument));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::SequenceNumber;
+    use sui_sdk::types::transaction::{CallArg, ObjectArg};
+
+    fn shared_argument(ptb: &mut ProgrammableTransactionBuilder) -> Argument {
+        ptb.obj(ObjectArg::SharedObject {
+            id: ObjectID::random(),
+            initial_shared_version: SequenceNumber::from_u64(1),
+            mutable: true,
+        }).unwrap()
+    }
+
+    /// Commands of the PTB, rendered as `module::function` for Move calls.
+    fn command_names(ptb: ProgrammableTransactionBuilder) -> Vec<String> {
+        ptb.finish()
+            .commands
+            .iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => format!("{}::{}", call.module, call.function),
+                Command::TransferObjects(..) => "TransferObjects".to_string(),
+                other => format!("{:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unstake_settles_into_manager() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "DEEP_SUI").unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let objects = [0, 1, 2].map(|_| shared_argument(&mut ptb));
+
+        append_unstake(&mut ptb, &config, &pool, objects).unwrap();
+
+        // The unstaked DEEP is already back in the manager, so no deposit follows
+        assert_eq!(command_names(ptb), vec!["pool::unstake"]);
+    }
+
+    #[test]
+    fn test_unstake_and_transfer_withdraws_after_unstake() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "DEEP_SUI").unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let objects = [0, 1, 2].map(|_| shared_argument(&mut ptb));
+
+        append_unstake(&mut ptb, &config, &pool, objects).unwrap();
+        append_withdraw_and_transfer(&mut ptb, &config, objects[1], 1_500_000, SuiAddress::ZERO).unwrap();
+
+        assert_eq!(command_names(ptb), vec![
+            "pool::unstake",
+            "balance_manager::withdraw",
+            "TransferObjects",
+        ]);
+    }
+
+    #[test]
+    fn test_stake_precedes_vote_with_one_proof() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "DEEP_SUI").unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let [pool_argument, manager_argument] = [0, 1].map(|_| shared_argument(&mut ptb));
+        let trade_proof_argument = deepbook_move_call(
+            &mut ptb,
+            &config,
+            "balance_manager",
+            "generate_proof_as_owner",
+            vec![],
+            vec![manager_argument],
+        ).unwrap();
+        let objects = [pool_argument, manager_argument, trade_proof_argument];
+
+        append_stake(&mut ptb, &config, &pool, objects, 100_000_000).unwrap();
+        append_vote(&mut ptb, &config, &pool, objects, ObjectID::random()).unwrap();
+
+        let calls: Vec<_> = ptb
+            .finish()
+            .commands
+            .into_iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => call,
+                other => panic!("unexpected command: {:?}", other),
+            })
+            .collect();
+        let functions: Vec<_> = calls.iter().map(|call| call.function.as_str()).collect();
+        assert_eq!(functions, vec!["generate_proof_as_owner", "stake", "vote"]);
+        // Both pool calls take the proof of the only proof command and the same manager
+        assert!(calls[1..].iter().all(|call| call.arguments[1..3] == [manager_argument, trade_proof_argument]));
+    }
+
+    #[test]
+    fn test_submit_proposal_encodes_fee_rates() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let pool = OrderPool::from_config(&config, "DEEP_SUI").unwrap();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let objects = [0, 1, 2].map(|_| shared_argument(&mut ptb));
+
+        append_submit_proposal(&mut ptb, &config, &pool, objects, FeeRate::from_bps(10.0), FeeRate::from_bps(5.0), 100_000_000)
+            .unwrap();
+
+        let transaction = ptb.finish();
+        for value in [1_000_000u64, 500_000, 100_000_000] {
+            assert!(transaction.inputs.contains(&CallArg::Pure(bcs::to_bytes(&value).unwrap())));
+        }
     }
 }
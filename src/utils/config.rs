@@ -3,15 +3,39 @@
 //
 // This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
 
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+use anyhow::{anyhow, bail, Context, Result};
+use futures::{stream, StreamExt};
+use log::warn;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::transaction::ObjectArg;
+use sui_types::{TypeTag, SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION};
 use crate::utils::constants::{
     BalanceManager, BalanceManagerMap, Coin, CoinMap, Pool, PoolMap, MAINNET_COINS,
     MAINNET_PACKAGE_IDS, MAINNET_POOLS, TESTNET_COINS, TESTNET_PACKAGE_IDS, TESTNET_POOLS,
 };
+use crate::utils::error::DeepBookError;
+use crate::utils::transactions::RegistryReader;
+use crate::utils::types::{registered_pools_from_fields, RegisteredPool};
 
 pub const FLOAT_SCALAR: u64 = 1_000_000_000;
 pub const MAX_TIMESTAMP: u64 = u64::MAX;
 pub const GAS_BUDGET: f64 = 0.5 * 500_000_000.0;
 pub const DEEP_SCALAR: u64 = 1_000_000;
+/// Multiplier applied to fees paid in the input coin instead of DEEP.
+pub const FEE_PENALTY_MULTIPLIER: f64 = 1.25;
+/// Margin added on top of estimated DEEP fees when recommending a deposit, as a fraction.
+pub const DEEP_DEPOSIT_SAFETY_MARGIN: f64 = 0.1;
+/// DeepBook package version the configured package ids and Move calls are written against.
+pub const DEEPBOOK_VERSION: u64 = 1;
+/// Default bound on a single full-node request of a `DeepBookClient`.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Most pages of events a `DeepBookClient` read pages through before giving up, at 50 events a page.
+pub const MAX_EVENT_PAGES: usize = 100;
+/// Most coin metadata requests `DeepBookConfig::from_chain` has in flight at once.
+const METADATA_CONCURRENCY: usize = 10;
 
 /// Represents the configuration for the DeepBook environment.
 #[derive(Debug)]
@@ -19,11 +43,19 @@ pub struct DeepBookConfig {
     pub coins: CoinMap,
     pub pools: PoolMap,
     pub balance_managers: BalanceManagerMap,
-    pub address: String,
+    /// The user address, parsed once at construction; the default sender of dev-inspected reads.
+    sender: SuiAddress,
     pub deepbook_package_id: String,
     pub registry_id: String,
     pub deep_treasury_id: String,
     pub admin_cap: Option<String>,
+    /// Expiration timestamp meaning "never expires" for orders on this network.
+    pub max_timestamp: u64,
+    /// Id of the clock object passed to time-dependent Move calls, `0x6` on public networks.
+    pub clock_object_id: ObjectID,
+    /// Initial shared version of the clock object, `SUI_CLOCK_OBJECT_SHARED_VERSION` on public
+    /// networks. Set it with `clock_object_id` for a network with a custom genesis.
+    pub clock_initial_shared_version: SequenceNumber,
 }
 
 impl DeepBookConfig {
@@ -33,6 +65,10 @@ impl DeepBookConfig {
     /// * `env` - The environment (`mainnet` or `testnet`).
     /// * `address` - The user address.
     /// * `admin_cap` - Optional admin capability.
+    ///
+    /// # Returns
+    /// The configuration, or an error naming the invalid address, so a bad address fails here
+    /// instead of at every read and transaction.
     pub fn new(
         env: &str,
         address: String,
@@ -40,6 +76,21 @@ impl DeepBookConfig {
         balance_managers: Option<BalanceManagerMap>,
         coins: Option<CoinMap>,
         pools: Option<PoolMap>,
+    ) -> Result<Self> {
+        let sender = parse_address(&address)
+            .with_context(|| format!("Invalid address in configuration: {:?}", address))?;
+
+        Ok(Self::for_sender(env, sender, admin_cap, balance_managers, coins, pools))
+    }
+
+    /// Creates a new `DeepBookConfig` like `new`, for an already parsed address.
+    fn for_sender(
+        env: &str,
+        sender: SuiAddress,
+        admin_cap: Option<String>,
+        balance_managers: Option<BalanceManagerMap>,
+        coins: Option<CoinMap>,
+        pools: Option<PoolMap>,
     ) -> Self {
         let (default_coins, default_pools, package_ids) = match env {
             "mainnet" => (&MAINNET_COINS, &MAINNET_POOLS, &MAINNET_PACKAGE_IDS),
@@ -50,47 +101,352 @@ impl DeepBookConfig {
             coins: coins.unwrap_or_else(|| (*default_coins).clone()),
             pools: pools.unwrap_or_else(|| (*default_pools).clone()),
             balance_managers: balance_managers.unwrap_or_default(),
-            address,
+            sender,
             deepbook_package_id: package_ids.deepbook_package_id.to_string(),
             registry_id: package_ids.registry_id.to_string(),
             deep_treasury_id: package_ids.deep_treasury_id.to_string(),
             admin_cap,
+            max_timestamp: MAX_TIMESTAMP,
+            clock_object_id: SUI_CLOCK_OBJECT_ID,
+            clock_initial_shared_version: SUI_CLOCK_OBJECT_SHARED_VERSION,
         }
     }
 
+    /// Creates a new `DeepBookConfig` whose pools and coins are read from the on-chain registry,
+    /// so pools listed after this release are usable without a code update. Registered pools and
+    /// coins are added to the network's defaults, keyed `BASE_QUOTE` and by coin struct name
+    /// (e.g. `USDC`), and their scalars read from the coin metadata. The defaults are kept alone
+    /// if the registry can't be read, and pools of a coin whose metadata can't be read are skipped.
+    ///
+    /// # Arguments
+    /// * `reader` - The Sui client, or another `RegistryReader`, to read the registry with.
+    /// * `network` - The network (`mainnet` or `testnet`).
+    /// * `address` - The user address.
+    ///
+    /// # Returns
+    /// The configuration, or an error if `address` is not a valid Sui address.
+    pub async fn from_chain<R: RegistryReader + ?Sized>(reader: &R, network: &str, address: String) -> Result<Self> {
+        let mut config = Self::new(network, address, None, None, None, None)?;
+        if let Err(err) = config.load_registry(reader).await {
+            warn!("Failed to read the DeepBook registry, using the built-in pools and coins: {:#}", err);
+        }
+
+        Ok(config)
+    }
+
+    /// Adds the pools of the on-chain registry, and their coins, to the configuration.
+    /// The metadata of the new coins is fetched concurrently.
+    async fn load_registry<R: RegistryReader + ?Sized>(&mut self, reader: &R) -> Result<()> {
+        let fields = reader.registry_pool_fields(&self.registry_id).await?;
+        let registered = registered_pools_from_fields(&fields)?;
+
+        let mut coin_types: Vec<&String> = vec![];
+        for pool in &registered {
+            for coin_type in [&pool.base_type, &pool.quote_type] {
+                if !coin_types.contains(&coin_type) && find_coin_key(&self.coins, coin_type).is_none() {
+                    coin_types.push(coin_type);
+                }
+            }
+        }
+        let decimals: Vec<Result<Option<u8>>> = stream::iter(&coin_types)
+            .map(|coin_type| reader.coin_decimals(coin_type))
+            .buffered(METADATA_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut scalars = HashMap::new();
+        for (coin_type, decimals) in coin_types.into_iter().zip(decimals) {
+            match decimals {
+                Ok(Some(decimals)) => {
+                    scalars.insert(coin_type.clone(), 10u64.pow(decimals as u32));
+                }
+                Ok(None) => warn!("No coin metadata found for registered coin: {}", coin_type),
+                Err(err) => warn!("Failed to fetch metadata of registered coin {}: {:#}", coin_type, err),
+            }
+        }
+        merge_registered_pools(&mut self.coins, &mut self.pools, &registered, &scalars);
+
+        Ok(())
+    }
+
+    /// The user address, the default sender of dev-inspected reads.
+    pub fn sender(&self) -> SuiAddress {
+        self.sender
+    }
+
+    /// Rebuilds the configuration for another network, e.g. to promote a setup tested on testnet
+    /// to mainnet. The address and balance managers are kept, while coins, pools and package ids
+    /// are replaced by the network's defaults, so a pool key resolves to that network's pool.
+    /// Balance managers are network-specific objects: keep only the ones that exist on `network`.
+    /// The admin cap is dropped, as it can't be valid on both networks.
+    ///
+    /// # Arguments
+    /// * `network` - The network to switch to (`mainnet` or `testnet`).
+    ///
+    /// # Returns
+    /// The configuration for `network`.
+    pub fn switch_network(&self, network: &str) -> DeepBookConfig {
+        DeepBookConfig::for_sender(network, self.sender, None, Some(self.balance_managers.clone()), None, None)
+    }
+
+    /// Checks the configuration for mistakes that would otherwise silently corrupt orders:
+    /// every coin scalar must be a power of ten and every coin type defined at its coin address.
+    ///
+    /// # Returns
+    /// Ok(()) if the configuration is valid, or an error naming the offending coin.
+    pub fn validate(&self) -> Result<()> {
+        let mut coin_keys: Vec<&String> = self.coins.keys().collect();
+        coin_keys.sort();
+        for coin_key in coin_keys {
+            let coin = &self.coins[coin_key];
+            if !coin.scalar_is_power_of_ten() {
+                bail!("Scalar {} of coin {} is not a power of ten", coin.scalar, coin_key);
+            }
+            coin.validate().with_context(|| format!("Invalid coin: {}", coin_key))?;
+        }
+
+        Ok(())
+    }
+
+    /// The read-only shared object argument of the configured clock.
+    pub fn clock_object_arg(&self) -> ObjectArg {
+        ObjectArg::SharedObject {
+            id: self.clock_object_id,
+            initial_shared_version: self.clock_initial_shared_version,
+            mutable: false,
+        }
+    }
+
+    /// Returns the expiration timestamp to use for orders that should never expire.
+    pub fn no_expiration(&self) -> u64 {
+        self.max_timestamp
+    }
+
     /// Retrieves a coin by its key.
     pub fn get_coin(&self, key: &str) -> Option<&Coin> {
         self.coins.get(key)
     }
 
+    /// Retrieves the DEEP coin by its well-known type suffix `::deep::DEEP`, whatever its key,
+    /// falling back to the coin keyed `DEEP`.
+    pub fn deep_coin(&self) -> Option<&Coin> {
+        self.coins
+            .values()
+            .find(|coin| coin.type_.ends_with("::deep::DEEP"))
+            .or_else(|| self.coins.get("DEEP"))
+    }
+
+    /// Retrieves the DEEP coin as `deep_coin` does, or a `DeepBookError::DeepCoinUnavailable`
+    /// for operations that can't proceed without it.
+    pub fn require_deep_coin(&self) -> Result<&Coin, DeepBookError> {
+        self.deep_coin().ok_or(DeepBookError::DeepCoinUnavailable)
+    }
+
+    /// Retrieves the key of the DEEP coin, as found by `deep_coin`.
+    pub fn deep_coin_key(&self) -> Option<&str> {
+        let deep_coin = self.deep_coin()?;
+        self.coins
+            .iter()
+            .find(|(_, coin)| *coin == deep_coin)
+            .map(|(key, _)| key.as_str())
+    }
+
+    /// Returns the scalar of the DEEP coin, used to scale fees and stakes.
+    pub fn deep_scalar(&self) -> u64 {
+        self.deep_coin().map_or(DEEP_SCALAR, |coin| coin.scalar)
+    }
+
     /// Retrieves a pool by its key.
     pub fn get_pool(&self, key: &str) -> Option<&Pool> {
         self.pools.get(key)
     }
 
+    /// Retrieves a coin by its key, ignoring ASCII case, e.g. `"dbusdc"` for `"DBUSDC"`.
+    /// An exact match wins; otherwise the key must match a single coin case-insensitively.
+    pub fn get_coin_ci(&self, key: &str) -> Option<&Coin> {
+        get_ci(&self.coins, key)
+    }
+
+    /// Retrieves a pool by its key, ignoring ASCII case, e.g. `"deep_sui"` for `"DEEP_SUI"`.
+    /// An exact match wins; otherwise the key must match a single pool case-insensitively.
+    pub fn get_pool_ci(&self, key: &str) -> Option<&Pool> {
+        get_ci(&self.pools, key)
+    }
+
+    /// Resolves the DEEP reference pool used by `add_deep_price_point` for a target pool:
+    /// a pool trading DEEP against the target's quote coin (e.g. `DEEP_USDC` for USDC-quoted
+    /// pools), otherwise against its base coin. Pools trading DEEP themselves need none.
+    pub fn reference_pool_for(&self, target_pool_key: &str) -> Option<&str> {
+        let target = self.get_pool(target_pool_key)?;
+        let deep_key = self.deep_coin_key()?;
+        if target.base_coin == deep_key || target.quote_coin == deep_key {
+            return None;
+        }
+
+        let mut deep_pools: Vec<(&str, &str)> = self
+            .pools
+            .iter()
+            .filter_map(|(key, pool)| match (pool.base_coin == deep_key, pool.quote_coin == deep_key) {
+                (true, false) => Some((key.as_str(), pool.quote_coin.as_str())),
+                (false, true) => Some((key.as_str(), pool.base_coin.as_str())),
+                _ => None,
+            })
+            .collect();
+        deep_pools.sort_unstable();
+
+        [&target.quote_coin, &target.base_coin].into_iter().find_map(|coin| {
+            deep_pools
+                .iter()
+                .find(|(_, other_coin)| *other_coin == coin.as_str())
+                .map(|(key, _)| *key)
+        })
+    }
+
     /// Retrieves a balance manager by its key.
     pub fn get_balance_manager(&self, key: &str) -> Option<&BalanceManager> {
         self.balance_managers.get(key)
     }
+
+    /// Retrieves a coin by its key, or a `DeepBookError::CoinNotFound`.
+    pub fn require_coin(&self, key: &str) -> Result<&Coin, DeepBookError> {
+        self.get_coin(key).ok_or_else(|| DeepBookError::CoinNotFound(key.to_string()))
+    }
+
+    /// Retrieves a pool by its key, or a `DeepBookError::PoolNotFound`.
+    pub fn require_pool(&self, key: &str) -> Result<&Pool, DeepBookError> {
+        self.get_pool(key).ok_or_else(|| DeepBookError::PoolNotFound(key.to_string()))
+    }
+
+    /// Retrieves a balance manager by its key, or a `DeepBookError::ManagerNotFound`.
+    pub fn require_balance_manager(&self, key: &str) -> Result<&BalanceManager, DeepBookError> {
+        self.get_balance_manager(key).ok_or_else(|| DeepBookError::ManagerNotFound(key.to_string()))
+    }
+}
+
+/// Adds registered pools missing from `pools`, and their coins missing from `coins`. Known coins
+/// and pools keep their keys; new ones are keyed by coin struct name and `BASE_QUOTE`. Pools
+/// whose key is taken, or with a coin of unknown scalar, are skipped.
+pub(crate) fn merge_registered_pools(
+    coins: &mut CoinMap,
+    pools: &mut PoolMap,
+    registered: &[RegisteredPool],
+    scalars: &HashMap<String, u64>,
+) {
+    for registered_pool in registered {
+        let known_pool = pools.values().any(|pool| {
+            ObjectID::from_hex_literal(&pool.address).is_ok_and(|address| address == registered_pool.pool_id)
+        });
+        if known_pool {
+            continue;
+        }
+
+        let base_key = registered_coin_key(coins, &registered_pool.base_type, scalars);
+        let quote_key = registered_coin_key(coins, &registered_pool.quote_type, scalars);
+        let (base_key, quote_key) = match (base_key, quote_key) {
+            (Ok(base_key), Ok(quote_key)) if base_key != quote_key => (base_key, quote_key),
+            (Err(err), _) | (_, Err(err)) => {
+                warn!("Skipping registered pool {}: {}", registered_pool.pool_id, err);
+                continue;
+            }
+            (Ok(coin_key), Ok(_)) => {
+                warn!("Skipping registered pool {}: both coins are keyed {}", registered_pool.pool_id, coin_key);
+                continue;
+            }
+        };
+
+        let pool_key = format!("{}_{}", base_key, quote_key);
+        if pools.contains_key(&pool_key) {
+            warn!("Skipping registered pool {}: key {} is taken", registered_pool.pool_id, pool_key);
+            continue;
+        }
+        let new_coins = [(&base_key, &registered_pool.base_type), (&quote_key, &registered_pool.quote_type)];
+        for (coin_key, coin_type) in new_coins {
+            if let (false, Some(&scalar)) = (coins.contains_key(coin_key), scalars.get(coin_type)) {
+                // The type was parsed as a struct type when keyed, so this can't fail
+                if let Ok(coin) = Coin::from_type(coin_type, scalar) {
+                    coins.insert(coin_key.clone(), coin);
+                }
+            }
+        }
+        pools.insert(pool_key, Pool {
+            address: registered_pool.pool_id.to_hex_literal(),
+            base_coin: base_key,
+            quote_coin: quote_key,
+        });
+    }
+}
+
+/// Returns the key of a registered coin: its key in `coins` if known, otherwise its struct name.
+fn registered_coin_key(coins: &CoinMap, coin_type: &str, scalars: &HashMap<String, u64>) -> Result<String, String> {
+    if let Some(coin_key) = find_coin_key(coins, coin_type) {
+        return Ok(coin_key.to_string());
+    }
+    if !scalars.contains_key(coin_type) {
+        return Err(format!("unknown scalar of coin {}", coin_type));
+    }
+    let coin_key = match TypeTag::from_str(coin_type) {
+        Ok(TypeTag::Struct(tag)) => tag.name.to_string(),
+        _ => return Err(format!("invalid coin type {}", coin_type)),
+    };
+    if coins.contains_key(&coin_key) {
+        return Err(format!("coin key {} is taken", coin_key));
+    }
+
+    Ok(coin_key)
+}
+
+/// Finds the key of the coin of `coin_type`, comparing parsed types so short and full-length
+/// addresses match.
+fn find_coin_key<'a>(coins: &'a CoinMap, coin_type: &str) -> Option<&'a str> {
+    let coin_type = TypeTag::from_str(coin_type).ok()?;
+    coins
+        .iter()
+        .find(|(_, coin)| TypeTag::from_str(&coin.type_).is_ok_and(|type_| type_ == coin_type))
+        .map(|(key, _)| key.as_str())
+}
+
+/// Looks up a key exactly, then case-insensitively if that is unambiguous.
+fn get_ci<'a, V>(map: &'a HashMap<String, V>, key: &str) -> Option<&'a V> {
+    if let Some(value) = map.get(key) {
+        return Some(value);
+    }
+
+    let mut matches = map
+        .iter()
+        .filter(|(candidate, _)| candidate.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value);
+    match (matches.next(), matches.next()) {
+        (Some(value), None) => Some(value),
+        _ => None,
+    }
+}
+
+/// Parses a Sui address, either in full or in the short `0x`-prefixed form such as `0x1`.
+fn parse_address(address: &str) -> Result<SuiAddress> {
+    SuiAddress::from_str(address)
+        .or_else(|_| ObjectID::from_hex_literal(address).map(SuiAddress::from))
+        .map_err(|_| anyhow!("Not a Sui address"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use futures::future::BoxFuture;
     use crate::utils::constants::{MAINNET_COINS, TESTNET_COINS};
 
     #[test]
     fn test_config_creation_mainnet() {
         let config = DeepBookConfig::new(
             "mainnet",
-            "test_address".to_string(),
+            "0x7e57".to_string(),
             Some("admin_cap".to_string()),
             None,
             None,
             None,
-        );
+        ).unwrap();
 
-        assert_eq!(config.address, "test_address");
+        assert_eq!(config.sender(), parse_address("0x7e57").unwrap());
         assert_eq!(config.admin_cap, Some("admin_cap".to_string()));
         assert_eq!(
             config.deepbook_package_id,
@@ -103,14 +459,14 @@ mod tests {
     fn test_config_creation_testnet() {
         let config = DeepBookConfig::new(
             "testnet",
-            "test_address".to_string(),
+            "0x7e57".to_string(),
             Some("admin_cap".to_string()),
             None,
             None,
             None,
-        );
+        ).unwrap();
 
-        assert_eq!(config.address, "test_address");
+        assert_eq!(config.sender(), parse_address("0x7e57").unwrap());
         assert_eq!(config.admin_cap, Some("admin_cap".to_string()));
         assert_eq!(
             config.deepbook_package_id,
@@ -127,14 +483,14 @@ mod tests {
 
         let config = DeepBookConfig::new(
             "mainnet",
-            "custom_address".to_string(),
+            "0xc057".to_string(),
             None,
             Some(custom_balance_managers.clone()),
             Some(custom_coins.clone()),
             Some(custom_pools.clone()),
-        );
+        ).unwrap();
 
-        assert_eq!(config.address, "custom_address");
+        assert_eq!(config.sender(), parse_address("0xc057").unwrap());
         assert_eq!(config.balance_managers, custom_balance_managers);
         assert_eq!(config.coins, custom_coins);
         assert_eq!(config.pools, custom_pools);
@@ -144,12 +500,12 @@ mod tests {
     fn test_get_coin() {
         let config = DeepBookConfig::new(
             "testnet",
-            "test_address".to_string(),
+            "0x7e57".to_string(),
             None,
             None,
             None,
             None,
-        );
+        ).unwrap();
 
         let coin = config.get_coin("DEEP");
         assert!(coin.is_some());
@@ -162,16 +518,46 @@ mod tests {
         assert!(nonexistent_coin.is_none());
     }
 
+    #[test]
+    fn test_deep_coin_with_custom_key() {
+        let mut custom_coins = CoinMap::new();
+        let deep = TESTNET_COINS.get("DEEP").unwrap().clone();
+        custom_coins.insert("DEEP_TOKEN".to_string(), deep.clone());
+        custom_coins.insert("SUI".to_string(), TESTNET_COINS.get("SUI").unwrap().clone());
+
+        let config = DeepBookConfig::new(
+            "testnet",
+            "0x7e57".to_string(),
+            None,
+            None,
+            Some(custom_coins),
+            None,
+        ).unwrap();
+
+        assert!(config.get_coin("DEEP").is_none());
+        assert_eq!(config.deep_coin(), Some(&deep));
+        assert_eq!(config.deep_scalar(), deep.scalar);
+    }
+
+    #[test]
+    fn test_deep_coin_key() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        assert_eq!(config.deep_coin_key(), Some("DEEP"));
+
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, Some(CoinMap::new()), None).unwrap();
+        assert_eq!(config.deep_coin_key(), None);
+    }
+
     #[test]
     fn test_get_pool() {
         let config = DeepBookConfig::new(
             "testnet",
-            "test_address".to_string(),
+            "0x7e57".to_string(),
             None,
             None,
             None,
             None,
-        );
+        ).unwrap();
 
         let pool = config.get_pool("DEEP_SUI");
         assert!(pool.is_some());
@@ -184,6 +570,64 @@ mod tests {
         assert!(nonexistent_pool.is_none());
     }
 
+    #[test]
+    fn test_case_insensitive_lookup() {
+        let config = DeepBookConfig::new(
+            "testnet",
+            "0x7e57".to_string(),
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        assert!(config.get_pool("deep_sui").is_none());
+        assert_eq!(config.get_pool_ci("deep_sui"), config.get_pool("DEEP_SUI"));
+        assert_eq!(config.get_pool_ci("Sui_DbUsdc"), config.get_pool("SUI_DBUSDC"));
+        assert_eq!(config.get_coin_ci("dbusdc"), config.get_coin("DBUSDC"));
+        assert!(config.get_coin_ci("nonexistent").is_none());
+    }
+
+    #[test]
+    fn test_case_insensitive_lookup_prefers_exact_and_rejects_ambiguity() {
+        let sui = TESTNET_COINS.get("SUI").unwrap().clone();
+        let deep = TESTNET_COINS.get("DEEP").unwrap().clone();
+        let mut custom_coins = CoinMap::new();
+        custom_coins.insert("Sui".to_string(), sui.clone());
+        custom_coins.insert("SUI".to_string(), deep.clone());
+
+        let config = DeepBookConfig::new(
+            "testnet",
+            "0x7e57".to_string(),
+            None,
+            None,
+            Some(custom_coins),
+            None,
+        ).unwrap();
+
+        assert_eq!(config.get_coin_ci("SUI"), Some(&deep));
+        assert_eq!(config.get_coin_ci("Sui"), Some(&sui));
+        assert!(config.get_coin_ci("sui").is_none());
+    }
+
+    #[test]
+    fn test_reference_pool_for_mainnet_pools() {
+        let config = DeepBookConfig::new(
+            "mainnet",
+            "0x7e57".to_string(),
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        assert_eq!(config.reference_pool_for("SUI_USDC"), Some("DEEP_USDC"));
+        assert_eq!(config.reference_pool_for("WUSDT_USDC"), Some("DEEP_USDC"));
+        assert_eq!(config.reference_pool_for("NS_SUI"), Some("DEEP_SUI"));
+        assert_eq!(config.reference_pool_for("DEEP_SUI"), None);
+        assert_eq!(config.reference_pool_for("NONEXISTENT"), None);
+    }
+
     #[test]
     fn test_get_balance_manager() {
         let mut custom_balance_managers = BalanceManagerMap::new();
@@ -197,12 +641,12 @@ mod tests {
 
         let config = DeepBookConfig::new(
             "mainnet",
-            "test_address".to_string(),
+            "0x7e57".to_string(),
             None,
             Some(custom_balance_managers.clone()),
             None,
             None,
-        );
+        ).unwrap();
 
         let balance_manager = config.get_balance_manager("manager1");
         assert!(balance_manager.is_some());
@@ -215,16 +659,101 @@ mod tests {
         assert!(nonexistent_manager.is_none());
     }
 
+    #[test]
+    fn test_validate_coin_scalars() {
+        let config = DeepBookConfig::new("mainnet", "0x1".to_string(), None, None, None, None).unwrap();
+        assert!(config.validate().is_ok());
+
+        let mut coins = CoinMap::new();
+        coins.insert("USDC".to_string(), Coin { address: "0x1".to_string(), type_: "0x1::usdc::USDC".to_string(), scalar: 1_000_000 });
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, Some(coins.clone()), None).unwrap();
+        assert!(config.validate().is_ok());
+
+        coins.insert("BAD".to_string(), Coin { address: "0x2".to_string(), type_: "0x2::bad::BAD".to_string(), scalar: 1_500_000 });
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, Some(coins), None).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("BAD"));
+        assert!(err.contains("1500000"));
+
+        let mut coins = CoinMap::new();
+        coins.insert("USDT".to_string(), Coin { address: "0x1".to_string(), type_: "0x3::usdt::USDT".to_string(), scalar: 1_000_000 });
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, Some(coins), None).unwrap();
+        assert_eq!(config.validate().unwrap_err().to_string(), "Invalid coin: USDT");
+    }
+
+    #[test]
+    fn test_invalid_address_fails_at_config_build() {
+        let err = DeepBookConfig::new("testnet", String::new(), None, None, None, None).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid address in configuration: \"\"");
+        assert!(DeepBookConfig::new("testnet", "test_address".to_string(), None, None, None, None).is_err());
+
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        assert_eq!(config.sender().to_string(), format!("0x{:0>64}", 1));
+    }
+
+    #[test]
+    fn test_switch_network_keeps_address_and_managers() {
+        let mut balance_managers = BalanceManagerMap::new();
+        balance_managers.insert("MANAGER_1".to_string(), BalanceManager {
+            address: "0x1".to_string(),
+            trade_cap: None,
+        });
+        let testnet = DeepBookConfig::new("testnet", "0xabc".to_string(), None, Some(balance_managers), None, None)
+            .unwrap();
+
+        let mainnet = testnet.switch_network("mainnet");
+        assert_eq!(mainnet.deepbook_package_id, MAINNET_PACKAGE_IDS.deepbook_package_id);
+        assert_ne!(mainnet.deepbook_package_id, testnet.deepbook_package_id);
+        assert_eq!(mainnet.get_pool("DEEP_SUI"), MAINNET_POOLS.get("DEEP_SUI"));
+        assert_ne!(mainnet.get_pool("DEEP_SUI"), testnet.get_pool("DEEP_SUI"));
+        assert_eq!(mainnet.sender(), testnet.sender());
+        assert_eq!(mainnet.balance_managers, testnet.balance_managers);
+
+        let back = mainnet.switch_network("testnet");
+        assert_eq!(back.deepbook_package_id, testnet.deepbook_package_id);
+        assert_eq!(back.pools, testnet.pools);
+    }
+
+    #[test]
+    fn test_require_lookups_return_typed_errors() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+
+        assert!(config.require_coin("DEEP").is_ok());
+        assert!(config.require_pool("DEEP_SUI").is_ok());
+        assert_eq!(config.require_coin("NOPE"), Err(DeepBookError::CoinNotFound("NOPE".to_string())));
+        assert_eq!(config.require_pool("NOPE"), Err(DeepBookError::PoolNotFound("NOPE".to_string())));
+        assert_eq!(
+            config.require_balance_manager("NOPE").unwrap_err().to_string(),
+            "BalanceManager not found for key: NOPE",
+        );
+    }
+
+    #[test]
+    fn test_no_expiration() {
+        let mut config = DeepBookConfig::new(
+            "testnet",
+            "0x7e57".to_string(),
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+        assert_eq!(config.no_expiration(), MAX_TIMESTAMP);
+
+        config.max_timestamp = 1_844_674_407_370_955_161;
+        assert_eq!(config.no_expiration(), 1_844_674_407_370_955_161);
+    }
+
     #[test]
     fn test_invalid_env_defaults_to_testnet() {
         let config = DeepBookConfig::new(
             "unknown",
-            "test_address".to_string(),
+            "0x7e57".to_string(),
             None,
             None,
             None,
             None,
-        );
+        ).unwrap();
 
         assert_eq!(
             config.deepbook_package_id,
@@ -232,4 +761,125 @@ mod tests {
         );
         assert_eq!(config.coins.len(), TESTNET_COINS.len());
     }
+
+    const WAL_TYPE: &str = "0x8270feb7375eee355e64fdb69c50abb6b5f9393a722883c1cf45f8e26048810a::wal::WAL";
+
+    /// A `registry::PoolKey` entry of the registry's `pools` bag, as returned by the full node.
+    fn registry_entry(base: &str, quote: &str, pool_id: &str) -> serde_json::Value {
+        let type_name = |coin_type: &str| serde_json::json!({
+            "type": "0x1::type_name::TypeName",
+            "fields": { "name": coin_type.trim_start_matches("0x") },
+        });
+        serde_json::json!({
+            "id": { "id": "0x7" },
+            "name": {
+                "type": "0x1::registry::PoolKey",
+                "fields": { "base": type_name(base), "quote": type_name(quote) },
+            },
+            "value": pool_id,
+        })
+    }
+
+    /// A registry with the given pool entries, or none if `fields` is None, whose coin metadata
+    /// requests each take a second to answer.
+    struct MockRegistry {
+        fields: Option<Vec<serde_json::Value>>,
+        decimals: HashMap<String, u8>,
+        metadata_requests: AtomicUsize,
+    }
+
+    impl RegistryReader for MockRegistry {
+        fn registry_pool_fields<'a>(&'a self, _registry_id: &'a str) -> BoxFuture<'a, Result<Vec<serde_json::Value>>> {
+            Box::pin(async move { self.fields.clone().ok_or_else(|| anyhow!("connection refused")) })
+        }
+
+        fn coin_decimals<'a>(&'a self, coin_type: &'a str) -> BoxFuture<'a, Result<Option<u8>>> {
+            Box::pin(async move {
+                self.metadata_requests.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                match self.decimals.get(coin_type) {
+                    Some(decimals) => Ok(Some(*decimals)),
+                    None => Err(anyhow!("metadata of {} is unavailable", coin_type)),
+                }
+            })
+        }
+    }
+
+    #[test]
+    fn test_registry_entries_populate_maps() {
+        let mut config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let deep_type = config.get_coin("DEEP").unwrap().type_.clone();
+        let unknown_type = "0x3::unknown::UNKNOWN";
+        let fields = [
+            registry_entry(&deep_type, "0x2::sui::SUI", &config.get_pool("DEEP_SUI").unwrap().address),
+            registry_entry(WAL_TYPE, "0x2::sui::SUI", "0xa"),
+            registry_entry(unknown_type, "0x2::sui::SUI", "0xb"),
+        ];
+        let registered = registered_pools_from_fields(&fields).unwrap();
+        assert_eq!(registered[1].base_type, WAL_TYPE);
+        assert_eq!(registered[1].pool_id, ObjectID::from_hex_literal("0xa").unwrap());
+        let scalars = HashMap::from([(WAL_TYPE.to_string(), 1_000_000_000)]);
+
+        merge_registered_pools(&mut config.coins, &mut config.pools, &registered, &scalars);
+
+        assert_eq!(config.pools.len(), TESTNET_POOLS.len() + 1);
+        assert_eq!(config.get_pool("DEEP_SUI"), TESTNET_POOLS.get("DEEP_SUI"));
+        let wal_pool = config.get_pool("WAL_SUI").unwrap();
+        assert_eq!(wal_pool.address, ObjectID::from_hex_literal("0xa").unwrap().to_hex_literal());
+        assert_eq!(wal_pool.quote_coin, "SUI");
+        let wal = config.get_coin("WAL").unwrap();
+        assert_eq!(wal.type_, WAL_TYPE);
+        assert_eq!(wal.scalar, 1_000_000_000);
+        // The pool of a coin without metadata is skipped
+        assert!(config.get_coin("UNKNOWN").is_none());
+        assert!(config.get_pool("UNKNOWN_SUI").is_none());
+        assert!(config.validate().is_ok());
+
+        assert!(registered_pools_from_fields(&[serde_json::json!({ "name": fields[0]["name"] })]).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_from_chain_merges_registry_and_skips_coins_without_metadata() {
+        let defaults = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+        let deep_sui = defaults.get_pool("DEEP_SUI").unwrap();
+        let deep_type = &defaults.get_coin("DEEP").unwrap().type_;
+        let broken_type = "0x3::broken::BROKEN";
+        let registry = MockRegistry {
+            fields: Some(vec![
+                registry_entry(deep_type, "0x2::sui::SUI", &deep_sui.address),
+                registry_entry(WAL_TYPE, "0x2::sui::SUI", "0xa"),
+                registry_entry(broken_type, "0x2::sui::SUI", "0xb"),
+                registry_entry(WAL_TYPE, broken_type, "0xc"),
+            ]),
+            decimals: HashMap::from([(WAL_TYPE.to_string(), 9)]),
+            metadata_requests: AtomicUsize::new(0),
+        };
+
+        let started = tokio::time::Instant::now();
+        let config = DeepBookConfig::from_chain(&registry, "testnet", "0x1".to_string()).await.unwrap();
+
+        // One request per new coin, in flight together rather than one after another
+        assert_eq!(registry.metadata_requests.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(config.pools.len(), TESTNET_POOLS.len() + 1);
+        let wal_pool = config.get_pool("WAL_SUI").unwrap();
+        assert_eq!(wal_pool.address, ObjectID::from_hex_literal("0xa").unwrap().to_hex_literal());
+        assert_eq!(config.get_coin("WAL").unwrap().scalar, 1_000_000_000);
+        // The coin whose metadata failed is skipped with its pools, not the whole registry
+        assert!(config.get_coin("BROKEN").is_none());
+        assert!(config.get_pool("BROKEN_SUI").is_none());
+        assert!(config.get_pool("WAL_BROKEN").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_from_chain_falls_back_to_defaults() {
+        let registry = MockRegistry { fields: None, decimals: HashMap::new(), metadata_requests: AtomicUsize::new(0) };
+
+        let config = DeepBookConfig::from_chain(&registry, "testnet", "0x1".to_string()).await.unwrap();
+
+        assert_eq!(config.pools.len(), TESTNET_POOLS.len());
+        assert_eq!(config.coins.len(), TESTNET_COINS.len());
+        assert_eq!(registry.metadata_requests.load(Ordering::SeqCst), 0);
+        assert!(DeepBookConfig::from_chain(&registry, "testnet", "not an address".to_string()).await.is_err());
+    }
 }
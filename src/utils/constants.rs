@@ -5,6 +5,9 @@
 
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::str::FromStr;
+use anyhow::{bail, Context, Result};
+use sui_sdk::types::{base_types::ObjectID, TypeTag};
 
 pub type BalanceManagerMap = HashMap<String, BalanceManager>;
 pub type CoinMap = HashMap<String, Coin>;
@@ -25,6 +28,97 @@ pub struct Coin {
     pub scalar: u64,
 }
 
+impl Coin {
+    /// Describes a coin that is not in the configuration by its type and scalar,
+    /// e.g. a newly listed token. The address is the package of the coin type.
+    ///
+    /// # Arguments
+    /// * `coin_type` - The coin type, e.g. `0x2::sui::SUI`.
+    /// * `scalar` - The scalar of the coin, `10^decimals`.
+    pub fn from_type(coin_type: &str, scalar: u64) -> Result<Coin> {
+        let address = match TypeTag::from_str(coin_type) {
+            Ok(TypeTag::Struct(tag)) => ObjectID::from(tag.address),
+            _ => bail!("Coin type {} is not a valid struct type", coin_type),
+        };
+
+        Ok(Coin { address: address.to_hex_literal(), type_: coin_type.to_string(), scalar })
+    }
+
+    /// Returns the number of decimals of the coin, i.e. `log10(scalar)`.
+    pub fn decimals(&self) -> u8 {
+        self.scalar.checked_ilog10().unwrap_or(0) as u8
+    }
+
+    /// Whether the scalar is a power of ten (`10^decimals`), as all scaling math assumes.
+    pub fn scalar_is_power_of_ten(&self) -> bool {
+        self.scalar.checked_ilog10().is_some_and(|decimals| 10u64.pow(decimals) == self.scalar)
+    }
+
+    /// Checks that `type_` parses as a struct type defined at `address`, catching copy-paste
+    /// mistakes between coin entries. Addresses are compared parsed, so `0x2` matches the
+    /// full-length form.
+    ///
+    /// # Returns
+    /// Ok(()) if the coin is consistent, or an error describing the mismatch.
+    pub fn validate(&self) -> Result<()> {
+        let address = ObjectID::from_hex_literal(&self.address)
+            .with_context(|| format!("Invalid coin address: {}", self.address))?;
+        let type_address = match TypeTag::from_str(&self.type_) {
+            Ok(TypeTag::Struct(tag)) => ObjectID::from(tag.address),
+            _ => bail!("Coin type {} is not a valid struct type", self.type_),
+        };
+        if type_address != address {
+            bail!("Coin type {} is not defined at coin address {}", self.type_, self.address);
+        }
+
+        Ok(())
+    }
+
+    /// Converts a raw on-chain amount into a human-readable amount.
+    pub fn to_human(&self, raw: u64) -> f64 {
+        raw as f64 / self.scalar as f64
+    }
+
+    /// Converts a human-readable amount into a raw on-chain amount, rounded to the nearest unit.
+    pub fn to_units(&self, human: f64) -> u64 {
+        (human * self.scalar as f64).round() as u64
+    }
+
+    /// Converts a human-readable amount into a raw on-chain amount, like `to_units`.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_human(&self, human: f64) -> u64 {
+        self.to_units(human)
+    }
+
+    /// Converts a human-readable amount into a raw on-chain amount, truncated to whole units so
+    /// that a deposit never takes more than was asked for.
+    pub fn to_units_floor(&self, human: f64) -> u64 {
+        (human * self.scalar as f64) as u64
+    }
+}
+
+/// Formats a raw on-chain amount as a fixed-decimal string with exactly `coin.decimals()`
+/// decimal places, computed with integer arithmetic so it never uses exponent notation.
+pub fn format_coin_amount(raw: u64, coin: &Coin) -> String {
+    let decimals = coin.decimals() as u32;
+    if decimals == 0 {
+        return raw.to_string();
+    }
+    let unit = 10u64.pow(decimals);
+
+    format!("{}.{:0width$}", raw / unit, raw % unit, width = decimals as usize)
+}
+
+/// Formats a raw on-chain amount like `format_coin_amount`, without trailing zeros.
+pub fn format_coin_amount_trimmed(raw: u64, coin: &Coin) -> String {
+    let formatted = format_coin_amount(raw, coin);
+    if !formatted.contains('.') {
+        return formatted;
+    }
+
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
 /// Represents a pool in the DeepBook ecosystem.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Pool {
@@ -409,3 +503,87 @@ pub static MAINNET_POOLS: Lazy<PoolMap> = Lazy::new(|| {
 
     map
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coin_with_scalar(scalar: u64) -> Coin {
+        Coin {
+            address: String::from("0x2"),
+            type_: String::from("0x2::sui::SUI"),
+            scalar,
+        }
+    }
+
+    #[test]
+    fn test_coin_decimals() {
+        assert_eq!(coin_with_scalar(1_000_000).decimals(), 6);
+        assert_eq!(coin_with_scalar(100_000_000).decimals(), 8);
+        assert_eq!(coin_with_scalar(1_000_000_000).decimals(), 9);
+    }
+
+    #[test]
+    fn test_coin_to_human() {
+        assert_eq!(coin_with_scalar(1_000_000).to_human(1_500_000), 1.5);
+        assert_eq!(coin_with_scalar(100_000_000).to_human(25_000_000), 0.25);
+        assert_eq!(coin_with_scalar(1_000_000_000).to_human(3_000_000_000), 3.0);
+    }
+
+    #[test]
+    fn test_coin_to_units() {
+        assert_eq!(coin_with_scalar(1_000_000).to_units(1.5), 1_500_000);
+        assert_eq!(coin_with_scalar(100_000_000).to_units(0.25), 25_000_000);
+        // 0.3 * 1e9 is 299_999_999.99... in f64 and must not be truncated
+        assert_eq!(coin_with_scalar(1_000_000_000).to_units(0.3), 300_000_000);
+        assert_eq!(coin_with_scalar(1_000_000_000).from_human(0.3), 300_000_000);
+    }
+
+    #[test]
+    fn test_coin_to_units_floor() {
+        assert_eq!(coin_with_scalar(1_000_000).to_units_floor(1.5), 1_500_000);
+        assert_eq!(coin_with_scalar(1_000_000).to_units_floor(1.9999999), 1_999_999);
+        assert_eq!(coin_with_scalar(1_000_000).to_units(1.9999999), 2_000_000);
+    }
+
+    #[test]
+    fn test_coin_validate() {
+        assert!(coin_with_scalar(1_000_000_000).validate().is_ok());
+        for coin in TESTNET_COINS.values().chain(MAINNET_COINS.values()) {
+            assert!(coin.validate().is_ok(), "{:?}", coin);
+        }
+
+        // DBUSDT's type pasted under DBUSDC's address, with one digit changed
+        let mismatched = Coin {
+            address: String::from("0xf7152c05930480cd740d7311b5b8b45c6f488e3a53a11c3f74a6fac36a52e0d8"),
+            type_: String::from("0xf7152c05930480cd740d7311b5b8b45c6f488e3a53a11c3f74a6fac36a52e0d7::DBUSDT::DBUSDT"),
+            scalar: 1_000_000,
+        };
+        let err = mismatched.validate().unwrap_err().to_string();
+        assert!(err.contains("is not defined at coin address"), "{}", err);
+
+        let not_a_struct = Coin { type_: String::from("u64"), ..coin_with_scalar(1_000_000) };
+        assert!(not_a_struct.validate().is_err());
+    }
+
+    #[test]
+    fn test_format_coin_amount_dust() {
+        let sui = coin_with_scalar(1_000_000_000);
+
+        // 1 MIST, which round-trips through f64 as 1e-9
+        assert_eq!(format_coin_amount(1, &sui), "0.000000001");
+        assert_eq!(format_coin_amount_trimmed(1, &sui), "0.000000001");
+        assert_eq!(format_coin_amount(0, &sui), "0.000000000");
+        assert_eq!(format_coin_amount_trimmed(0, &sui), "0");
+    }
+
+    #[test]
+    fn test_format_coin_amount_large() {
+        let usdc = coin_with_scalar(1_000_000);
+
+        assert_eq!(format_coin_amount(u64::MAX, &usdc), "18446744073709.551615");
+        assert_eq!(format_coin_amount(12_345_000_000, &usdc), "12345.000000");
+        assert_eq!(format_coin_amount_trimmed(12_345_000_000, &usdc), "12345");
+        assert_eq!(format_coin_amount_trimmed(12_345_500_000, &usdc), "12345.5");
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+use std::fmt;
+use std::time::Duration;
+
+/// Errors raised by the SDK itself, as opposed to RPC or Move failures.
+/// They are returned inside `anyhow::Error`, so match them with `err.downcast_ref::<DeepBookError>()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeepBookError {
+    /// No coin is configured for the key.
+    CoinNotFound(String),
+    /// No pool is configured for the key.
+    PoolNotFound(String),
+    /// No balance manager is configured for the key.
+    ManagerNotFound(String),
+    /// The operation needs the DEEP coin (fees, stake, governance), but none is configured.
+    DeepCoinUnavailable,
+    /// A full-node request did not complete within the client's request timeout.
+    Timeout(Duration),
+}
+
+impl fmt::Display for DeepBookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeepBookError::CoinNotFound(key) => write!(f, "Coin not found for key: {}", key),
+            DeepBookError::PoolNotFound(key) => write!(f, "Pool not found for key: {}", key),
+            DeepBookError::ManagerNotFound(key) => write!(f, "BalanceManager not found for key: {}", key),
+            DeepBookError::DeepCoinUnavailable => write!(f, "DEEP coin is not configured"),
+            DeepBookError::Timeout(timeout) => write!(f, "Request timed out after {:?}", timeout),
+        }
+    }
+}
+
+impl std::error::Error for DeepBookError {}
+
+/// A known abort of the DeepBook Move package, decoded from its module and abort code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeepBookAbort {
+    InvalidPrice,
+    OrderBelowMinimumSize,
+    InvalidLotSize,
+    InvalidExpireTimestamp,
+    InvalidOrderType,
+    PostOnlyOrderCrossesBook,
+    FillOrKillNotFilled,
+    /// A taker order would match an order of the same balance manager with `CancelTaker`.
+    SelfMatchingNotAllowed,
+    InvalidOwner,
+    InvalidTrader,
+    InvalidProof,
+    InsufficientBalance,
+    EmptyOrderbook,
+    /// The reference pool of a DEEP price point is not whitelisted.
+    PoolNotWhitelisted,
+    PackageVersionDisabled,
+    MinimumQuantityOutNotMet,
+    /// An abort this SDK has no description for.
+    Unknown { module: String, code: u64 },
+}
+
+impl DeepBookAbort {
+    /// Maps an abort code of a DeepBook module to its variant.
+    ///
+    /// # Arguments
+    /// * `module` - The name of the aborting module, e.g. `pool`.
+    /// * `code` - The abort code.
+    pub fn from_abort(module: &str, code: u64) -> DeepBookAbort {
+        match (module, code) {
+            ("order_info", 0) => DeepBookAbort::InvalidPrice,
+            ("order_info", 1) | ("book", 5) => DeepBookAbort::OrderBelowMinimumSize,
+            ("order_info", 2) | ("book", 6) => DeepBookAbort::InvalidLotSize,
+            ("order_info", 3) => DeepBookAbort::InvalidExpireTimestamp,
+            ("order_info", 4) => DeepBookAbort::InvalidOrderType,
+            ("order_info", 5) => DeepBookAbort::PostOnlyOrderCrossesBook,
+            ("order_info", 6) => DeepBookAbort::FillOrKillNotFilled,
+            ("order_info", 8) => DeepBookAbort::SelfMatchingNotAllowed,
+            ("balance_manager", 0) => DeepBookAbort::InvalidOwner,
+            ("balance_manager", 1) => DeepBookAbort::InvalidTrader,
+            ("balance_manager", 2) => DeepBookAbort::InvalidProof,
+            ("balance_manager", 3) => DeepBookAbort::InsufficientBalance,
+            ("book", 2) => DeepBookAbort::EmptyOrderbook,
+            ("pool", 7) => DeepBookAbort::PoolNotWhitelisted,
+            ("pool", 11) => DeepBookAbort::PackageVersionDisabled,
+            ("pool", 12) => DeepBookAbort::MinimumQuantityOutNotMet,
+            (module, code) => DeepBookAbort::Unknown { module: module.to_string(), code },
+        }
+    }
+
+    /// Decodes the abort of an execution error such as
+    /// `MoveAbort(MoveLocation { module: ModuleId { .., name: Identifier("pool") }, .. }, 7) in command 0`.
+    ///
+    /// # Returns
+    /// The abort, or None if the error is not a Move abort.
+    pub fn from_execution_error(error: &str) -> Option<DeepBookAbort> {
+        let location = error.split_once("MoveAbort(")?.1;
+        let module = location.split_once("name: Identifier(\"")?.1.split_once('"')?.0;
+        let code = location.rsplit_once("}, ")?.1.split_once(')')?.0.trim().parse().ok()?;
+
+        Some(DeepBookAbort::from_abort(module, code))
+    }
+}
+
+impl fmt::Display for DeepBookAbort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeepBookAbort::InvalidPrice => f.write_str("Order price is out of range or not a multiple of the tick size"),
+            DeepBookAbort::OrderBelowMinimumSize => f.write_str("Order quantity is below the pool's minimum size"),
+            DeepBookAbort::InvalidLotSize => f.write_str("Order quantity is not a multiple of the pool's lot size"),
+            DeepBookAbort::InvalidExpireTimestamp => f.write_str("Order expire timestamp is in the past"),
+            DeepBookAbort::InvalidOrderType => f.write_str("Invalid order type"),
+            DeepBookAbort::PostOnlyOrderCrossesBook => f.write_str("Post-only order would cross the order book"),
+            DeepBookAbort::FillOrKillNotFilled => f.write_str("Fill-or-kill order cannot be fully filled"),
+            DeepBookAbort::SelfMatchingNotAllowed => f.write_str("Order would match an order of the same balance manager"),
+            DeepBookAbort::InvalidOwner => f.write_str("Sender is not the owner of the balance manager"),
+            DeepBookAbort::InvalidTrader => f.write_str("Sender is not allowed to trade for the balance manager"),
+            DeepBookAbort::InvalidProof => f.write_str("Trade proof does not belong to the balance manager"),
+            DeepBookAbort::InsufficientBalance => f.write_str("Balance manager balance is too low"),
+            DeepBookAbort::EmptyOrderbook => f.write_str("Order book side is empty"),
+            DeepBookAbort::PoolNotWhitelisted => f.write_str("Reference pool is not whitelisted"),
+            DeepBookAbort::PackageVersionDisabled => f.write_str("DeepBook package version is disabled for the pool"),
+            DeepBookAbort::MinimumQuantityOutNotMet => f.write_str("Swap output is below the minimum quantity out"),
+            DeepBookAbort::Unknown { module, code } => write!(f, "DeepBook abort {} in module {}", code, module),
+        }
+    }
+}
+
+impl std::error::Error for DeepBookAbort {}
+
+/// Builds the error of a failed dev-inspect or transaction. Known DeepBook aborts are described
+/// ahead of the failed command and the raw error, and can be matched with
+/// `err.downcast_ref::<DeepBookAbort>()`.
+pub(crate) fn execution_error(error: &str) -> anyhow::Error {
+    let raw = anyhow::anyhow!("{}", error);
+    let Some(abort) = DeepBookAbort::from_execution_error(error) else {
+        return raw;
+    };
+
+    let message = match aborted_command(error) {
+        Some(command) => format!("{} (command {}): {}", abort, command, error),
+        None => format!("{}: {}", abort, error),
+    };
+    raw.context(abort).context(message)
+}
+
+/// The index of the PTB command that failed in an execution error such as `MoveAbort(..) in command 2`.
+///
+/// # Returns
+/// The command index, or None if the error doesn't name a Move abort and its command.
+pub(crate) fn aborted_command(error: &str) -> Option<usize> {
+    let command = error.split_once("MoveAbort(")?.1.rsplit_once(" in command ")?.1;
+    command.trim().split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_abort_codes() {
+        assert_eq!(DeepBookAbort::from_abort("order_info", 8), DeepBookAbort::SelfMatchingNotAllowed);
+        assert_eq!(DeepBookAbort::from_abort("balance_manager", 3), DeepBookAbort::InsufficientBalance);
+        assert_eq!(DeepBookAbort::from_abort("order_info", 1), DeepBookAbort::OrderBelowMinimumSize);
+        assert_eq!(DeepBookAbort::from_abort("book", 5), DeepBookAbort::OrderBelowMinimumSize);
+        assert_eq!(DeepBookAbort::from_abort("pool", 7), DeepBookAbort::PoolNotWhitelisted);
+        assert_eq!(DeepBookAbort::InsufficientBalance.to_string(), "Balance manager balance is too low");
+    }
+
+    #[test]
+    fn test_unknown_abort_falls_back() {
+        let abort = DeepBookAbort::from_abort("vault", 42);
+        assert_eq!(abort, DeepBookAbort::Unknown { module: "vault".to_string(), code: 42 });
+        assert_eq!(abort.to_string(), "DeepBook abort 42 in module vault");
+    }
+
+    #[test]
+    fn test_abort_from_execution_error() {
+        let error = "MoveAbort(MoveLocation { module: ModuleId { address: 2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809, name: Identifier(\"balance_manager\") }, function: 11, instruction: 42, function_name: Some(\"withdraw_with_proof\") }, 3) in command 2";
+        assert_eq!(DeepBookAbort::from_execution_error(error), Some(DeepBookAbort::InsufficientBalance));
+        assert_eq!(DeepBookAbort::from_execution_error("InsufficientGas"), None);
+
+        let err = execution_error(error);
+        assert_eq!(err.downcast_ref::<DeepBookAbort>(), Some(&DeepBookAbort::InsufficientBalance));
+        assert_eq!(err.to_string(), format!("Balance manager balance is too low (command 2): {}", error));
+        assert_eq!(execution_error("InsufficientGas").to_string(), "InsufficientGas");
+        assert_eq!(aborted_command(error), Some(2));
+        assert_eq!(aborted_command("InsufficientGas in command 1"), None);
+    }
+}
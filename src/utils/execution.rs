@@ -0,0 +1,469 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+use anyhow::{anyhow, Context, Result};
+use futures::future::BoxFuture;
+use shared_crypto::intent::Intent;
+use sui_config::{sui_config_dir, SUI_KEYSTORE_FILENAME};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
+use sui_sdk::rpc_types::{
+    ObjectChange, SuiCoin, SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+};
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::crypto::Signature;
+use sui_sdk::types::digests::TransactionDigest;
+use sui_sdk::types::transaction::TransactionData;
+use crate::utils::error::{execution_error, DeepBookAbort};
+use crate::utils::types::{json_number, OrderEvent, OrderInfo};
+
+/// Signs transactions on behalf of a Sui address.
+///
+/// Implement this trait to plug a custom key source into `DeepBookClient::execute_and_wait`.
+/// Signing is asynchronous so hardware wallets and remote (KMS) signers can be used;
+/// `Box<dyn DeepBookSigner>` implements the trait as well.
+pub trait DeepBookSigner: Send + Sync {
+    /// The address transactions are signed for (used as the sender).
+    fn address(&self) -> SuiAddress;
+
+    /// Signs the given transaction data.
+    fn sign_transaction<'a>(&'a self, tx_data: &'a TransactionData) -> BoxFuture<'a, Result<Signature>>;
+}
+
+impl<S: DeepBookSigner + ?Sized> DeepBookSigner for Box<S> {
+    fn address(&self) -> SuiAddress {
+        (**self).address()
+    }
+
+    fn sign_transaction<'a>(&'a self, tx_data: &'a TransactionData) -> BoxFuture<'a, Result<Signature>> {
+        (**self).sign_transaction(tx_data)
+    }
+}
+
+/// `DeepBookSigner` backed by a local Sui file keystore.
+pub struct KeystoreSigner {
+    keystore: FileBasedKeystore,
+    address: SuiAddress,
+}
+
+impl KeystoreSigner {
+    pub fn new(keystore: FileBasedKeystore, address: SuiAddress) -> Self {
+        Self { keystore, address }
+    }
+
+    /// Creates a signer from the default keystore (located at ~/.sui/sui_config/sui.keystore).
+    pub fn from_default_keystore(address: SuiAddress) -> Result<Self> {
+        let keystore = FileBasedKeystore::new(&sui_config_dir()?.join(SUI_KEYSTORE_FILENAME))
+            .with_context(|| "Failed to open the default Sui keystore")?;
+
+        Ok(Self::new(keystore, address))
+    }
+}
+
+impl DeepBookSigner for KeystoreSigner {
+    fn address(&self) -> SuiAddress {
+        self.address
+    }
+
+    fn sign_transaction<'a>(&'a self, tx_data: &'a TransactionData) -> BoxFuture<'a, Result<Signature>> {
+        let signature = self.keystore
+            .sign_secure(&self.address, tx_data, Intent::sui_transaction())
+            .map_err(|e| anyhow!("Failed to sign transaction for {}: {}", self.address, e));
+
+        Box::pin(async move { signature })
+    }
+}
+
+/// An object created by an executed transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreatedObject {
+    pub object_id: ObjectID,
+    pub object_type: String,
+}
+
+/// Structured result of an executed transaction.
+#[derive(Clone, Debug)]
+pub struct ExecutionOutcome {
+    pub digest: TransactionDigest,
+    pub status: SuiExecutionStatus,
+    pub created_objects: Vec<CreatedObject>,
+    pub order_ids: Vec<u128>,
+    /// The `OrderInfo` of every order processed by the transaction, with its fills.
+    pub order_infos: Vec<OrderInfo>,
+}
+
+impl ExecutionOutcome {
+    /// Builds an `ExecutionOutcome` from a transaction response fetched with full content.
+    pub fn from_response(response: &SuiTransactionBlockResponse) -> Result<Self> {
+        let status = response
+            .effects
+            .as_ref()
+            .ok_or_else(|| anyhow!("Transaction response missing effects"))?
+            .status()
+            .clone();
+
+        Ok(Self {
+            digest: response.digest,
+            status,
+            created_objects: parse_created_objects(response),
+            order_ids: parse_order_ids(response),
+            order_infos: parse_order_infos(response),
+        })
+    }
+
+    /// Returns the `OrderInfo` of the order placed with `client_order_id`, if any.
+    /// For an `ImmediateOrCancel` order, `executed_quantity` is how much filled and
+    /// `order_status()` is `Canceled` if a remainder was canceled.
+    pub fn order_info(&self, client_order_id: u64) -> Option<&OrderInfo> {
+        self.order_infos
+            .iter()
+            .find(|order_info| order_info.client_order_id == client_order_id)
+    }
+
+    /// Returns true if the transaction executed successfully.
+    pub fn is_success(&self) -> bool {
+        self.status.is_ok()
+    }
+
+    /// Returns the DeepBook abort the transaction failed with, if it failed on a Move abort.
+    pub fn abort(&self) -> Option<DeepBookAbort> {
+        match &self.status {
+            SuiExecutionStatus::Success => None,
+            SuiExecutionStatus::Failure { error } => DeepBookAbort::from_execution_error(error),
+        }
+    }
+
+    /// Returns the outcome if the transaction executed successfully, otherwise its failure as an
+    /// error, matchable with `err.downcast_ref::<DeepBookAbort>()` for a DeepBook abort.
+    pub fn ensure_success(self) -> Result<Self> {
+        match &self.status {
+            SuiExecutionStatus::Success => Ok(self),
+            SuiExecutionStatus::Failure { error } => {
+                Err(execution_error(&format!("Transaction {} failed: {}", self.digest, error)))
+            }
+        }
+    }
+}
+
+/// Collects the objects created by a transaction from its object changes.
+pub fn parse_created_objects(response: &SuiTransactionBlockResponse) -> Vec<CreatedObject> {
+    response
+        .object_changes
+        .iter()
+        .flatten()
+        .filter_map(|change| match change {
+            ObjectChange::Created { object_id, object_type, .. } => Some(CreatedObject {
+                object_id: *object_id,
+                object_type: object_type.to_string(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the id of a `balance_manager::BalanceManager` created by a transaction.
+/// Only the module and struct name are matched, since types keep the original package id
+/// across package upgrades.
+pub fn find_created_balance_manager_id(response: &SuiTransactionBlockResponse) -> Option<ObjectID> {
+    response
+        .object_changes
+        .iter()
+        .flatten()
+        .find_map(|change| match change {
+            ObjectChange::Created { object_id, object_type, .. }
+                if object_type.module.as_str() == "balance_manager"
+                    && object_type.name.as_str() == "BalanceManager" =>
+            {
+                Some(*object_id)
+            }
+            _ => None,
+        })
+}
+
+/// Collects the order ids of orders placed by a transaction from its emitted events.
+pub fn parse_order_ids(response: &SuiTransactionBlockResponse) -> Vec<u128> {
+    let mut order_ids = Vec::new();
+
+    for event in response.events.iter().flat_map(|events| events.data.iter()) {
+        let event_name = event.type_.name.as_str();
+        if event_name != "OrderPlaced" && event_name != "OrderInfo" {
+            continue;
+        }
+        if let Some(order_id) = event.parsed_json.get("order_id").and_then(json_number::<u128>) {
+            if !order_ids.contains(&order_id) {
+                order_ids.push(order_id);
+            }
+        }
+    }
+
+    order_ids
+}
+
+/// Decodes the `OrderInfo` events emitted by a transaction, in emission order.
+/// Events that fail to decode (e.g. from an incompatible package version) are skipped.
+pub fn parse_order_infos(response: &SuiTransactionBlockResponse) -> Vec<OrderInfo> {
+    response
+        .events
+        .iter()
+        .flat_map(|events| events.data.iter())
+        .filter(|event| event.type_.name.as_str() == "OrderInfo")
+        .filter_map(|event| bcs::from_bytes::<OrderInfo>(event.bcs.bytes()).ok())
+        .collect()
+}
+
+/// Decodes the order lifecycle events emitted by the DeepBook package in a transaction, in
+/// emission order. Events of other packages, and events that fail to decode (e.g. from an
+/// incompatible package version), are skipped.
+///
+/// # Arguments
+/// * `response` - The transaction response, fetched with events.
+/// * `package_id` - The DeepBook package id.
+pub fn parse_order_events(response: &SuiTransactionBlockResponse, package_id: ObjectID) -> Vec<OrderEvent> {
+    response
+        .events
+        .iter()
+        .flat_map(|events| events.data.iter())
+        .filter(|event| event.package_id == package_id || ObjectID::from(event.type_.address) == package_id)
+        .filter_map(|event| OrderEvent::from_event_json(event.type_.name.as_str(), &event.parsed_json).ok().flatten())
+        .collect()
+}
+
+/// Select a single gas coin able to cover `budget`, preferring the smallest sufficient one
+/// so larger coins stay free to be used as transaction inputs.
+///
+/// # Arguments
+/// * `coins` - The SUI coins owned by the sender.
+/// * `budget` - The gas budget in MIST.
+///
+/// # Returns
+/// The selected coin, or None if no single coin covers the budget.
+pub fn select_gas_coin(coins: &[SuiCoin], budget: u64) -> Option<&SuiCoin> {
+    coins
+        .iter()
+        .filter(|coin| coin.balance >= budget)
+        .min_by_key(|coin| coin.balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::SequenceNumber;
+    use sui_sdk::types::digests::ObjectDigest;
+    use sui_sdk::types::object::Owner;
+    use sui_sdk::types::parse_sui_struct_tag;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use shared_crypto::intent::IntentMessage;
+    use sui_sdk::types::crypto::{get_key_pair, Ed25519KeyPair, KeypairTraits};
+    use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_sdk::rpc_types::{BcsEvent, SuiEvent, SuiTransactionBlockEvents};
+    use sui_sdk::types::event::EventID;
+    use sui_sdk::types::Identifier;
+
+    #[test]
+    fn test_parse_created_objects() {
+        let manager_type = "0xcbf4748a965d469ea3a36cf0ccc5743b96c2d0ae6dee0762ed3eca65fac07f7e::balance_manager::BalanceManager";
+        let manager_id = ObjectID::from_hex_literal("0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9").unwrap();
+
+        let mut response = SuiTransactionBlockResponse::new(TransactionDigest::random());
+        response.object_changes = Some(vec![
+            ObjectChange::Mutated {
+                sender: SuiAddress::ZERO,
+                owner: Owner::AddressOwner(SuiAddress::ZERO),
+                object_type: parse_sui_struct_tag("0x2::coin::Coin<0x2::sui::SUI>").unwrap(),
+                object_id: ObjectID::random(),
+                version: SequenceNumber::from_u64(2),
+                previous_version: SequenceNumber::from_u64(1),
+                digest: ObjectDigest::random(),
+            },
+            ObjectChange::Created {
+                sender: SuiAddress::ZERO,
+                owner: Owner::Shared { initial_shared_version: SequenceNumber::from_u64(2) },
+                object_type: parse_sui_struct_tag(manager_type).unwrap(),
+                object_id: manager_id,
+                version: SequenceNumber::from_u64(2),
+                digest: ObjectDigest::random(),
+            },
+        ]);
+
+        let created = parse_created_objects(&response);
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].object_id, manager_id);
+        assert!(created[0].object_type.ends_with("::balance_manager::BalanceManager"));
+
+        assert_eq!(find_created_balance_manager_id(&response), Some(manager_id));
+    }
+
+    #[test]
+    fn test_find_created_balance_manager_id_ignores_other_objects() {
+        let mut response = SuiTransactionBlockResponse::new(TransactionDigest::random());
+        response.object_changes = Some(vec![ObjectChange::Created {
+            sender: SuiAddress::ZERO,
+            owner: Owner::AddressOwner(SuiAddress::ZERO),
+            object_type: parse_sui_struct_tag(
+                "0xcbf4748a965d469ea3a36cf0ccc5743b96c2d0ae6dee0762ed3eca65fac07f7e::balance_manager::TradeCap",
+            ).unwrap(),
+            object_id: ObjectID::random(),
+            version: SequenceNumber::from_u64(2),
+            digest: ObjectDigest::random(),
+        }]);
+
+        assert_eq!(find_created_balance_manager_id(&response), None);
+    }
+
+    #[test]
+    fn test_parse_order_events_decodes_fill() {
+        let package_id = ObjectID::from_hex_literal("0x2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809").unwrap();
+        let pool_id = ObjectID::from_hex_literal("0x1c19362ca52b8ffd7a33cee805a67d40f31e6ba303753fd3a4cfdfacea7163a5").unwrap();
+        let maker_id = ObjectID::from_hex_literal("0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9").unwrap();
+        let event = |package_id: ObjectID, name: &str, parsed_json: serde_json::Value| SuiEvent {
+            id: EventID { tx_digest: TransactionDigest::random(), event_seq: 0 },
+            package_id,
+            transaction_module: Identifier::new("pool").unwrap(),
+            sender: SuiAddress::ZERO,
+            type_: parse_sui_struct_tag(&format!("{}::order_info::{}", package_id, name)).unwrap(),
+            parsed_json,
+            bcs: BcsEvent::new(vec![]),
+            timestamp_ms: None,
+        };
+        let fill = serde_json::json!({
+            "pool_id": pool_id.to_string(),
+            "maker_order_id": "170141183460469231731687303715884105728",
+            "taker_order_id": "18446744073709551617",
+            "maker_client_order_id": "1",
+            "taker_client_order_id": "2",
+            "price": "2500000",
+            "taker_is_bid": true,
+            "taker_fee": "1000",
+            "taker_fee_is_deep": true,
+            "maker_fee": "500",
+            "maker_fee_is_deep": true,
+            "base_quantity": "1000000000",
+            "quote_quantity": "2500000",
+            "maker_balance_manager_id": maker_id.to_string(),
+            "taker_balance_manager_id": ObjectID::ZERO.to_string(),
+            "timestamp": "1700000000000",
+        });
+
+        let mut response = SuiTransactionBlockResponse::new(TransactionDigest::random());
+        response.events = Some(SuiTransactionBlockEvents {
+            data: vec![
+                event(package_id, "OrderFilled", fill.clone()),
+                // Same name, another package
+                event(ObjectID::from_hex_literal("0x3").unwrap(), "OrderFilled", fill),
+                // A DeepBook event that isn't an order lifecycle event
+                event(package_id, "BalanceEvent", serde_json::json!({})),
+            ],
+        });
+
+        assert_eq!(
+            parse_order_events(&response, package_id),
+            vec![OrderEvent::Filled {
+                pool_id,
+                maker_order_id: 170_141_183_460_469_231_731_687_303_715_884_105_728,
+                taker_order_id: 18_446_744_073_709_551_617,
+                maker_balance_manager_id: maker_id,
+                taker_balance_manager_id: ObjectID::ZERO,
+                price: 2_500_000,
+                base_quantity: 1_000_000_000,
+                quote_quantity: 2_500_000,
+                taker_is_bid: true,
+                timestamp: 1_700_000_000_000,
+            }],
+        );
+    }
+
+    #[test]
+    fn test_failed_outcome_surfaces_deepbook_abort() {
+        let outcome = |status| ExecutionOutcome {
+            digest: TransactionDigest::random(),
+            status,
+            created_objects: vec![],
+            order_ids: vec![],
+            order_infos: vec![],
+        };
+        let error = "MoveAbort(MoveLocation { module: ModuleId { address: 2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809, name: Identifier(\"order_info\") }, function: 21, instruction: 40, function_name: Some(\"validate_inputs\") }, 1) in command 0";
+
+        assert_eq!(outcome(SuiExecutionStatus::Success).abort(), None);
+        assert_eq!(
+            outcome(SuiExecutionStatus::Failure { error: error.to_string() }).abort(),
+            Some(DeepBookAbort::OrderBelowMinimumSize),
+        );
+
+        assert!(outcome(SuiExecutionStatus::Success).ensure_success().is_ok());
+        let err = outcome(SuiExecutionStatus::Failure { error: error.to_string() }).ensure_success().unwrap_err();
+        assert_eq!(err.downcast_ref::<DeepBookAbort>(), Some(&DeepBookAbort::OrderBelowMinimumSize));
+        assert!(err.to_string().contains("(command 0): Transaction"));
+        let out_of_gas = outcome(SuiExecutionStatus::Failure { error: "InsufficientGas".to_string() });
+        assert!(out_of_gas.ensure_success().unwrap_err().to_string().ends_with("failed: InsufficientGas"));
+    }
+
+    fn sui_coin(balance: u64) -> SuiCoin {
+        SuiCoin {
+            coin_type: "0x2::sui::SUI".to_string(),
+            coin_object_id: ObjectID::random(),
+            version: SequenceNumber::from_u64(1),
+            digest: ObjectDigest::random(),
+            balance,
+            previous_transaction: TransactionDigest::random(),
+        }
+    }
+
+    #[test]
+    fn test_select_gas_coin_picks_single_sufficient_coin() {
+        let coins = vec![sui_coin(100), sui_coin(5_000), sui_coin(1_000), sui_coin(300)];
+
+        let selected = select_gas_coin(&coins, 500).unwrap();
+        assert_eq!(selected.balance, 1_000);
+        assert!(select_gas_coin(&coins, 10_000).is_none());
+    }
+
+    /// Signer standing in for a network-backed signer: it yields before signing.
+    struct RemoteMockSigner {
+        key_pair: Ed25519KeyPair,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl DeepBookSigner for RemoteMockSigner {
+        fn address(&self) -> SuiAddress {
+            SuiAddress::from(self.key_pair.public())
+        }
+
+        fn sign_transaction<'a>(&'a self, tx_data: &'a TransactionData) -> BoxFuture<'a, Result<Signature>> {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                self.requests.fetch_add(1, Ordering::SeqCst);
+
+                Ok(Signature::new_secure(
+                    &IntentMessage::new(Intent::sui_transaction(), tx_data),
+                    &self.key_pair,
+                ))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_signer_behind_box() {
+        let (_, key_pair): (_, Ed25519KeyPair) = get_key_pair();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let signer: Box<dyn DeepBookSigner> = Box::new(RemoteMockSigner { key_pair, requests: requests.clone() });
+        let gas = (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random());
+        let tx_data = TransactionData::new_programmable(
+            signer.address(),
+            vec![gas],
+            ProgrammableTransactionBuilder::new().finish(),
+            1_000_000,
+            1_000,
+        );
+
+        let as_dyn: &dyn DeepBookSigner = &signer;
+        let first = as_dyn.sign_transaction(&tx_data).await.unwrap();
+        let second = signer.sign_transaction(&tx_data).await.unwrap();
+
+        // Ed25519 signatures are deterministic
+        assert_eq!(first, second);
+        // Both calls reached the signer through the box
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,295 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+//! Test fixtures for the Move mirror structs.
+//! Holds hex-encoded return values laid out like the Move structs to decode the mirrors from,
+//! and realistic values to feed code under test. Also stands in for the full node where a test
+//! needs one.
+
+use std::sync::Arc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+use sui_sdk::rpc_types::{SuiObjectData, SuiObjectResponse};
+use sui_sdk::types::base_types::{ObjectID, SequenceNumber};
+use sui_sdk::types::digests::ObjectDigest;
+use sui_sdk::types::object::Owner;
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use sui_sdk::types::collection_types::VecSet;
+use crate::utils::types::{Account, Balances, Order, OrderDeepPrice};
+
+/// A live bid for 1 base unit (at 6 decimals) paying fees in DEEP, owned by the zero manager.
+pub(crate) fn order(order_id: u128, client_order_id: u64) -> Order {
+    Order {
+        balance_manager_id: ObjectID::ZERO,
+        order_id,
+        client_order_id,
+        quantity: 1_000_000,
+        filled_quantity: 0,
+        fee_is_deep: true,
+        order_deep_price: OrderDeepPrice::default(),
+        epoch: 1,
+        status: 0,
+        expire_timestamp: u64::MAX,
+    }
+}
+
+/// An account with the given open orders and no volume, stake or balances.
+pub(crate) fn account(open_orders: &[u128]) -> Account {
+    Account {
+        epoch: 512,
+        open_orders: vec_set(open_orders),
+        taker_volume: 0,
+        maker_volume: 0,
+        active_stake: 0,
+        inactive_stake: 0,
+        created_proposal: false,
+        voted_proposal: None,
+        unclaimed_rebates: Balances::default(),
+        settled_balances: Balances::default(),
+        owed_balances: Balances::default(),
+    }
+}
+
+pub(crate) fn vec_set(contents: &[u128]) -> VecSet<u128> {
+    VecSet { contents: contents.to_vec() }
+}
+
+/// Decodes a hex fixture into its bytes.
+pub(crate) fn hex_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("fixture is hex"))
+        .collect()
+}
+
+// The hex fixtures below are written field by field from the Move struct definitions of
+// DeepBook v3, independently of the SDK's mirror structs, so a mirror that drifts from the Move
+// layout fails to decode them. Integers are little-endian, as in BCS.
+
+/// `order_info::OrderInfo` of a no-restriction bid for 2 SUI at 2.5 DBUSDC that filled 1 SUI
+/// against one ask and rested the rest, as `pool::place_limit_order` returns it.
+pub(crate) const ORDER_INFO_HEX: &str = concat!(
+    "520c89c6c78c566eed0ebf24f854a8c22d8fdd06a6f16ad01f108dad7f1baaea", // pool_id
+    "1200000000000000a025260000000000", // order_id: 46116860184273879040000018
+    "54de6cd6ae3c5e9a2b2b9f3fa6e7d78f7c6f0a2fe4a4eb0e8d0b7f3f5f6c1d2e", // balance_manager_id
+    "2a00000000000000", // client_order_id: 42
+    "7e57c0ffee7e57c0ffee7e57c0ffee7e57c0ffee7e57c0ffee7e57c0ffee7e57", // trader
+    "00", // order_type: 0
+    "01", // self_matching_option: 1
+    "a025260000000000", // price: 2500000
+    "01", // is_bid: true
+    "0094357700000000", // original_quantity: 2000000000
+    "00", // order_deep_price.asset_is_base: false
+    "50d6dc0100000000", // order_deep_price.deep_per_asset: 31250000
+    "ffffffffffffffff", // expire_timestamp: 18446744073709551615
+    "00ca9a3b00000000", // executed_quantity: 1000000000
+    "a025260000000000", // cumulative_quote_quantity: 2500000
+    "01", // fills: 1 entry
+    "1100000000000000a025260000000080", // fills[0].maker_order_id: 170141183460515348591871577594924105745
+    "0700000000000000", // fills[0].maker_client_order_id: 7
+    "a025260000000000", // fills[0].execution_price: 2500000
+    "9c1e0f2b7b4e87195a3f3dfb5e64d0a8c5e6a1f18e4b7c2d3a9f0e1b2c3d4e5f", // fills[0].balance_manager_id
+    "00", // fills[0].expired: false
+    "01", // fills[0].completed: true
+    "00ca9a3b00000000", // fills[0].original_maker_quantity: 1000000000
+    "00ca9a3b00000000", // fills[0].base_quantity: 1000000000
+    "a025260000000000", // fills[0].quote_quantity: 2500000
+    "01", // fills[0].taker_is_bid: true
+    "ff01000000000000", // fills[0].maker_epoch: 511
+    "00", // fills[0].maker_deep_price.asset_is_base: false
+    "50d6dc0100000000", // fills[0].maker_deep_price.deep_per_asset: 31250000
+    "c409000000000000", // fills[0].taker_fee: 2500
+    "01", // fills[0].taker_fee_is_deep: true
+    "e204000000000000", // fills[0].maker_fee: 1250
+    "01", // fills[0].maker_fee_is_deep: true
+    "01", // fee_is_deep: true
+    "c409000000000000", // paid_fees: 2500
+    "0002000000000000", // epoch: 512
+    "01", // status: 1
+    "00", // market_order: false
+    "00", // fill_limit_reached: false
+    "01", // order_inserted: true
+    "0068e5cf8b010000", // timestamp: 1700000000000
+);
+
+/// `order_info::OrderInfo` of an immediate-or-cancel bid for 2 SUI at 2.5 DBUSDC that found
+/// 0.6 SUI on the book: the remainder is canceled and nothing is inserted.
+pub(crate) const IOC_ORDER_INFO_HEX: &str = concat!(
+    "520c89c6c78c566eed0ebf24f854a8c22d8fdd06a6f16ad01f108dad7f1baaea", // pool_id
+    "1400000000000000a025260000000000", // order_id: 46116860184273879040000020
+    "54de6cd6ae3c5e9a2b2b9f3fa6e7d78f7c6f0a2fe4a4eb0e8d0b7f3f5f6c1d2e", // balance_manager_id
+    "2b00000000000000", // client_order_id: 43
+    "7e57c0ffee7e57c0ffee7e57c0ffee7e57c0ffee7e57c0ffee7e57c0ffee7e57", // trader
+    "01", // order_type: 1
+    "00", // self_matching_option: 0
+    "a025260000000000", // price: 2500000
+    "01", // is_bid: true
+    "0094357700000000", // original_quantity: 2000000000
+    "00", // order_deep_price.asset_is_base: false
+    "50d6dc0100000000", // order_deep_price.deep_per_asset: 31250000
+    "ffffffffffffffff", // expire_timestamp: 18446744073709551615
+    "0046c32300000000", // executed_quantity: 600000000
+    "60e3160000000000", // cumulative_quote_quantity: 1500000
+    "01", // fills: 1 entry
+    "1300000000000000a025260000000080", // fills[0].maker_order_id: 170141183460515348591871577594924105747
+    "0300000000000000", // fills[0].maker_client_order_id: 3
+    "a025260000000000", // fills[0].execution_price: 2500000
+    "9c1e0f2b7b4e87195a3f3dfb5e64d0a8c5e6a1f18e4b7c2d3a9f0e1b2c3d4e5f", // fills[0].balance_manager_id
+    "00", // fills[0].expired: false
+    "01", // fills[0].completed: true
+    "0046c32300000000", // fills[0].original_maker_quantity: 600000000
+    "0046c32300000000", // fills[0].base_quantity: 600000000
+    "60e3160000000000", // fills[0].quote_quantity: 1500000
+    "01", // fills[0].taker_is_bid: true
+    "0002000000000000", // fills[0].maker_epoch: 512
+    "00", // fills[0].maker_deep_price.asset_is_base: false
+    "50d6dc0100000000", // fills[0].maker_deep_price.deep_per_asset: 31250000
+    "dc05000000000000", // fills[0].taker_fee: 1500
+    "01", // fills[0].taker_fee_is_deep: true
+    "0000000000000000", // fills[0].maker_fee: 0
+    "01", // fills[0].maker_fee_is_deep: true
+    "01", // fee_is_deep: true
+    "dc05000000000000", // paid_fees: 1500
+    "0002000000000000", // epoch: 512
+    "03", // status: 3
+    "00", // market_order: false
+    "00", // fill_limit_reached: false
+    "00", // order_inserted: false
+    "f469e5cf8b010000", // timestamp: 1700000000500
+);
+
+/// `account::Account` of a manager with two open orders, 150 DEEP staked and settled amounts
+/// left to withdraw, as `pool::account` returns it.
+pub(crate) const ACCOUNT_HEX: &str = concat!(
+    "0002000000000000", // epoch: 512
+    "02", // open_orders: 2 entries
+    "0500000000000000009f240000000000", // open_orders[0]: 44272185776902923878400005
+    "060000000000000040ac270000000080", // open_orders[1]: 170141183460517193266278948550085705734
+    "00f90295000000000000000000000000", // taker_volume: 2500000000
+    "00c0bcf7e90a00000000000000000000", // maker_volume: 12000000000000
+    "80d1f00800000000", // active_stake: 150000000
+    "40787d0100000000", // inactive_stake: 25000000
+    "00", // created_proposal: false
+    "011b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90a", // voted_proposal: some
+    "0000000000000000", // unclaimed_rebates.base: 0
+    "0000000000000000", // unclaimed_rebates.quote: 0
+    "d012130000000000", // unclaimed_rebates.deep: 1250000
+    "00f9029500000000", // settled_balances.base: 2500000000
+    "50a06e0000000000", // settled_balances.quote: 7250000
+    "48e8010000000000", // settled_balances.deep: 125000
+    "0000000000000000", // owed_balances.base: 0
+    "40420f0000000000", // owed_balances.quote: 1000000
+    "0000000000000000", // owed_balances.deep: 0
+);
+
+/// `order::Order` of the bid of `ORDER_INFO_HEX` resting half filled on the book, as
+/// `pool::get_order` returns it.
+pub(crate) const ORDER_HEX: &str = concat!(
+    "54de6cd6ae3c5e9a2b2b9f3fa6e7d78f7c6f0a2fe4a4eb0e8d0b7f3f5f6c1d2e", // balance_manager_id
+    "1200000000000000a025260000000000", // order_id: 46116860184273879040000018
+    "2a00000000000000", // client_order_id: 42
+    "0094357700000000", // quantity: 2000000000
+    "00ca9a3b00000000", // filled_quantity: 1000000000
+    "01", // fee_is_deep: true
+    "00", // order_deep_price.asset_is_base: false
+    "50d6dc0100000000", // order_deep_price.deep_per_asset: 31250000
+    "0002000000000000", // epoch: 512
+    "01", // status: 1
+    "ffffffffffffffff", // expire_timestamp: 18446744073709551615
+);
+
+/// BCS bytes of `value`, as found in a dev-inspect return value.
+pub(crate) fn to_bcs<T: Serialize>(value: &T) -> Vec<u8> {
+    bcs::to_bytes(value).expect("fixture serializes")
+}
+
+pub(crate) fn vec_set_bytes(contents: &[u128]) -> Vec<u8> {
+    to_bcs(&vec_set(contents))
+}
+
+/// Decodes `bytes` as `T`, panicking with the type name if they don't decode.
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8]) -> T {
+    bcs::from_bytes(bytes)
+        .unwrap_or_else(|err| panic!("failed to decode {}: {}", std::any::type_name::<T>(), err))
+}
+
+/// The response of a full node for a shared object at version 10, shared at version 1.
+pub(crate) fn shared_object_response(object_id: ObjectID) -> SuiObjectResponse {
+    SuiObjectResponse::new_with_data(SuiObjectData {
+        object_id,
+        version: SequenceNumber::from_u64(10),
+        digest: ObjectDigest::random(),
+        type_: None,
+        owner: Some(Owner::Shared { initial_shared_version: SequenceNumber::from_u64(1) }),
+        previous_transaction: None,
+        storage_rebate: None,
+        display: None,
+        content: None,
+        bcs: None,
+    })
+}
+
+/// A `SuiClient` connected to a local stand-in for a full node that only answers `rpc.discover`.
+/// Lets tests reach code holding a client without the network; any other RPC fails to decode.
+pub(crate) async fn offline_sui_client() -> Arc<SuiClient> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let rpc_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve_discover(socket));
+        }
+    });
+
+    Arc::new(SuiClientBuilder::default().build(&rpc_url).await.unwrap())
+}
+
+/// Answers every request on a kept-alive connection with an empty OpenRPC document.
+async fn serve_discover(mut socket: TcpStream) {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        while let Some((body_start, end)) = request_bounds(&request) {
+            let id = serde_json::from_slice::<serde_json::Value>(&request[body_start..end])
+                .ok()
+                .and_then(|body| body.get("id").cloned())
+                .unwrap_or(serde_json::Value::Null);
+            let body = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {"openrpc": "1.2.6", "info": {"title": "", "version": "1.0.0"}, "methods": []},
+            }).to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body,
+            );
+            if socket.write_all(response.as_bytes()).await.is_err() {
+                return;
+            }
+            request.drain(..end);
+        }
+        match socket.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => request.extend_from_slice(&buffer[..read]),
+        }
+    }
+}
+
+/// Where the body of the first complete HTTP request in `request` starts and ends,
+/// or `None` while more bytes are needed.
+fn request_bounds(request: &[u8]) -> Option<(usize, usize)> {
+    let header_end = request.windows(4).position(|window| window == b"\r\n\r\n")? + 4;
+    let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+    let body_len: usize = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|len| len.trim().parse().ok())
+        .unwrap_or(0);
+
+    (request.len() >= header_end + body_len).then_some((header_end, header_end + body_len))
+}
@@ -0,0 +1,121 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+use std::fmt;
+use std::ops::Deref;
+use anyhow::{bail, Result};
+use crate::DeepBookConfig;
+
+/// Defines a key newtype that can only be built from a key present in a `DeepBookConfig`.
+/// Keys deref to `&str`, so they can be passed wherever a key string is expected.
+macro_rules! config_key {
+    ($(#[$doc:meta])* $name:ident, $kind:literal, $map:ident) => {
+        $(#[$doc])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(String);
+
+        impl $name {
+            /// Validates `key` against the configuration.
+            ///
+            /// # Arguments
+            /// * `config` - The configuration the key must be present in.
+            /// * `key` - The key string.
+            ///
+            /// # Returns
+            /// The validated key, or an error naming the missing key.
+            pub fn new(config: &DeepBookConfig, key: &str) -> Result<Self> {
+                if !config.$map.contains_key(key) {
+                    bail!(concat!($kind, " not found for key: {}"), key);
+                }
+                Ok($name(key.to_string()))
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl TryFrom<(&DeepBookConfig, &str)> for $name {
+            type Error = anyhow::Error;
+
+            fn try_from((config, key): (&DeepBookConfig, &str)) -> Result<Self> {
+                $name::new(config, key)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(key: $name) -> String {
+                key.0
+            }
+        }
+    };
+}
+
+config_key!(
+    /// The key of a pool in the configuration, e.g. `DEEP_SUI`.
+    PoolKey, "Pool", pools
+);
+config_key!(
+    /// The key of a coin in the configuration, e.g. `DEEP`.
+    CoinKey, "Coin", coins
+);
+config_key!(
+    /// The key of a balance manager in the configuration.
+    ManagerKey, "BalanceManager", balance_managers
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::{BalanceManager, BalanceManagerMap};
+
+    #[test]
+    fn test_valid_keys() {
+        let mut balance_managers = BalanceManagerMap::new();
+        balance_managers.insert("MANAGER_1".to_string(), BalanceManager {
+            address: "0x1".to_string(),
+            trade_cap: None,
+        });
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, Some(balance_managers), None, None)
+            .unwrap();
+
+        let pool_key = PoolKey::new(&config, "DEEP_SUI").unwrap();
+        assert_eq!(pool_key.as_str(), "DEEP_SUI");
+        assert!(config.get_pool(&pool_key).is_some());
+        assert_eq!(CoinKey::try_from((&config, "DEEP")).unwrap().to_string(), "DEEP");
+        assert_eq!(String::from(ManagerKey::new(&config, "MANAGER_1").unwrap()), "MANAGER_1");
+    }
+
+    #[test]
+    fn test_invalid_keys_fail_early() {
+        let config = DeepBookConfig::new("testnet", "0x1".to_string(), None, None, None, None).unwrap();
+
+        let err = PoolKey::new(&config, "DEEP_SUII").unwrap_err().to_string();
+        assert_eq!(err, "Pool not found for key: DEEP_SUII");
+        // Keys are case-sensitive, like the configuration maps
+        assert!(CoinKey::new(&config, "deep").is_err());
+        assert!(ManagerKey::try_from((&config, "MANAGER_1")).is_err());
+    }
+}
@@ -5,4 +5,11 @@
 
 pub mod config;
 pub mod constants;
+pub mod error;
+pub mod execution;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod keys;
+pub mod pricing;
 pub mod transactions;
+pub mod types;
@@ -0,0 +1,72 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+use anyhow::{anyhow, Context, Result};
+use futures::future::BoxFuture;
+
+/// Prices coins in a common unit, e.g. USD.
+///
+/// Implement this trait to plug an oracle or a price API into `DeepBookClient::pool_tvl`;
+/// the SDK ships none, so it stays oracle-agnostic. Pricing is asynchronous so feeds can be
+/// queried over the network; `Box<dyn PriceFeed>` implements the trait as well.
+pub trait PriceFeed: Send + Sync {
+    /// The price of one whole coin (in human units) of `coin_key` in the feed's unit.
+    fn price<'a>(&'a self, coin_key: &'a str) -> BoxFuture<'a, Result<f64>>;
+}
+
+impl<F: PriceFeed + ?Sized> PriceFeed for Box<F> {
+    fn price<'a>(&'a self, coin_key: &'a str) -> BoxFuture<'a, Result<f64>> {
+        (**self).price(coin_key)
+    }
+}
+
+/// Values `(coin_key, amount)` holdings, in human units, in the unit of `price_feed`.
+pub(crate) async fn holdings_value<F: PriceFeed + ?Sized>(
+    price_feed: &F,
+    holdings: &[(&str, f64)],
+) -> Result<f64> {
+    let mut value = 0.0;
+    for &(coin_key, amount) in holdings {
+        let price = price_feed
+            .price(coin_key)
+            .await
+            .with_context(|| format!("Failed to price coin: {}", coin_key))?;
+        if !price.is_finite() || price < 0.0 {
+            return Err(anyhow!("Invalid price {} for coin: {}", price, coin_key));
+        }
+        value += amount * price;
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct FixedPriceFeed(HashMap<&'static str, f64>);
+
+    impl PriceFeed for FixedPriceFeed {
+        fn price<'a>(&'a self, coin_key: &'a str) -> BoxFuture<'a, Result<f64>> {
+            Box::pin(async move {
+                self.0.get(coin_key).copied().ok_or_else(|| anyhow!("No price for {}", coin_key))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_holdings_value_of_vault_balances() {
+        let feed = FixedPriceFeed(HashMap::from([("SUI", 2.5), ("DBUSDC", 1.0)]));
+
+        // A SUI_DBUSDC vault holding 1,000 SUI and 4,000 DBUSDC
+        let value = holdings_value(&feed, &[("SUI", 1_000.0), ("DBUSDC", 4_000.0)]).await.unwrap();
+        assert_eq!(value, 6_500.0);
+
+        let boxed: Box<dyn PriceFeed> = Box::new(feed);
+        let err = holdings_value(&boxed, &[("DEEP", 1.0)]).await.unwrap_err();
+        assert!(err.to_string().contains("Failed to price coin: DEEP"));
+    }
+}
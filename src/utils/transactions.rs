@@ -1,12 +1,201 @@
-use anyhow::{Context, Result};
-use sui_sdk::rpc_types::{SuiObjectDataOptions, SuiObjectResponse};
+use anyhow::{anyhow, Context, Result};
+use std::future::Future;
+use std::time::Duration;
+use futures::future::BoxFuture;
+use sui_sdk::rpc_types::{
+    Coin as SuiCoin, DevInspectResults, EventFilter, Page, SuiEvent, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiParsedData,
+};
 use sui_sdk::SuiClient;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
+use sui_types::event::EventID;
 use sui_types::object::Owner;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::SUI_CLOCK_OBJECT_ID;
-use sui_types::transaction::{Argument, ObjectArg};
+use sui_types::sui_serde::BigInt;
+use sui_types::transaction::{Argument, Command, ObjectArg, TransactionKind};
+use sui_types::{parse_sui_struct_tag, Identifier, TypeTag};
 use crate::DeepBookConfig;
+use crate::utils::error::{execution_error, DeepBookError};
+use crate::utils::types::{json_number, json_object_id, move_field};
+
+/// Source of on-chain objects used by the prepare helpers.
+/// Implemented for `SuiClient`; tests can provide their own implementation.
+pub trait ObjectFetcher {
+    /// Fetches the given objects (with content, type and owner) in a single request.
+    /// Responses are returned in the same order as `object_ids`.
+    fn multi_get_objects<'a>(
+        &'a self,
+        object_ids: &'a [ObjectID],
+    ) -> BoxFuture<'a, Result<Vec<SuiObjectResponse>>>;
+}
+
+impl ObjectFetcher for SuiClient {
+    fn multi_get_objects<'a>(
+        &'a self,
+        object_ids: &'a [ObjectID],
+    ) -> BoxFuture<'a, Result<Vec<SuiObjectResponse>>> {
+        let fetch = async move {
+            self.read_api()
+                .multi_get_object_with_options(
+                    object_ids.to_vec(),
+                    SuiObjectDataOptions::new()
+                        .with_content()
+                        .with_type()
+                        .with_owner(),
+                )
+                .await
+                .with_context(|| format!("Failed to fetch objects for ids: {:?}", object_ids))
+        };
+
+        #[cfg(feature = "tracing")]
+        let fetch = tracing::Instrument::instrument(
+            fetch,
+            tracing::debug_span!("multi_get_objects", ?object_ids),
+        );
+
+        Box::pin(fetch)
+    }
+}
+
+/// Source of the DeepBook registry and coin metadata read by `DeepBookConfig::from_chain`.
+/// Implemented for `SuiClient`; tests can provide their own implementation.
+pub trait RegistryReader {
+    /// Fetches the `Field<PoolKey, ID>` entries of the `pools` bag of the registry as JSON.
+    fn registry_pool_fields<'a>(&'a self, registry_id: &'a str) -> BoxFuture<'a, Result<Vec<serde_json::Value>>>;
+
+    /// Fetches the decimals of a coin from its coin metadata, or None if it has no metadata.
+    fn coin_decimals<'a>(&'a self, coin_type: &'a str) -> BoxFuture<'a, Result<Option<u8>>>;
+}
+
+impl RegistryReader for SuiClient {
+    fn registry_pool_fields<'a>(&'a self, registry_id: &'a str) -> BoxFuture<'a, Result<Vec<serde_json::Value>>> {
+        Box::pin(fetch_registry_pool_fields(self, registry_id))
+    }
+
+    fn coin_decimals<'a>(&'a self, coin_type: &'a str) -> BoxFuture<'a, Result<Option<u8>>> {
+        Box::pin(async move {
+            let metadata = self
+                .coin_read_api()
+                .get_coin_metadata(coin_type.to_string())
+                .await
+                .with_context(|| format!("Failed to fetch metadata of coin: {}", coin_type))?;
+
+            Ok(metadata.map(|metadata| metadata.decimals))
+        })
+    }
+}
+
+/// Helper function to create the Pool, BalanceManager and SuiClock `Argument`s for PTB
+/// used by order operations, resolving the Pool and BalanceManager with a single RPC.
+pub async fn prepare_order_arguments<F: ObjectFetcher + ?Sized>(
+    fetcher: &F,
+    config: &DeepBookConfig,
+    ptb: &mut ProgrammableTransactionBuilder,
+    pool_key: &str,
+    manager_key: &str,
+) -> Result<(Argument, Argument, Argument)> {
+    let pool = config.require_pool(pool_key)?;
+    let manager = config.require_balance_manager(manager_key)?;
+
+    prepare_order_arguments_for(fetcher, ptb, &pool.address, &manager.address, config.clock_object_arg())
+        .await
+        .with_context(|| format!("Failed to prepare order arguments for pool: {}, manager: {}", pool_key, manager_key))
+}
+
+/// Helper function like `prepare_order_arguments`, taking the Pool, BalanceManager and clock ids directly.
+pub async fn prepare_order_arguments_for<F: ObjectFetcher + ?Sized>(
+    fetcher: &F,
+    ptb: &mut ProgrammableTransactionBuilder,
+    pool_id: &str,
+    manager_id: &str,
+    clock: ObjectArg,
+) -> Result<(Argument, Argument, Argument)> {
+    let arguments = prepare_shared_object_arguments(
+        fetcher,
+        ptb,
+        &[(pool_id, true), (manager_id, true)],
+    ).await?;
+    let sui_clock_argument = shared_clock_argument(ptb, clock)?;
+
+    Ok((arguments[0], arguments[1], sui_clock_argument))
+}
+
+/// Helper function to create the Pool and, for account reads, BalanceManager `Argument`s of a
+/// read-only query with a single RPC. Reads take both objects by reference and never need a
+/// trade proof, so no trade cap is fetched and the arguments are immutable.
+///
+/// # Returns
+/// The Pool argument, followed by the BalanceManager argument if `manager_key` is given.
+pub async fn prepare_read_arguments<F: ObjectFetcher + ?Sized>(
+    fetcher: &F,
+    config: &DeepBookConfig,
+    ptb: &mut ProgrammableTransactionBuilder,
+    pool_key: &str,
+    manager_key: Option<&str>,
+) -> Result<Vec<Argument>> {
+    let pool = config.require_pool(pool_key)?;
+    let mut objects = vec![(pool.address.as_str(), false)];
+    if let Some(manager_key) = manager_key {
+        objects.push((config.require_balance_manager(manager_key)?.address.as_str(), false));
+    }
+
+    prepare_shared_object_arguments(fetcher, ptb, &objects)
+        .await
+        .with_context(|| format!("Failed to prepare read arguments for pool: {}", pool_key))
+}
+
+/// Helper function to create several shared object `Argument`s for PTB with a single RPC.
+/// Each entry is an object id with the mutability of the argument to create.
+pub async fn prepare_shared_object_arguments<F: ObjectFetcher + ?Sized>(
+    fetcher: &F,
+    ptb: &mut ProgrammableTransactionBuilder,
+    objects: &[(&str, bool)],
+) -> Result<Vec<Argument>> {
+    let object_ids = objects
+        .iter()
+        .map(|(object_id, _)| ObjectID::from_hex_literal(object_id)
+            .with_context(|| format!("Invalid ObjectID: {}", object_id)))
+        .collect::<Result<Vec<_>>>()?;
+    let responses = fetcher.multi_get_objects(&object_ids).await?;
+
+    objects
+        .iter()
+        .zip(object_ids)
+        .zip(responses.iter())
+        .map(|(((object_id, mutable), id), response)| {
+            shared_object_argument(ptb, id, response, *mutable)
+                .with_context(|| format!("Failed to prepare shared object argument for id: {}", object_id))
+        })
+        .collect()
+}
+
+/// Helper function like `prepare_shared_object_arguments`, but objects that can't be resolved
+/// (invalid id, missing or not shared) are skipped as `None` instead of failing the batch.
+pub async fn prepare_available_shared_object_arguments<F: ObjectFetcher + ?Sized>(
+    fetcher: &F,
+    ptb: &mut ProgrammableTransactionBuilder,
+    objects: &[(&str, bool)],
+) -> Result<Vec<Option<Argument>>> {
+    let object_ids: Vec<Option<ObjectID>> = objects
+        .iter()
+        .map(|(object_id, _)| ObjectID::from_hex_literal(object_id).ok())
+        .collect();
+    let valid_ids: Vec<ObjectID> = object_ids.iter().flatten().copied().collect();
+    let responses = fetcher.multi_get_objects(&valid_ids).await?;
+    let mut responses = responses.iter();
+
+    Ok(objects
+        .iter()
+        .zip(object_ids)
+        .map(|((_, mutable), id)| {
+            let id = id?;
+            let response = responses.next()?;
+            shared_object_argument(ptb, id, response, *mutable).ok()
+        })
+        .collect())
+}
 
 /// Helper function to create a BalanceManager `Argument` for PTB using manager_key.
 /// BalanceManager is a shared object and must be fetched from SuiClient.
@@ -17,9 +206,7 @@ pub async fn prepare_balance_manager_argument(
     manager_key: &str,
 ) -> Result<Argument> {
     // Retrieve the manager information
-    let manager = config
-        .get_balance_manager(manager_key)
-        .with_context(|| format!("BalanceManager not found for key: {}", manager_key))?;
+    let manager = config.require_balance_manager(manager_key)?;
 
     prepare_shared_object_argument(
         client,
@@ -37,9 +224,7 @@ pub async fn prepare_pool_argument(
     ptb: &mut ProgrammableTransactionBuilder,
     pool_key: &str,
 ) -> Result<Argument> {
-    let pool = config
-        .get_pool(pool_key)
-        .with_context(|| format!("Pool not found for key: {}", pool_key))?;
+    let pool = config.require_pool(pool_key)?;
 
     prepare_shared_object_argument(
         client,
@@ -49,7 +234,17 @@ pub async fn prepare_pool_argument(
     ).await.with_context(|| format!("Failed to prepare pool argument for key: {}", pool_key))
 }
 
-/// Helper function to create a SuiClock `Argument` for PTB.
+/// Helper function to create a SuiClock `Argument` for PTB from the configured clock,
+/// see `DeepBookConfig::clock_object_arg`. Its initial shared version is configured, so no RPC is issued.
+pub fn shared_clock_argument(
+    ptb: &mut ProgrammableTransactionBuilder,
+    clock: ObjectArg,
+) -> Result<Argument> {
+    ptb.obj(clock).with_context(|| "Failed to prepare SuiClock argument")
+}
+
+/// Helper function to create a SuiClock `Argument` for PTB from the fetched `0x6` clock object.
+#[deprecated(note = "use `shared_clock_argument`, which builds the argument without an RPC")]
 pub async fn prepare_sui_clock_argument(
     client: &SuiClient,
     ptb: &mut ProgrammableTransactionBuilder,
@@ -70,16 +265,27 @@ pub async fn prepare_shared_object_argument(
     mutable: &bool,
 ) -> Result<Argument> {
     let object = fetch_object(client, object_id).await?;
+    let id = ObjectID::from_hex_literal(object_id)
+        .with_context(|| "Invalid ObjectID")?;
+
+    shared_object_argument(ptb, id, &object, *mutable)
+}
 
+/// Creates a shared object `Argument` for PTB from an already fetched object.
+fn shared_object_argument(
+    ptb: &mut ProgrammableTransactionBuilder,
+    id: ObjectID,
+    object: &SuiObjectResponse,
+    mutable: bool,
+) -> Result<Argument> {
     match object.owner() {
         Some(Owner::Shared { initial_shared_version, .. }) => {
             let object_argument = ptb.obj(ObjectArg::SharedObject {
-                id: ObjectID::from_hex_literal(object_id)
-                    .with_context(|| "Invalid ObjectID")?,
-                initial_shared_version: initial_shared_version.clone(),
-                mutable: mutable.clone(),
+                id,
+                initial_shared_version,
+                mutable,
             })
-                .with_context(|| format!("Failed to create PTB Argument for object id: {}", object_id))?;
+                .with_context(|| format!("Failed to create PTB Argument for object id: {}", id))?;
 
             Ok(object_argument)
         }
@@ -104,6 +310,7 @@ pub async fn prepare_imm_or_owned_object_argument(
     Ok(object_argument)
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(client)))]
 pub async fn fetch_object(
     client: &SuiClient,
     object_id: &str,
@@ -121,3 +328,931 @@ pub async fn fetch_object(
 
     Ok(sui_object_response)
 }
+
+/// Fetches every coin of `coin_type` owned by `owner`, following pagination.
+pub async fn fetch_owned_coins(
+    client: &SuiClient,
+    owner: SuiAddress,
+    coin_type: &str,
+) -> Result<Vec<SuiCoin>> {
+    let mut cursor = None;
+    let mut coins = vec![];
+
+    loop {
+        let page = client
+            .coin_read_api()
+            .get_coins(owner, Some(coin_type.to_string()), cursor, None)
+            .await
+            .with_context(|| format!("Failed to fetch coins of type {} for {}", coin_type, owner))?;
+
+        coins.extend(page.data);
+        if !page.has_next_page {
+            return Ok(coins);
+        }
+        cursor = page.next_cursor;
+    }
+}
+
+/// Fetches an object and returns the fields of its Move struct as JSON.
+pub async fn fetch_move_object_fields(
+    client: &SuiClient,
+    object_id: &str,
+) -> Result<serde_json::Value> {
+    let object = fetch_object(client, object_id).await?;
+
+    move_object_fields(&object)
+        .with_context(|| format!("Failed to read Move fields of object id: {}", object_id))
+}
+
+/// Fetches the `PoolInner` state of a pool and returns its fields as JSON.
+/// The state lives in a dynamic field of the pool's `Versioned` wrapper, keyed by version.
+pub async fn fetch_pool_inner_fields(
+    client: &SuiClient,
+    config: &DeepBookConfig,
+    pool_key: &str,
+) -> Result<serde_json::Value> {
+    let pool = config.require_pool(pool_key)?;
+    let pool_fields = fetch_move_object_fields(client, &pool.address).await?;
+
+    fetch_versioned_inner_fields(client, &pool_fields)
+        .await
+        .with_context(|| format!("Failed to fetch inner state of pool: {}", pool_key))
+}
+
+/// Reads the inner value of the `Versioned` field named `inner` of a Move object.
+pub(crate) async fn fetch_versioned_inner_fields(
+    client: &SuiClient,
+    object_fields: &serde_json::Value,
+) -> Result<serde_json::Value> {
+    let inner = move_field(object_fields, "inner")
+        .ok_or_else(|| anyhow::anyhow!("Object has no versioned inner field"))?;
+    let versioned_id = move_field(inner, "id")
+        .and_then(json_object_id)
+        .ok_or_else(|| anyhow::anyhow!("Versioned object is missing its id"))?;
+    let version = move_field(inner, "version")
+        .and_then(json_number::<u64>)
+        .ok_or_else(|| anyhow::anyhow!("Versioned object is missing its version"))?;
+
+    let dynamic_field = client
+        .read_api()
+        .get_dynamic_field_object(
+            versioned_id,
+            DynamicFieldName {
+                type_: TypeTag::U64,
+                value: serde_json::Value::String(version.to_string()),
+            },
+        )
+        .await
+        .with_context(|| format!("Failed to fetch versioned inner object: {}", versioned_id))?;
+    let field = move_object_fields(&dynamic_field)?;
+
+    move_field(&field, "value")
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Versioned inner object has no value"))
+}
+
+/// Finds the dynamic field of an object whose value type contains `type_fragment`, and returns
+/// the fields of its value as JSON. Works for both dynamic fields and dynamic object fields.
+pub(crate) async fn fetch_dynamic_field_by_type(
+    client: &SuiClient,
+    parent_id: &str,
+    type_fragment: &str,
+) -> Result<serde_json::Value> {
+    let parent = ObjectID::from_hex_literal(parent_id)
+        .with_context(|| format!("Invalid object id: {}", parent_id))?;
+    let dynamic_fields = client
+        .read_api()
+        .get_dynamic_fields(parent, None, None)
+        .await
+        .with_context(|| format!("Failed to fetch dynamic fields of object id: {}", parent_id))?;
+    let field_info = dynamic_fields
+        .data
+        .into_iter()
+        .find(|field_info| field_info.object_type.contains(type_fragment))
+        .ok_or_else(|| anyhow::anyhow!("Object {} has no dynamic field of type {}", parent_id, type_fragment))?;
+
+    let fields = fetch_move_object_fields(client, &field_info.object_id.to_string()).await?;
+
+    // A plain dynamic field wraps its value in `0x2::dynamic_field::Field`
+    Ok(move_field(&fields, "value").cloned().unwrap_or(fields))
+}
+
+/// Fetches the dynamic fields of an object, e.g. the entries of a `Bag` or `Table`,
+/// following pagination.
+pub(crate) async fn fetch_all_dynamic_fields(client: &SuiClient, parent: ObjectID) -> Result<Vec<DynamicFieldInfo>> {
+    collect_all_pages(|cursor| async move {
+        client
+            .read_api()
+            .get_dynamic_fields(parent, cursor, None)
+            .await
+            .with_context(|| format!("Failed to fetch dynamic fields of object id: {}", parent))
+    }).await
+}
+
+/// Fetches the `Field<PoolKey, ID>` entries of the `pools` bag of the DeepBook registry and
+/// returns their fields as JSON. The entries are paged through, then fetched 50 per request.
+pub(crate) async fn fetch_registry_pool_fields(
+    client: &SuiClient,
+    registry_id: &str,
+) -> Result<Vec<serde_json::Value>> {
+    let registry_fields = fetch_move_object_fields(client, registry_id)
+        .await
+        .with_context(|| "Failed to fetch registry")?;
+    let registry_inner = fetch_versioned_inner_fields(client, &registry_fields)
+        .await
+        .with_context(|| "Failed to fetch inner state of registry")?;
+    let pools_id = move_field(&registry_inner, "pools")
+        .and_then(|pools| move_field(pools, "id"))
+        .and_then(json_object_id)
+        .ok_or_else(|| anyhow::anyhow!("Registry is missing its pools"))?;
+
+    let field_ids: Vec<ObjectID> = fetch_all_dynamic_fields(client, pools_id)
+        .await
+        .with_context(|| "Failed to fetch registered pools")?
+        .into_iter()
+        .map(|field_info| field_info.object_id)
+        .collect();
+    let mut fields = Vec::with_capacity(field_ids.len());
+    for chunk in field_ids.chunks(50) {
+        for object in client.multi_get_objects(chunk).await? {
+            fields.push(move_object_fields(&object)?);
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Returns the fields of the Move struct of a fetched object as JSON.
+fn move_object_fields(object: &SuiObjectResponse) -> Result<serde_json::Value> {
+    let content = object
+        .data
+        .as_ref()
+        .and_then(|data| data.content.clone())
+        .ok_or_else(|| anyhow::anyhow!("Object has no content"))?;
+
+    match content {
+        SuiParsedData::MoveObject(move_object) => Ok(move_object.fields.to_json_value()),
+        SuiParsedData::Package(_) => Err(anyhow::anyhow!("Object is a package, not a Move object")),
+    }
+}
+
+/// Fetches a pool object and checks that it is a `pool::Pool<Base, Quote>` of the expected coin types.
+/// Catches configurations whose pool address no longer matches the configured coins.
+pub async fn verify_pool_object(
+    client: &SuiClient,
+    pool_address: &str,
+    base_coin_type: &TypeTag,
+    quote_coin_type: &TypeTag,
+) -> Result<()> {
+    let object = fetch_object(client, pool_address).await?;
+
+    check_pool_object_type(&object, base_coin_type, quote_coin_type)
+        .with_context(|| format!("Pool verification failed for object id: {}", pool_address))
+}
+
+/// Checks that a fetched object is a `pool::Pool<Base, Quote>` of the expected coin types.
+pub(crate) fn check_pool_object_type(
+    object: &SuiObjectResponse,
+    base_coin_type: &TypeTag,
+    quote_coin_type: &TypeTag,
+) -> Result<()> {
+    let object_type = object_type_string(object)?;
+    let expected = format!("pool::Pool<{}, {}>", base_coin_type, quote_coin_type);
+
+    match pool_coin_types_from_type(&object_type) {
+        Ok((base, quote)) if &base == base_coin_type && &quote == quote_coin_type => Ok(()),
+        _ => Err(anyhow::anyhow!("Expected an object of type {}, found {}", expected, object_type)),
+    }
+}
+
+/// The type of a fetched object, e.g. `0x..::pool::Pool<0x2::sui::SUI, 0x..::usdc::USDC>`.
+pub(crate) fn object_type_string(object: &SuiObjectResponse) -> Result<String> {
+    Ok(object
+        .data
+        .as_ref()
+        .and_then(|data| data.type_.as_ref())
+        .ok_or_else(|| anyhow::anyhow!("Object not found or has no type"))?
+        .to_string())
+}
+
+/// Extracts the base and quote coin types from the type of a `pool::Pool<Base, Quote>` object.
+pub(crate) fn pool_coin_types_from_type(object_type: &str) -> Result<(TypeTag, TypeTag)> {
+    let struct_tag = parse_sui_struct_tag(object_type)
+        .with_context(|| format!("Invalid object type: {}", object_type))?;
+    match (struct_tag.module.as_str(), struct_tag.name.as_str(), struct_tag.type_params.as_slice()) {
+        ("pool", "Pool", [base, quote]) => Ok((base.clone(), quote.clone())),
+        _ => Err(anyhow::anyhow!("Expected a pool::Pool<Base, Quote> object, found {}", object_type)),
+    }
+}
+
+/// Fetches the ids of all objects of the given struct type owned by `owner`, following pagination.
+pub async fn fetch_owned_object_ids(
+    client: &SuiClient,
+    owner: SuiAddress,
+    struct_type: &str,
+) -> Result<Vec<ObjectID>> {
+    let struct_tag = parse_sui_struct_tag(struct_type)
+        .with_context(|| format!("Invalid struct type: {}", struct_type))?;
+    let query = SuiObjectResponseQuery::new(
+        Some(SuiObjectDataFilter::StructType(struct_tag)),
+        Some(SuiObjectDataOptions::new().with_type()),
+    );
+
+    let objects = collect_all_pages(|cursor| {
+        let query = query.clone();
+        async move {
+            client
+                .read_api()
+                .get_owned_objects(owner, Some(query), cursor, None)
+                .await
+                .with_context(|| format!("Failed to fetch objects of type {} owned by {}", struct_type, owner))
+        }
+    }).await?;
+
+    Ok(objects
+        .iter()
+        .filter_map(|object| object.data.as_ref().map(|data| data.object_id))
+        .collect())
+}
+
+/// Collects the items of every page returned by `fetch_page`, starting from no cursor and
+/// following `next_cursor` until the last page.
+pub(crate) async fn collect_all_pages<T, C, F, Fut>(mut fetch_page: F) -> Result<Vec<T>>
+where
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<Page<T, C>>>,
+{
+    let mut cursor = None;
+    let mut items = vec![];
+
+    loop {
+        let page = fetch_page(cursor).await?;
+
+        items.extend(page.data);
+        if !page.has_next_page || page.next_cursor.is_none() {
+            return Ok(items);
+        }
+        cursor = page.next_cursor;
+    }
+}
+
+/// Collects the items of the pages returned by `fetch_page`, following `next_cursor`, until
+/// `keep` rejects an item: that item and the rest are dropped and no further page is fetched.
+/// Meant for pages in descending order, e.g. the events after a timestamp. Fails instead of
+/// fetching more than `max_pages` pages.
+pub(crate) async fn collect_pages_while<T, C, F, Fut, K>(
+    mut fetch_page: F,
+    mut keep: K,
+    max_pages: usize,
+) -> Result<Vec<T>>
+where
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<Page<T, C>>>,
+    K: FnMut(&T) -> bool,
+{
+    let mut cursor = None;
+    let mut items = vec![];
+
+    for _ in 0..max_pages {
+        let page = fetch_page(cursor).await?;
+
+        for item in page.data {
+            if !keep(&item) {
+                return Ok(items);
+            }
+            items.push(item);
+        }
+        if !page.has_next_page || page.next_cursor.is_none() {
+            return Ok(items);
+        }
+        cursor = page.next_cursor;
+    }
+
+    Err(anyhow!("Stopped after {} pages without reaching the end of the range", max_pages))
+}
+
+/// Pages through `fetch_page` from `cursor`, keeping the values `select` maps items to, until
+/// `limit` values are kept, the pages run out or `max_pages` pages were read.
+///
+/// # Returns
+/// The values, and the cursor to resume from: past the last item read, or None once the pages ran out.
+pub(crate) async fn collect_pages_up_to<T, C, V, F, Fut, S>(
+    mut cursor: Option<C>,
+    limit: usize,
+    max_pages: usize,
+    mut fetch_page: F,
+    item_cursor: impl Fn(&T) -> C,
+    mut select: S,
+) -> Result<(Vec<V>, Option<C>)>
+where
+    C: Clone,
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<Page<T, C>>>,
+    S: FnMut(&T) -> Result<Option<V>>,
+{
+    let mut values = vec![];
+    if limit == 0 {
+        return Ok((values, cursor));
+    }
+
+    for _ in 0..max_pages {
+        let page = fetch_page(cursor.clone()).await?;
+
+        for item in &page.data {
+            if let Some(value) = select(item)? {
+                values.push(value);
+                if values.len() >= limit {
+                    return Ok((values, Some(item_cursor(item))));
+                }
+            }
+        }
+        if !page.has_next_page || page.next_cursor.is_none() {
+            return Ok((values, None));
+        }
+        cursor = page.next_cursor;
+    }
+
+    Ok((values, cursor))
+}
+
+/// Fetches the events of a Move event type emitted at or after `since_ms`, newest first,
+/// following pagination for at most `max_pages` pages.
+pub async fn fetch_events_since(
+    client: &SuiClient,
+    event_type: &str,
+    since_ms: u64,
+    max_pages: usize,
+) -> Result<Vec<SuiEvent>> {
+    fetch_events_while(
+        client,
+        event_type,
+        |event: &SuiEvent| event.timestamp_ms.is_some_and(|timestamp_ms| timestamp_ms >= since_ms),
+        max_pages,
+    ).await
+}
+
+/// Fetches the events of a Move event type, newest first, following pagination until `keep`
+/// rejects an event. Fails if that takes more than `max_pages` pages.
+pub async fn fetch_events_while<K: FnMut(&SuiEvent) -> bool>(
+    client: &SuiClient,
+    event_type: &str,
+    keep: K,
+    max_pages: usize,
+) -> Result<Vec<SuiEvent>> {
+    collect_pages_while(|cursor| fetch_events_page(client, event_type, cursor), keep, max_pages).await
+}
+
+/// Fetches one page of the events of a Move event type, newest first, after `cursor`.
+pub async fn fetch_events_page(
+    client: &SuiClient,
+    event_type: &str,
+    cursor: Option<EventID>,
+) -> Result<Page<SuiEvent, EventID>> {
+    let struct_tag = parse_sui_struct_tag(event_type)
+        .with_context(|| format!("Invalid event type: {}", event_type))?;
+
+    client
+        .event_api()
+        .query_events(EventFilter::MoveEventType(struct_tag), cursor, None, true)
+        .await
+        .with_context(|| format!("Failed to query events of type {}", event_type))
+}
+
+/// Appends a call to `<deepbook_package>::<module>::<function>` to the PTB.
+pub(crate) fn deepbook_move_call(
+    ptb: &mut ProgrammableTransactionBuilder,
+    config: &DeepBookConfig,
+    module: &str,
+    function: &str,
+    type_arguments: Vec<TypeTag>,
+    arguments: Vec<Argument>,
+) -> Result<Argument> {
+    Ok(ptb.programmable_move_call(
+        ObjectID::from_hex_literal(&config.deepbook_package_id)
+            .with_context(|| format!("Invalid package ID for deepbook_package_id: {}", config.deepbook_package_id))?,
+        Identifier::new(module).with_context(|| format!("Invalid identifier for '{}'", module))?,
+        Identifier::new(function).with_context(|| format!("Invalid identifier for '{}'", function))?,
+        type_arguments,
+        arguments,
+    ))
+}
+
+/// Appends a call to `0x2::coin::zero<T>` to the PTB, returning the empty coin.
+pub(crate) fn zero_coin(
+    ptb: &mut ProgrammableTransactionBuilder,
+    coin_type: TypeTag,
+) -> Result<Argument> {
+    Ok(ptb.programmable_move_call(
+        ObjectID::from_hex_literal("0x2").with_context(|| "Invalid package ID for sui framework: 0x2")?,
+        Identifier::new("coin").with_context(|| "Invalid identifier for 'coin'")?,
+        Identifier::new("zero").with_context(|| "Invalid identifier for 'zero'")?,
+        vec![coin_type],
+        vec![],
+    ))
+}
+
+/// Adds the given owned coins to the PTB and merges them into the first one.
+///
+/// # Returns
+/// The `Argument` of the merged coin.
+pub fn merge_coin_arguments(
+    ptb: &mut ProgrammableTransactionBuilder,
+    coin_refs: &[ObjectRef],
+) -> Result<Argument> {
+    let (first, rest) = coin_refs
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("No coins to merge"))?;
+    let primary = ptb.obj(ObjectArg::ImmOrOwnedObject(*first))
+        .with_context(|| format!("Failed to create PTB Argument for coin: {}", first.0))?;
+    let sources = rest
+        .iter()
+        .map(|coin_ref| ptb.obj(ObjectArg::ImmOrOwnedObject(*coin_ref))
+            .with_context(|| format!("Failed to create PTB Argument for coin: {}", coin_ref.0)))
+        .collect::<Result<Vec<_>>>()?;
+
+    if !sources.is_empty() {
+        ptb.command(Command::MergeCoins(primary, sources));
+    }
+
+    Ok(primary)
+}
+
+/// Merges the given owned coins and splits a coin of exactly `amount` raw units off them.
+///
+/// # Returns
+/// The `Argument` of the split coin.
+pub fn split_coin_argument(
+    ptb: &mut ProgrammableTransactionBuilder,
+    coin_refs: &[ObjectRef],
+    amount: u64,
+) -> Result<Argument> {
+    let merged = merge_coin_arguments(ptb, coin_refs)?;
+    let amount_argument = ptb.pure(amount)
+        .with_context(|| "Failed to prepare amount pure argument")?;
+
+    Ok(ptb.command(Command::SplitCoins(merged, vec![amount_argument])))
+}
+
+/// Returns the `index`-th value of a command result that returns multiple values.
+pub(crate) fn nested_result(result: Argument, index: u16) -> Result<Argument> {
+    match result {
+        Argument::Result(command) => Ok(Argument::NestedResult(command, index)),
+        _ => Err(anyhow::anyhow!("Expected a command result, got {:?}", result)),
+    }
+}
+
+/// Dev-inspects the PTB as the configured address, without executing it.
+pub(crate) async fn dev_inspect_ptb(
+    client: &SuiClient,
+    config: &DeepBookConfig,
+    ptb: ProgrammableTransactionBuilder,
+) -> Result<DevInspectResults> {
+    dev_inspect_ptb_as(client, config.sender(), ptb).await
+}
+
+/// Dev-inspects the PTB as `sender`, without executing it.
+pub(crate) async fn dev_inspect_ptb_as(
+    client: &SuiClient,
+    sender: SuiAddress,
+    ptb: ProgrammableTransactionBuilder,
+) -> Result<DevInspectResults> {
+    let tx_data = TransactionKind::ProgrammableTransaction(ptb.finish());
+    let gas_budget = BigInt::from(10_000);
+
+    client
+        .read_api()
+        .dev_inspect_transaction_block(
+            sender,
+            tx_data,
+            Some(gas_budget),
+            None,
+            None,
+        )
+        .await
+        .with_context(|| "Failed to dev inspect transaction block")
+}
+
+/// Runs a full-node request, failing with `DeepBookError::Timeout` if it takes longer than `timeout`.
+pub(crate) async fn with_timeout<T>(timeout: Duration, request: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(timeout, request)
+        .await
+        .map_err(|_| DeepBookError::Timeout(timeout))?
+}
+
+/// Parses the `value`-th return value of the `command`-th command of the dev inspect results,
+/// for PTBs batching several read calls or calls returning several values.
+pub(crate) fn parse_return_value_at<T: serde::de::DeserializeOwned>(
+    response: &DevInspectResults,
+    command: usize,
+    value: usize,
+) -> Result<T> {
+    decode_return_value(return_value_bytes(response, command, value))?
+        .ok_or_else(|| match &response.error {
+            Some(error) => execution_error(error),
+            None => anyhow::anyhow!("Transaction response missing return value {} of command {}", value, command),
+        })
+}
+
+pub(crate) fn return_value_bytes(response: &DevInspectResults, command: usize, value: usize) -> Option<&[u8]> {
+    response
+        .results
+        .as_ref()
+        .and_then(|results| results.get(command))
+        .and_then(|result| result.return_values.get(value))
+        .map(|(bytes, _)| bytes.as_slice())
+}
+
+/// Decodes a dev-inspect return value. A missing or empty value is None;
+/// bytes that are present but fail to decode are an error.
+pub(crate) fn decode_return_value<T: serde::de::DeserializeOwned>(bytes: Option<&[u8]>) -> Result<Option<T>> {
+    match bytes {
+        None | Some([]) => Ok(None),
+        Some(bytes) => bcs::from_bytes::<T>(bytes)
+            .map(Some)
+            .context("Failed to decode data from BCS bytes"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use sui_types::base_types::{ObjectType, SequenceNumber};
+    use sui_types::collection_types::VecSet;
+    use sui_types::digests::ObjectDigest;
+    use sui_types::transaction::CallArg;
+    use sui_types::SUI_CLOCK_OBJECT_SHARED_VERSION;
+    use crate::utils::constants::{BalanceManager, BalanceManagerMap};
+    use crate::utils::fixtures::shared_object_response;
+
+    /// ObjectFetcher returning shared objects and counting the RPCs it serves.
+    struct CountingFetcher {
+        calls: AtomicUsize,
+        objects: AtomicUsize,
+    }
+
+    impl ObjectFetcher for CountingFetcher {
+        fn multi_get_objects<'a>(
+            &'a self,
+            object_ids: &'a [ObjectID],
+        ) -> BoxFuture<'a, Result<Vec<SuiObjectResponse>>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.objects.fetch_add(object_ids.len(), Ordering::SeqCst);
+            let responses = object_ids.iter().map(|id| shared_object_response(*id)).collect();
+
+            Box::pin(async move { Ok(responses) })
+        }
+    }
+
+    fn test_config() -> DeepBookConfig {
+        let mut balance_managers = BalanceManagerMap::new();
+        balance_managers.insert(
+            "MANAGER_1".to_string(),
+            BalanceManager {
+                address: "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9".to_string(),
+                trade_cap: None,
+            },
+        );
+
+        DeepBookConfig::new("testnet", "0x1".to_string(), None, Some(balance_managers), None, None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prepare_order_arguments_uses_one_rpc() {
+        let fetcher = CountingFetcher { calls: AtomicUsize::new(0), objects: AtomicUsize::new(0) };
+        let config = test_config();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        let (pool, manager, clock) = prepare_order_arguments(&fetcher, &config, &mut ptb, "DEEP_SUI", "MANAGER_1")
+            .await
+            .unwrap();
+
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+        assert_eq!((pool, manager, clock), (Argument::Input(0), Argument::Input(1), Argument::Input(2)));
+
+        let inputs = ptb.finish().inputs;
+        assert_eq!(inputs.len(), 3);
+    }
+
+    /// ObjectFetcher answering only after `delay`, like a hanging full node.
+    struct SlowFetcher {
+        delay: Duration,
+    }
+
+    impl ObjectFetcher for SlowFetcher {
+        fn multi_get_objects<'a>(
+            &'a self,
+            object_ids: &'a [ObjectID],
+        ) -> BoxFuture<'a, Result<Vec<SuiObjectResponse>>> {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                Ok(object_ids.iter().map(|id| shared_object_response(*id)).collect())
+            })
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_request_trips_timeout() {
+        // The paused clock jumps straight to the earliest timer, so nothing really sleeps
+        let fetcher = SlowFetcher { delay: Duration::from_secs(5) };
+        let ids = [SUI_CLOCK_OBJECT_ID];
+
+        let err = with_timeout(Duration::from_millis(20), fetcher.multi_get_objects(&ids))
+            .await
+            .unwrap_err();
+        assert_eq!(err.downcast_ref::<DeepBookError>(), Some(&DeepBookError::Timeout(Duration::from_millis(20))));
+
+        let fast = SlowFetcher { delay: Duration::ZERO };
+        let responses = with_timeout(Duration::from_secs(5), fast.multi_get_objects(&ids)).await.unwrap();
+        assert_eq!(responses.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_order_arguments_does_not_fetch_clock() {
+        let fetcher = CountingFetcher { calls: AtomicUsize::new(0), objects: AtomicUsize::new(0) };
+        let config = test_config();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        prepare_order_arguments(&fetcher, &config, &mut ptb, "DEEP_SUI", "MANAGER_1")
+            .await
+            .unwrap();
+
+        assert_eq!(fetcher.objects.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_queries_fetch_only_their_objects() {
+        let config = test_config();
+
+        // An account read (e.g. `account_open_orders`): the pool and manager in one RPC
+        let fetcher = CountingFetcher { calls: AtomicUsize::new(0), objects: AtomicUsize::new(0) };
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let arguments = prepare_read_arguments(&fetcher, &config, &mut ptb, "DEEP_SUI", Some("MANAGER_1"))
+            .await
+            .unwrap();
+        assert_eq!(arguments, vec![Argument::Input(0), Argument::Input(1)]);
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fetcher.objects.load(Ordering::SeqCst), 2);
+        // No trade proof is generated and the objects are read-only
+        let transaction = ptb.finish();
+        assert!(transaction.commands.is_empty());
+        assert!(transaction.inputs.iter().all(|input| matches!(
+            input,
+            CallArg::Object(ObjectArg::SharedObject { mutable: false, .. }),
+        )));
+
+        // A pool read (e.g. `mid_price`): only the pool, the clock needs no RPC
+        let fetcher = CountingFetcher { calls: AtomicUsize::new(0), objects: AtomicUsize::new(0) };
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        prepare_read_arguments(&fetcher, &config, &mut ptb, "DEEP_SUI", None).await.unwrap();
+        shared_clock_argument(&mut ptb, config.clock_object_arg()).unwrap();
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fetcher.objects.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_available_shared_object_arguments_skips_unresolved() {
+        let fetcher = CountingFetcher { calls: AtomicUsize::new(0), objects: AtomicUsize::new(0) };
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let valid = "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9";
+
+        let arguments = prepare_available_shared_object_arguments(
+            &fetcher,
+            &mut ptb,
+            &[("not-an-id", false), (valid, false)],
+        ).await.unwrap();
+
+        assert_eq!(arguments, vec![None, Some(Argument::Input(0))]);
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fetcher.objects.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_custom_clock_id_flows_into_order_arguments() {
+        let fetcher = CountingFetcher { calls: AtomicUsize::new(0), objects: AtomicUsize::new(0) };
+        let mut config = test_config();
+        config.clock_object_id = ObjectID::from_hex_literal("0x60").unwrap();
+        config.clock_initial_shared_version = SequenceNumber::from_u64(7);
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        prepare_order_arguments(&fetcher, &config, &mut ptb, "DEEP_SUI", "MANAGER_1")
+            .await
+            .unwrap();
+
+        let inputs = ptb.finish().inputs;
+        assert_eq!(inputs[2], CallArg::Object(ObjectArg::SharedObject {
+            id: config.clock_object_id,
+            initial_shared_version: SequenceNumber::from_u64(7),
+            mutable: false,
+        }));
+    }
+
+    #[test]
+    fn test_split_coin_argument_exact_deep_fee() {
+        let deep = crate::utils::constants::TESTNET_COINS.get("DEEP").unwrap();
+        let coin_refs: Vec<ObjectRef> = (0..2)
+            .map(|_| (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random()))
+            .collect();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        let fee_coin = split_coin_argument(&mut ptb, &coin_refs, deep.to_units(0.5)).unwrap();
+
+        assert_eq!(fee_coin, Argument::Result(1));
+        let transaction = ptb.finish();
+        assert_eq!(transaction.inputs[2], CallArg::Pure(bcs::to_bytes(&500_000u64).unwrap()));
+        assert_eq!(transaction.commands[1], Command::SplitCoins(Argument::Input(0), vec![Argument::Input(2)]));
+    }
+
+    #[test]
+    fn test_merge_coin_arguments_single_merge() {
+        let coin_refs: Vec<ObjectRef> = (0..3)
+            .map(|_| (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random()))
+            .collect();
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        let merged = merge_coin_arguments(&mut ptb, &coin_refs).unwrap();
+
+        assert_eq!(merged, Argument::Input(0));
+        let transaction = ptb.finish();
+        assert_eq!(transaction.inputs.len(), 3);
+        assert_eq!(transaction.commands, vec![
+            Command::MergeCoins(Argument::Input(0), vec![Argument::Input(1), Argument::Input(2)]),
+        ]);
+    }
+
+    #[test]
+    fn test_shared_clock_argument_uses_known_version() {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        shared_clock_argument(&mut ptb, test_config().clock_object_arg()).unwrap();
+
+        let inputs = ptb.finish().inputs;
+        assert_eq!(inputs, vec![CallArg::Object(ObjectArg::SharedObject {
+            id: SUI_CLOCK_OBJECT_ID,
+            initial_shared_version: SUI_CLOCK_OBJECT_SHARED_VERSION,
+            mutable: false,
+        })]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_pages_follows_cursor() {
+        let first = ObjectID::random();
+        let second = ObjectID::random();
+        let mut requested_cursors = vec![];
+
+        let items = collect_all_pages(|cursor: Option<ObjectID>| {
+            requested_cursors.push(cursor);
+            let page = match cursor {
+                None => Page { data: vec![first], next_cursor: Some(first), has_next_page: true },
+                Some(_) => Page { data: vec![second], next_cursor: Some(second), has_next_page: false },
+            };
+            async move { Ok(page) }
+        }).await.unwrap();
+
+        assert_eq!(items, vec![first, second]);
+        assert_eq!(requested_cursors, vec![None, Some(first)]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_while_stops_at_first_rejected_item() {
+        let mut requested_cursors = vec![];
+
+        // Descending timestamps: the second page crosses the cutoff
+        let items = collect_pages_while(
+            |cursor: Option<u64>| {
+                requested_cursors.push(cursor);
+                let page = match cursor {
+                    None => Page { data: vec![900u64, 800], next_cursor: Some(2), has_next_page: true },
+                    Some(2) => Page { data: vec![700, 400, 300], next_cursor: Some(5), has_next_page: true },
+                    Some(_) => Page { data: vec![200], next_cursor: None, has_next_page: false },
+                };
+                async move { Ok(page) }
+            },
+            |timestamp: &u64| *timestamp >= 500,
+            10,
+        ).await.unwrap();
+
+        assert_eq!(items, vec![900, 800, 700]);
+        assert_eq!(requested_cursors, vec![None, Some(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_while_fails_past_page_cap() {
+        let mut requests = 0;
+
+        // Every page has a next one and every item is kept
+        let err = collect_pages_while(
+            |cursor: Option<u64>| {
+                requests += 1;
+                let next = cursor.unwrap_or(0) + 1;
+                async move { Ok(Page { data: vec![next], next_cursor: Some(next), has_next_page: true }) }
+            },
+            |_: &u64| true,
+            3,
+        ).await.unwrap_err();
+
+        assert_eq!(err.to_string(), "Stopped after 3 pages without reaching the end of the range");
+        assert_eq!(requests, 3);
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_up_to_resumes_from_returned_cursor() {
+        // Items are their own cursors; even items are selected
+        let pages = |cursor: Option<u64>| {
+            let page = match cursor {
+                None => Page { data: vec![9u64, 8, 7], next_cursor: Some(7), has_next_page: true },
+                Some(7) => Page { data: vec![6, 5, 4], next_cursor: Some(4), has_next_page: true },
+                Some(_) => Page { data: vec![3, 2], next_cursor: None, has_next_page: false },
+            };
+            async move { Ok::<_, anyhow::Error>(page) }
+        };
+        let even = |item: &u64| -> Result<Option<u64>> { Ok((item % 2 == 0).then_some(*item)) };
+
+        // The limit stops mid-page, at the last item kept
+        let (kept, cursor) = collect_pages_up_to(None, 2, 10, pages, |item| *item, even).await.unwrap();
+        assert_eq!((kept, cursor), (vec![8, 6], Some(6)));
+
+        // The page cap stops at a page boundary, leaving its next cursor
+        let mut fetched = 0;
+        let counted = |cursor| {
+            fetched += 1;
+            pages(cursor)
+        };
+        let (kept, cursor) = collect_pages_up_to(None, 10, 1, counted, |item| *item, even).await.unwrap();
+        assert_eq!((kept, cursor, fetched), (vec![8], Some(7), 1));
+
+        // Running out of pages leaves no cursor
+        let (kept, cursor) = collect_pages_up_to(Some(7), 10, 10, pages, |item| *item, even).await.unwrap();
+        assert_eq!((kept, cursor), (vec![6, 4, 2], None));
+    }
+
+    fn typed_object_response(object_type: &str) -> SuiObjectResponse {
+        let mut response = shared_object_response(ObjectID::random());
+        if let Some(data) = response.data.as_mut() {
+            data.type_ = Some(ObjectType::from_str(object_type).unwrap());
+        }
+        response
+    }
+
+    #[test]
+    fn test_pool_coin_types_from_type() {
+        let (base, quote) = pool_coin_types_from_type(
+            "0x2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809::pool::Pool<0x2::sui::SUI, 0xdba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7::usdc::USDC>",
+        ).unwrap();
+        assert_eq!(base, TypeTag::from_str("0x2::sui::SUI").unwrap());
+        assert_eq!(
+            quote,
+            TypeTag::from_str("0xdba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7::usdc::USDC").unwrap()
+        );
+
+        assert!(pool_coin_types_from_type("0x2::coin::Coin<0x2::sui::SUI>").is_err());
+        assert!(pool_coin_types_from_type("not a type").is_err());
+    }
+
+    #[test]
+    fn test_check_pool_object_type() {
+        let deep = TypeTag::from_str("0x36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8::deep::DEEP").unwrap();
+        let sui = TypeTag::from_str("0x2::sui::SUI").unwrap();
+        let pool = typed_object_response(
+            "0xcbf4748a965d469ea3a36cf0ccc5743b96c2d0ae6dee0762ed3eca65fac07f7e::pool::Pool<0x36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8::deep::DEEP, 0x2::sui::SUI>",
+        );
+
+        assert!(check_pool_object_type(&pool, &deep, &sui).is_ok());
+        // Swapped coin types are a different pool
+        assert!(check_pool_object_type(&pool, &sui, &deep).is_err());
+    }
+
+    #[test]
+    fn test_check_pool_object_type_wrong_type() {
+        let deep = TypeTag::from_str("0x36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8::deep::DEEP").unwrap();
+        let sui = TypeTag::from_str("0x2::sui::SUI").unwrap();
+        let manager = typed_object_response(
+            "0xcbf4748a965d469ea3a36cf0ccc5743b96c2d0ae6dee0762ed3eca65fac07f7e::balance_manager::BalanceManager",
+        );
+
+        let err = check_pool_object_type(&manager, &deep, &sui).unwrap_err();
+        assert!(err.to_string().contains("Expected an object of type pool::Pool<"));
+        assert!(err.to_string().contains("balance_manager::BalanceManager"));
+    }
+
+    #[test]
+    fn test_decode_return_value_empty_is_none() {
+        let order_ids: Option<VecSet<u128>> = decode_return_value(Some(&[][..])).unwrap();
+        assert!(order_ids.is_none());
+
+        let order_ids: Option<VecSet<u128>> = decode_return_value(None).unwrap();
+        assert_eq!(order_ids.map(|o| o.contents).unwrap_or_default(), Vec::<u128>::new());
+    }
+
+    #[test]
+    fn test_decode_return_value_present_bytes() {
+        let bytes = bcs::to_bytes(&vec![1u128, 2u128]).unwrap();
+        let order_ids: Option<VecSet<u128>> = decode_return_value(Some(bytes.as_slice())).unwrap();
+        assert_eq!(order_ids.unwrap().contents, vec![1, 2]);
+
+        // A vector length prefix of 2 with no elements following
+        let truncated: Result<Option<VecSet<u128>>> = decode_return_value(Some(&[2u8][..]));
+        assert!(truncated.is_err());
+    }
+}
@@ -0,0 +1,1710 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::collection_types::VecSet;
+use sui_sdk::types::event::EventID;
+use sui_sdk::types::TypeTag;
+use crate::utils::config::FLOAT_SCALAR;
+
+/// Rust mirror of the Move `deepbook::balances::Balances` struct.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Balances {
+    pub base: u64,
+    pub quote: u64,
+    pub deep: u64,
+}
+
+/// Rust mirror of the Move `deepbook::account::Account` struct.
+/// Holds the per-manager state of a balance manager inside a pool.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Account {
+    pub epoch: u64,
+    pub open_orders: VecSet<u128>,
+    pub taker_volume: u128,
+    pub maker_volume: u128,
+    pub active_stake: u64,
+    pub inactive_stake: u64,
+    pub created_proposal: bool,
+    pub voted_proposal: Option<ObjectID>,
+    pub unclaimed_rebates: Balances,
+    pub settled_balances: Balances,
+    pub owed_balances: Balances,
+}
+
+impl Account {
+    /// Rescales the `(active_stake, inactive_stake)` of the account, tracked in DEEP base units.
+    pub fn stakes(&self, deep_scalar: u64) -> (f64, f64) {
+        (
+            self.active_stake as f64 / deep_scalar as f64,
+            self.inactive_stake as f64 / deep_scalar as f64,
+        )
+    }
+
+    /// Rescales the lifetime `(maker_volume, taker_volume)` of the account, tracked in base units.
+    pub fn volumes(&self, base_scalar: u64) -> (f64, f64) {
+        (
+            self.maker_volume as f64 / base_scalar as f64,
+            self.taker_volume as f64 / base_scalar as f64,
+        )
+    }
+
+    /// Rescales the `(base, quote, deep)` amounts settled from fills but not yet withdrawn,
+    /// i.e. what `withdraw_settled_amounts` would move back to the balance manager.
+    pub fn settled_amounts(&self, base_scalar: u64, quote_scalar: u64, deep_scalar: u64) -> (f64, f64, f64) {
+        let settled = &self.settled_balances;
+        (
+            settled.base as f64 / base_scalar as f64,
+            settled.quote as f64 / quote_scalar as f64,
+            settled.deep as f64 / deep_scalar as f64,
+        )
+    }
+}
+
+/// A fee rate as DeepBook encodes it on-chain: a `u64` in billionths (`FLOAT_SCALAR`) of the
+/// traded amount, e.g. `1_000_000` for 10 bps. Displays as a percentage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FeeRate(u64);
+
+impl FeeRate {
+    /// Builds a rate from its on-chain `FLOAT_SCALAR` encoding.
+    pub fn from_float_scalar(value: u64) -> Self {
+        FeeRate(value)
+    }
+
+    /// Builds a rate from basis points, e.g. `10.0` for 0.1%.
+    pub fn from_bps(bps: f64) -> Self {
+        Self::from_fraction(bps / 10_000.0)
+    }
+
+    /// Builds a rate from a fraction of the traded amount, e.g. `0.001` for 0.1%.
+    pub fn from_fraction(fraction: f64) -> Self {
+        FeeRate((fraction * FLOAT_SCALAR as f64).round() as u64)
+    }
+
+    /// The on-chain `FLOAT_SCALAR` encoding of the rate.
+    pub fn as_float_scalar(&self) -> u64 {
+        self.0
+    }
+
+    /// The rate in basis points.
+    pub fn as_bps(&self) -> f64 {
+        self.as_fraction() * 10_000.0
+    }
+
+    /// The rate as a fraction of the traded amount.
+    pub fn as_fraction(&self) -> f64 {
+        self.0 as f64 / FLOAT_SCALAR as f64
+    }
+}
+
+impl fmt::Display for FeeRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 1% is FLOAT_SCALAR / 100: print the exact decimal instead of a rounded float
+        let percent_scalar = FLOAT_SCALAR / 100;
+        let (whole, fraction) = (self.0 / percent_scalar, self.0 % percent_scalar);
+        if fraction == 0 {
+            return write!(f, "{}%", whole);
+        }
+        let digits = percent_scalar.ilog10() as usize;
+        let fraction = format!("{:0width$}", fraction, width = digits);
+        write!(f, "{}.{}%", whole, fraction.trim_end_matches('0'))
+    }
+}
+
+/// Trade parameters of a pool, as returned by `pool::pool_trade_params`, in on-chain units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoolTradeParams {
+    pub taker_fee: FeeRate,
+    pub maker_fee: FeeRate,
+    pub stake_required: u64,
+}
+
+impl PoolTradeParams {
+    /// Derives the fee tier of an account from its active stake.
+    pub fn fee_tier(&self, active_stake: u64) -> FeeTier {
+        if active_stake >= self.stake_required {
+            FeeTier::Staked
+        } else {
+            FeeTier::Standard
+        }
+    }
+}
+
+/// Fee tier of a balance manager in a pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeTier {
+    /// The active stake is below `stake_required`: the pool's regular fees apply.
+    Standard,
+    /// The active stake meets `stake_required`: the account qualifies for reduced taker fees.
+    Staked,
+}
+
+/// Rust mirror of the Move `deepbook::deep_price::OrderDeepPrice` struct.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderDeepPrice {
+    pub asset_is_base: bool,
+    pub deep_per_asset: u64,
+}
+
+/// Rust mirror of the Move `deepbook::order::Order` struct.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub balance_manager_id: ObjectID,
+    pub order_id: u128,
+    pub client_order_id: u64,
+    pub quantity: u64,
+    pub filled_quantity: u64,
+    pub fee_is_deep: bool,
+    pub order_deep_price: OrderDeepPrice,
+    pub epoch: u64,
+    pub status: u8,
+    pub expire_timestamp: u64,
+}
+
+impl Order {
+    /// The unfilled quantity of the order, in on-chain base units.
+    pub fn remaining_quantity(&self) -> u64 {
+        self.quantity.saturating_sub(self.filled_quantity)
+    }
+}
+
+/// Rust mirror of the Move `deepbook::fill::Fill` struct: one match of a taker order against a maker order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub maker_order_id: u128,
+    pub maker_client_order_id: u64,
+    pub execution_price: u64,
+    pub balance_manager_id: ObjectID,
+    pub expired: bool,
+    pub completed: bool,
+    pub original_maker_quantity: u64,
+    pub base_quantity: u64,
+    pub quote_quantity: u64,
+    pub taker_is_bid: bool,
+    pub maker_epoch: u64,
+    pub maker_deep_price: OrderDeepPrice,
+    pub taker_fee: u64,
+    pub taker_fee_is_deep: bool,
+    pub maker_fee: u64,
+    pub maker_fee_is_deep: bool,
+}
+
+/// Rust mirror of the Move `deepbook::order_info::OrderInfo` struct,
+/// returned by `pool::place_limit_order` and emitted once the order is processed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrderInfo {
+    pub pool_id: ObjectID,
+    pub order_id: u128,
+    pub balance_manager_id: ObjectID,
+    pub client_order_id: u64,
+    pub trader: SuiAddress,
+    pub order_type: u8,
+    pub self_matching_option: u8,
+    pub price: u64,
+    pub is_bid: bool,
+    pub original_quantity: u64,
+    pub order_deep_price: OrderDeepPrice,
+    pub expire_timestamp: u64,
+    pub executed_quantity: u64,
+    pub cumulative_quote_quantity: u64,
+    pub fills: Vec<Fill>,
+    pub fee_is_deep: bool,
+    pub paid_fees: u64,
+    pub epoch: u64,
+    pub status: u8,
+    pub market_order: bool,
+    pub fill_limit_reached: bool,
+    pub order_inserted: bool,
+    pub timestamp: u64,
+}
+
+/// Status of an order, mirroring the `deepbook::constants` order statuses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderStatus {
+    Live,
+    PartiallyFilled,
+    Filled,
+    /// Canceled, including the unfilled remainder of an `ImmediateOrCancel` order.
+    Canceled,
+    Expired,
+}
+
+impl OrderStatus {
+    pub fn from_u8(status: u8) -> Option<OrderStatus> {
+        match status {
+            0 => Some(OrderStatus::Live),
+            1 => Some(OrderStatus::PartiallyFilled),
+            2 => Some(OrderStatus::Filled),
+            3 => Some(OrderStatus::Canceled),
+            4 => Some(OrderStatus::Expired),
+            _ => None,
+        }
+    }
+}
+
+impl OrderInfo {
+    /// The status of the order once processed, or None for an unknown status code.
+    /// A partially filled `ImmediateOrCancel` order is `Canceled` with a nonzero `executed_quantity`.
+    pub fn order_status(&self) -> Option<OrderStatus> {
+        OrderStatus::from_u8(self.status)
+    }
+
+    /// The quantity that neither filled nor rests on the book, e.g. the canceled remainder
+    /// of an `ImmediateOrCancel` order.
+    pub fn canceled_quantity(&self) -> u64 {
+        if self.order_inserted {
+            0
+        } else {
+            self.original_quantity.saturating_sub(self.executed_quantity)
+        }
+    }
+
+    /// Returns true if the whole order quantity was matched on placement.
+    pub fn is_filled(&self) -> bool {
+        self.executed_quantity >= self.original_quantity
+    }
+
+    /// Returns true if the unfilled remainder of the order rests on the book.
+    pub fn is_resting(&self) -> bool {
+        self.order_inserted
+    }
+}
+
+/// Book parameters of a pool, as returned by `pool::pool_book_params`, in on-chain units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoolBookParams {
+    pub tick_size: u64,
+    pub lot_size: u64,
+    pub min_size: u64,
+}
+
+impl PoolBookParams {
+    /// Checks an encoded price and quantity against the tick size, lot size and min size.
+    pub fn validate(&self, input_price: u64, input_quantity: u64) -> Result<()> {
+        if self.tick_size == 0 || input_price % self.tick_size != 0 {
+            return Err(anyhow!("Price {} is not a multiple of tick size {}", input_price, self.tick_size));
+        }
+        if self.lot_size == 0 || input_quantity % self.lot_size != 0 {
+            return Err(anyhow!("Quantity {} is not a multiple of lot size {}", input_quantity, self.lot_size));
+        }
+        if input_quantity < self.min_size {
+            return Err(anyhow!("Quantity {} is below min size {}", input_quantity, self.min_size));
+        }
+
+        Ok(())
+    }
+}
+
+/// A governance proposal of a pool, with fees as fractions and stake/votes in DEEP.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+    pub id: ObjectID,
+    pub taker_fee: f64,
+    pub maker_fee: f64,
+    pub stake_required: f64,
+    pub votes: f64,
+}
+
+impl Proposal {
+    /// Decodes the proposals of a pool from the JSON fields of its `Governance` struct.
+    /// Stakes and votes are scaled with `deep_scalar`, the scalar of the configured DEEP coin.
+    pub fn from_governance_json(governance: &serde_json::Value, deep_scalar: u64) -> Result<Vec<Proposal>> {
+        let entries = move_field(governance, "proposals")
+            .and_then(|proposals| move_field(proposals, "contents"))
+            .and_then(|contents| contents.as_array())
+            .ok_or_else(|| anyhow!("Governance is missing proposals"))?;
+
+        entries
+            .iter()
+            .map(|entry| {
+                let id = move_field(entry, "key")
+                    .and_then(json_object_id)
+                    .ok_or_else(|| anyhow!("Proposal entry is missing its id"))?;
+                let proposal = move_field(entry, "value")
+                    .ok_or_else(|| anyhow!("Proposal entry is missing its value"))?;
+                let field = |name: &str| {
+                    move_field(proposal, name)
+                        .and_then(json_number::<u64>)
+                        .ok_or_else(|| anyhow!("Proposal {} is missing field: {}", id, name))
+                };
+
+                Ok(Proposal {
+                    id,
+                    taker_fee: field("taker_fee")? as f64 / FLOAT_SCALAR as f64,
+                    maker_fee: field("maker_fee")? as f64 / FLOAT_SCALAR as f64,
+                    stake_required: field("stake_required")? as f64 / deep_scalar as f64,
+                    votes: field("votes")? as f64 / deep_scalar as f64,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Counts the balance managers whose latest vote in governance `epoch` is for `proposal_id`.
+///
+/// # Arguments
+/// * `vote_events` - The parsed JSON of a pool's `state::VoteEvent`s, newest first.
+/// * `proposal_id` - The proposal to count the voters of.
+/// * `epoch` - The governance epoch of the pool: votes of earlier epochs were reset.
+pub(crate) fn proposal_voter_count<'a>(
+    vote_events: impl IntoIterator<Item = &'a serde_json::Value>,
+    proposal_id: ObjectID,
+    epoch: u64,
+) -> u64 {
+    let mut seen_managers = HashSet::new();
+    let mut voter_count = 0;
+
+    for event in vote_events {
+        if event.get("epoch").and_then(json_number::<u64>) != Some(epoch) {
+            continue;
+        }
+        let Some(manager_id) = event.get("balance_manager_id").and_then(json_object_id) else {
+            continue;
+        };
+        // Newest first: the first vote seen of a manager is its current one
+        if seen_managers.insert(manager_id)
+            && event.get("to_proposal_id").and_then(json_object_id) == Some(proposal_id)
+        {
+            voter_count += 1;
+        }
+    }
+
+    voter_count
+}
+
+/// One price level of an order book, in human units.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BookLevel {
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// A level-2 snapshot of a pool's order book: bids from the best (highest) price down,
+/// asks from the best (lowest) price up.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrderBook {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+impl OrderBook {
+    /// Rescales the `(bid prices, bid quantities, ask prices, ask quantities)` vectors returned
+    /// by `pool::get_level2_ticks_from_mid` into human units.
+    pub fn from_level2(level2: [Vec<u64>; 4], base_scalar: u64, quote_scalar: u64) -> OrderBook {
+        let [bid_prices, bid_quantities, ask_prices, ask_quantities] = level2;
+        let levels = |prices: Vec<u64>, quantities: Vec<u64>| {
+            prices
+                .into_iter()
+                .zip(quantities)
+                .map(|(price, quantity)| BookLevel {
+                    price: decode_price(price, base_scalar, quote_scalar),
+                    quantity: quantity as f64 / base_scalar as f64,
+                })
+                .collect()
+        };
+
+        OrderBook { bids: levels(bid_prices, bid_quantities), asks: levels(ask_prices, ask_quantities) }
+    }
+
+    /// Keeps at most `depth` levels on each side.
+    pub fn truncate(&mut self, depth: usize) {
+        self.bids.truncate(depth);
+        self.asks.truncate(depth);
+    }
+
+    /// The best bid and best ask prices, each None if that side of the book is empty.
+    pub fn best_bid_ask(&self) -> (Option<f64>, Option<f64>) {
+        (self.bids.first().map(|level| level.price), self.asks.first().map(|level| level.price))
+    }
+
+    /// Computes the spread, depth and imbalance of the book, or None if either side is empty.
+    pub fn metrics(&self) -> Option<BookMetrics> {
+        let best_bid = self.bids.first()?.price;
+        let best_ask = self.asks.first()?.price;
+        let spread = best_ask - best_bid;
+        let bid_depth: f64 = self.bids.iter().map(|level| level.quantity).sum();
+        let ask_depth: f64 = self.asks.iter().map(|level| level.quantity).sum();
+
+        Some(BookMetrics {
+            best_bid,
+            best_ask,
+            spread,
+            spread_bps: spread / ((best_bid + best_ask) / 2.0) * 10_000.0,
+            bid_depth,
+            ask_depth,
+            imbalance: (bid_depth - ask_depth) / (bid_depth + ask_depth),
+        })
+    }
+}
+
+/// Top-of-book and depth metrics of an order book, with prices in quote per base and depths in base.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BookMetrics {
+    pub best_bid: f64,
+    pub best_ask: f64,
+    pub spread: f64,
+    /// The spread in basis points of the mid price.
+    pub spread_bps: f64,
+    pub bid_depth: f64,
+    pub ask_depth: f64,
+    /// `(bid_depth - ask_depth) / (bid_depth + ask_depth)`, from -1 (all asks) to 1 (all bids).
+    pub imbalance: f64,
+}
+
+/// A pre-trade snapshot of a pool: its book and trade parameters in on-chain units,
+/// whether it is whitelisted, and its mid price in quote per base.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoolParams {
+    pub book: PoolBookParams,
+    pub trade: PoolTradeParams,
+    pub whitelisted: bool,
+    /// None if either side of the book is empty.
+    pub mid_price: Option<f64>,
+}
+
+impl PoolParams {
+    /// Decodes the return values of a batch of `pool_book_params`, `pool_trade_params`,
+    /// `whitelisted` and a one-tick `get_level2_ticks_from_mid`, in that command order.
+    ///
+    /// # Arguments
+    /// * `return_value` - The BCS bytes of a return value, by command and value index.
+    /// * `base_scalar` - The scalar of the pool's base coin.
+    /// * `quote_scalar` - The scalar of the pool's quote coin.
+    pub(crate) fn from_return_values<'a>(
+        return_value: impl Fn(usize, usize) -> Option<&'a [u8]>,
+        base_scalar: u64,
+        quote_scalar: u64,
+    ) -> Result<PoolParams> {
+        let value = |command: usize, index: usize| -> Result<&'a [u8]> {
+            return_value(command, index)
+                .ok_or_else(|| anyhow!("Missing return value {} of command {}", index, command))
+        };
+        let number = |command: usize, index: usize| -> Result<u64> {
+            bcs::from_bytes(value(command, index)?)
+                .with_context(|| format!("Failed to decode return value {} of command {}", index, command))
+        };
+        let prices = |index: usize| -> Result<Vec<u64>> {
+            bcs::from_bytes(value(3, index)?).with_context(|| "Failed to decode order book prices")
+        };
+
+        // The mid is computed from the best levels, as `pool::mid_price` aborts on an empty side
+        let best_bid = prices(0)?.first().copied();
+        let best_ask = prices(2)?.first().copied();
+        let mid_price = best_bid
+            .zip(best_ask)
+            .map(|(bid, ask)| decode_price((bid + ask) / 2, base_scalar, quote_scalar));
+
+        Ok(PoolParams {
+            book: PoolBookParams { tick_size: number(0, 0)?, lot_size: number(0, 1)?, min_size: number(0, 2)? },
+            trade: PoolTradeParams {
+                taker_fee: FeeRate::from_float_scalar(number(1, 0)?),
+                maker_fee: FeeRate::from_float_scalar(number(1, 1)?),
+                stake_required: number(1, 2)?,
+            },
+            whitelisted: bcs::from_bytes(value(2, 0)?).with_context(|| "Failed to decode whitelisted")?,
+            mid_price,
+        })
+    }
+}
+
+/// A fill in a pool, in human units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trade {
+    pub trade_id: String,
+    pub price: f64,
+    pub base_volume: f64,
+    pub quote_volume: f64,
+    /// Whether the taker bought the base coin.
+    pub taker_is_bid: bool,
+    /// Timestamp of the fill in milliseconds.
+    pub timestamp: u64,
+}
+
+impl Trade {
+    /// Decodes the parsed JSON of an `order_info::OrderFilled` event into human units.
+    ///
+    /// # Arguments
+    /// * `trade_id` - The id to give the trade, e.g. the event id.
+    /// * `event` - The parsed JSON of the event.
+    /// * `base_scalar` - The scalar of the pool's base coin.
+    /// * `quote_scalar` - The scalar of the pool's quote coin.
+    pub fn from_order_filled_json(
+        trade_id: String,
+        event: &serde_json::Value,
+        base_scalar: u64,
+        quote_scalar: u64,
+    ) -> Result<Trade> {
+        let fill = FillAmounts::from_order_filled_json(event)?;
+
+        Ok(Trade {
+            trade_id,
+            price: decode_price(fill.price, base_scalar, quote_scalar),
+            base_volume: fill.base_quantity as f64 / base_scalar as f64,
+            quote_volume: fill.quote_quantity as f64 / quote_scalar as f64,
+            taker_is_bid: fill.taker_is_bid,
+            timestamp: fill.timestamp,
+        })
+    }
+}
+
+/// The amounts of an `order_info::OrderFilled` event in on-chain units, shared by `Trade` and
+/// `OrderEvent::Filled`.
+struct FillAmounts {
+    price: u64,
+    base_quantity: u64,
+    quote_quantity: u64,
+    taker_is_bid: bool,
+    timestamp: u64,
+}
+
+impl FillAmounts {
+    fn from_order_filled_json(event: &serde_json::Value) -> Result<FillAmounts> {
+        let field = |name: &str| event
+            .get(name)
+            .and_then(json_number::<u64>)
+            .ok_or_else(|| anyhow!("OrderFilled event is missing {}", name));
+
+        Ok(FillAmounts {
+            price: field("price")?,
+            base_quantity: field("base_quantity")?,
+            quote_quantity: field("quote_quantity")?,
+            taker_is_bid: event
+                .get("taker_is_bid")
+                .and_then(|taker_is_bid| taker_is_bid.as_bool())
+                .ok_or_else(|| anyhow!("OrderFilled event is missing taker_is_bid"))?,
+            timestamp: field("timestamp")?,
+        })
+    }
+}
+
+/// A fill of one balance manager's order, from that manager's side, in human units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManagerTrade {
+    /// The fill itself, with its price, volumes and timestamp.
+    pub trade: Trade,
+    pub pool_id: ObjectID,
+    pub order_id: u128,
+    /// Whether the manager bought the base coin.
+    pub is_bid: bool,
+    /// Whether the manager's order was the resting one.
+    pub is_maker: bool,
+    /// The fee paid by the manager: in DEEP if `fee_is_deep`, otherwise in the coin it paid in,
+    /// i.e. quote for a bid and base for an ask.
+    pub fee_paid: f64,
+    pub fee_is_deep: bool,
+}
+
+impl ManagerTrade {
+    /// Decodes the parsed JSON of an `order_info::OrderFilled` event from the side of `manager_id`.
+    ///
+    /// # Arguments
+    /// * `trade_id` - The id to give the trade, e.g. the event id.
+    /// * `event` - The parsed JSON of the event.
+    /// * `manager_id` - The balance manager to decode the fill for.
+    /// * `scalars` - The scalars of the pool's base coin, quote coin and DEEP.
+    ///
+    /// # Returns
+    /// The trade, or None if neither side of the fill is `manager_id`.
+    pub fn from_order_filled_json(
+        trade_id: String,
+        event: &serde_json::Value,
+        manager_id: ObjectID,
+        (base_scalar, quote_scalar, deep_scalar): (u64, u64, u64),
+    ) -> Result<Option<ManagerTrade>> {
+        let number = |name: &str| event
+            .get(name)
+            .and_then(json_number::<u128>)
+            .ok_or_else(|| anyhow!("OrderFilled event is missing {}", name));
+        let manager = |name: &str| event.get(name).and_then(json_object_id);
+
+        let is_maker = if manager("maker_balance_manager_id") == Some(manager_id) {
+            true
+        } else if manager("taker_balance_manager_id") == Some(manager_id) {
+            false
+        } else {
+            return Ok(None);
+        };
+        let trade = Trade::from_order_filled_json(trade_id, event, base_scalar, quote_scalar)?;
+        let side = if is_maker { "maker" } else { "taker" };
+        let is_bid = trade.taker_is_bid != is_maker;
+        let fee_is_deep = event
+            .get(&format!("{}_fee_is_deep", side))
+            .and_then(|flag| flag.as_bool())
+            .ok_or_else(|| anyhow!("OrderFilled event is missing {}_fee_is_deep", side))?;
+        let fee_scalar = match (fee_is_deep, is_bid) {
+            (true, _) => deep_scalar,
+            (false, true) => quote_scalar,
+            (false, false) => base_scalar,
+        };
+
+        Ok(Some(ManagerTrade {
+            trade,
+            pool_id: event
+                .get("pool_id")
+                .and_then(json_object_id)
+                .ok_or_else(|| anyhow!("OrderFilled event is missing pool_id"))?,
+            order_id: number(&format!("{}_order_id", side))?,
+            is_bid,
+            is_maker,
+            fee_paid: number(&format!("{}_fee", side))? as f64 / fee_scalar as f64,
+            fee_is_deep,
+        }))
+    }
+}
+
+/// A page of a balance manager's fills, and the cursor to fetch the next page from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManagerTradeHistory {
+    /// The fills, most recent first.
+    pub trades: Vec<ManagerTrade>,
+    /// The event to continue after, or None once there are no older events.
+    pub next_cursor: Option<EventID>,
+}
+
+/// An order lifecycle event emitted by the DeepBook package, in on-chain units.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OrderEvent {
+    /// `order_info::OrderPlaced`: an order was inserted into the book.
+    Placed {
+        pool_id: ObjectID,
+        balance_manager_id: ObjectID,
+        order_id: u128,
+        client_order_id: u64,
+        price: u64,
+        is_bid: bool,
+        placed_quantity: u64,
+        expire_timestamp: u64,
+    },
+    /// `order_info::OrderFilled`: a taker order matched a resting maker order.
+    Filled {
+        pool_id: ObjectID,
+        maker_order_id: u128,
+        taker_order_id: u128,
+        maker_balance_manager_id: ObjectID,
+        taker_balance_manager_id: ObjectID,
+        price: u64,
+        base_quantity: u64,
+        quote_quantity: u64,
+        taker_is_bid: bool,
+        timestamp: u64,
+    },
+    /// `order::OrderCanceled`: a resting order was canceled by its owner.
+    Canceled {
+        pool_id: ObjectID,
+        balance_manager_id: ObjectID,
+        order_id: u128,
+        client_order_id: u64,
+        price: u64,
+        is_bid: bool,
+        /// The base quantity left on the book that was canceled.
+        canceled_quantity: u64,
+    },
+    /// `order_info::OrderExpired`: an expired order was removed from the book while matching.
+    Expired {
+        pool_id: ObjectID,
+        balance_manager_id: ObjectID,
+        order_id: u128,
+        client_order_id: u64,
+        price: u64,
+        is_bid: bool,
+        /// The base quantity left on the book that was removed.
+        canceled_quantity: u64,
+    },
+    /// `order::OrderModified`: the quantity of a resting order was reduced.
+    Modified {
+        pool_id: ObjectID,
+        balance_manager_id: ObjectID,
+        order_id: u128,
+        client_order_id: u64,
+        price: u64,
+        is_bid: bool,
+        new_quantity: u64,
+    },
+}
+
+impl OrderEvent {
+    /// Decodes the parsed JSON of a DeepBook event.
+    ///
+    /// # Arguments
+    /// * `event_name` - The name of the event struct, e.g. `OrderFilled`.
+    /// * `event` - The parsed JSON of the event.
+    ///
+    /// # Returns
+    /// The event, or None if `event_name` isn't an order lifecycle event.
+    pub fn from_event_json(event_name: &str, event: &serde_json::Value) -> Result<Option<OrderEvent>> {
+        let number = |name: &str| event
+            .get(name)
+            .and_then(json_number::<u128>)
+            .ok_or_else(|| anyhow!("{} event is missing {}", event_name, name));
+        let flag = |name: &str| event
+            .get(name)
+            .and_then(|flag| flag.as_bool())
+            .ok_or_else(|| anyhow!("{} event is missing {}", event_name, name));
+        let id = |name: &str| event
+            .get(name)
+            .and_then(json_object_id)
+            .ok_or_else(|| anyhow!("{} event is missing {}", event_name, name));
+
+        let order_event = match event_name {
+            "OrderPlaced" => OrderEvent::Placed {
+                pool_id: id("pool_id")?,
+                balance_manager_id: id("balance_manager_id")?,
+                order_id: number("order_id")?,
+                client_order_id: number("client_order_id")? as u64,
+                price: number("price")? as u64,
+                is_bid: flag("is_bid")?,
+                placed_quantity: number("placed_quantity")? as u64,
+                expire_timestamp: number("expire_timestamp")? as u64,
+            },
+            "OrderFilled" => {
+                let fill = FillAmounts::from_order_filled_json(event)?;
+
+                OrderEvent::Filled {
+                    pool_id: id("pool_id")?,
+                    maker_order_id: number("maker_order_id")?,
+                    taker_order_id: number("taker_order_id")?,
+                    maker_balance_manager_id: id("maker_balance_manager_id")?,
+                    taker_balance_manager_id: id("taker_balance_manager_id")?,
+                    price: fill.price,
+                    base_quantity: fill.base_quantity,
+                    quote_quantity: fill.quote_quantity,
+                    taker_is_bid: fill.taker_is_bid,
+                    timestamp: fill.timestamp,
+                }
+            }
+            "OrderCanceled" | "OrderExpired" => {
+                let (pool_id, balance_manager_id) = (id("pool_id")?, id("balance_manager_id")?);
+                let (order_id, client_order_id) = (number("order_id")?, number("client_order_id")? as u64);
+                let (price, is_bid) = (number("price")? as u64, flag("is_bid")?);
+                let canceled_quantity = number("base_asset_quantity_canceled")? as u64;
+
+                if event_name == "OrderCanceled" {
+                    OrderEvent::Canceled { pool_id, balance_manager_id, order_id, client_order_id, price, is_bid, canceled_quantity }
+                } else {
+                    OrderEvent::Expired { pool_id, balance_manager_id, order_id, client_order_id, price, is_bid, canceled_quantity }
+                }
+            }
+            "OrderModified" => OrderEvent::Modified {
+                pool_id: id("pool_id")?,
+                balance_manager_id: id("balance_manager_id")?,
+                order_id: number("order_id")?,
+                client_order_id: number("client_order_id")? as u64,
+                price: number("price")? as u64,
+                is_bid: flag("is_bid")?,
+                new_quantity: number("new_quantity")? as u64,
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(order_event))
+    }
+}
+
+/// Trading statistics of a pool over a period, as shown on market listings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolStats {
+    pub volume_base: f64,
+    pub volume_quote: f64,
+    pub high: f64,
+    pub low: f64,
+    /// Price of the most recent trade.
+    pub last: f64,
+    /// Change from the price of the oldest trade to `last`, in percent.
+    pub change_pct: f64,
+}
+
+impl PoolStats {
+    /// Aggregates trades, in any order, into statistics. None if there are no trades.
+    pub fn from_trades(trades: &[Trade]) -> Option<PoolStats> {
+        let first = trades.iter().min_by_key(|trade| trade.timestamp)?;
+        let last = trades.iter().max_by_key(|trade| trade.timestamp)?;
+
+        Some(PoolStats {
+            volume_base: trades.iter().map(|trade| trade.base_volume).sum(),
+            volume_quote: trades.iter().map(|trade| trade.quote_volume).sum(),
+            high: trades.iter().map(|trade| trade.price).fold(f64::MIN, f64::max),
+            low: trades.iter().map(|trade| trade.price).fold(f64::MAX, f64::min),
+            last: last.price,
+            change_pct: (last.price - first.price) / first.price * 100.0,
+        })
+    }
+}
+
+/// Last price and 24h volumes of a pool.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ticker {
+    pub last_price: f64,
+    pub base_volume: f64,
+    pub quote_volume: f64,
+    pub is_frozen: bool,
+}
+
+/// Rescales an on-chain price into quote per base in human units.
+fn decode_price(price: u64, base_scalar: u64, quote_scalar: u64) -> f64 {
+    price as f64 * base_scalar as f64 / (FLOAT_SCALAR as f64 * quote_scalar as f64)
+}
+
+/// Capabilities authorized on a balance manager, decoded from its `allow_listed` set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ManagerCaps {
+    pub owner: SuiAddress,
+    /// Ids of every allow-listed cap, whatever its kind.
+    pub allow_listed: Vec<ObjectID>,
+    pub trade_caps: Vec<ObjectID>,
+    pub deposit_caps: Vec<ObjectID>,
+    pub withdraw_caps: Vec<ObjectID>,
+}
+
+impl ManagerCaps {
+    /// Decodes the owner and allow-listed cap ids from the JSON fields of a `BalanceManager`.
+    /// Caps are sorted into kinds by `classify`.
+    pub fn from_manager_json(manager: &serde_json::Value) -> Result<ManagerCaps> {
+        let owner = move_field(manager, "owner")
+            .and_then(|owner| owner.as_str())
+            .and_then(|owner| SuiAddress::from_str(owner).ok())
+            .ok_or_else(|| anyhow!("BalanceManager is missing its owner"))?;
+        let allow_listed = move_field(manager, "allow_listed")
+            .and_then(|allow_listed| move_field(allow_listed, "contents"))
+            .and_then(|contents| contents.as_array())
+            .ok_or_else(|| anyhow!("BalanceManager is missing allow_listed"))?
+            .iter()
+            .map(|cap_id| json_object_id(cap_id).ok_or_else(|| anyhow!("Invalid allow-listed cap id: {}", cap_id)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ManagerCaps { owner, allow_listed, ..Default::default() })
+    }
+
+    /// Sorts allow-listed caps into trade, deposit and withdraw caps from their object types.
+    pub fn classify(&mut self, cap_types: &[(ObjectID, String)]) {
+        for (cap_id, cap_type) in cap_types {
+            let caps = if cap_type.ends_with("::balance_manager::TradeCap") {
+                &mut self.trade_caps
+            } else if cap_type.ends_with("::balance_manager::DepositCap") {
+                &mut self.deposit_caps
+            } else if cap_type.ends_with("::balance_manager::WithdrawCap") {
+                &mut self.withdraw_caps
+            } else {
+                continue;
+            };
+            caps.push(*cap_id);
+        }
+    }
+}
+
+/// On-chain metadata of a coin type, as published in its `CoinMetadata` object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoinMetadata {
+    pub decimals: u8,
+    pub symbol: String,
+    pub name: String,
+}
+
+impl CoinMetadata {
+    /// The scalar of the coin, i.e. `10^decimals`, or None if it overflows a `u64`.
+    pub fn scalar(&self) -> Option<u64> {
+        10u64.checked_pow(self.decimals as u32)
+    }
+
+    /// Returns true if a configured scalar matches the on-chain decimals of the coin.
+    pub fn scalar_matches(&self, scalar: u64) -> bool {
+        self.scalar() == Some(scalar)
+    }
+}
+
+/// Supply of the DEEP token, read from the `TreasuryCap<DEEP>` held by the DEEP `ProtectedTreasury`.
+/// DEEP is minted once and can only be burned afterwards, so burns show up as a falling supply.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeepTreasuryInfo {
+    pub treasury_cap_id: ObjectID,
+    /// Total supply in DEEP.
+    pub total_supply: f64,
+}
+
+impl DeepTreasuryInfo {
+    /// Decodes the treasury info from the JSON fields of a `0x2::coin::TreasuryCap<DEEP>`.
+    /// The supply is scaled with `deep_scalar`, the scalar of the configured DEEP coin.
+    pub fn from_treasury_cap_json(treasury_cap: &serde_json::Value, deep_scalar: u64) -> Result<DeepTreasuryInfo> {
+        let treasury_cap_id = move_field(treasury_cap, "id")
+            .and_then(json_object_id)
+            .ok_or_else(|| anyhow!("TreasuryCap is missing its id"))?;
+        let total_supply = move_field(treasury_cap, "total_supply")
+            .and_then(|supply| move_field(supply, "value"))
+            .and_then(json_number::<u64>)
+            .ok_or_else(|| anyhow!("TreasuryCap is missing total_supply"))?;
+
+        Ok(DeepTreasuryInfo {
+            treasury_cap_id,
+            total_supply: total_supply as f64 / deep_scalar as f64,
+        })
+    }
+}
+
+/// Decodes the package versions allowed by the DeepBook registry or a pool from the JSON fields
+/// of its `RegistryInner` or `PoolInner` struct, in ascending order.
+pub(crate) fn allowed_versions_from_json(inner: &serde_json::Value) -> Result<Vec<u64>> {
+    let mut versions = move_field(inner, "allowed_versions")
+        .and_then(|allowed_versions| move_field(allowed_versions, "contents"))
+        .and_then(|contents| contents.as_array())
+        .ok_or_else(|| anyhow!("Object is missing allowed_versions"))?
+        .iter()
+        .map(|version| json_number::<u64>(version).ok_or_else(|| anyhow!("Invalid allowed version: {}", version)))
+        .collect::<Result<Vec<_>>>()?;
+    versions.sort_unstable();
+
+    Ok(versions)
+}
+
+/// Decodes the coin types of the pools registered in the registry, from the JSON names of the
+/// `registry::PoolKey { base, quote }` entries of its `pools` bag. Each coin is listed once,
+/// in the order of the first pool using it.
+pub(crate) fn coin_types_from_pool_keys(pool_keys: &[serde_json::Value]) -> Result<Vec<TypeTag>> {
+    let mut coin_types = vec![];
+    for pool_key in pool_keys {
+        for side in ["base", "quote"] {
+            let coin_type = pool_key_coin_type(pool_key, side)?;
+            let coin_type = TypeTag::from_str(&coin_type)
+                .with_context(|| format!("Invalid coin type: {}", coin_type))?;
+            if !coin_types.contains(&coin_type) {
+                coin_types.push(coin_type);
+            }
+        }
+    }
+
+    Ok(coin_types)
+}
+
+/// A pool registered in the DeepBook registry.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RegisteredPool {
+    pub base_type: String,
+    pub quote_type: String,
+    pub pool_id: ObjectID,
+}
+
+/// Decodes the pools registered in the registry from the JSON fields of the
+/// `dynamic_field::Field<PoolKey, ID>` entries of its `pools` bag, in the given order.
+pub(crate) fn registered_pools_from_fields(fields: &[serde_json::Value]) -> Result<Vec<RegisteredPool>> {
+    fields
+        .iter()
+        .map(|field| {
+            let pool_key = move_field(field, "name")
+                .ok_or_else(|| anyhow!("Registry entry is missing its pool key: {}", field))?;
+            let pool_id = move_field(field, "value")
+                .and_then(json_object_id)
+                .ok_or_else(|| anyhow!("Registry entry is missing its pool id: {}", field))?;
+
+            Ok(RegisteredPool {
+                base_type: pool_key_coin_type(pool_key, "base")?,
+                quote_type: pool_key_coin_type(pool_key, "quote")?,
+                pool_id,
+            })
+        })
+        .collect()
+}
+
+/// Reads the `base` or `quote` coin type of a `registry::PoolKey`, with a `0x` prefix.
+fn pool_key_coin_type(pool_key: &serde_json::Value, side: &str) -> Result<String> {
+    let name = move_field(pool_key, side)
+        .and_then(|type_name| move_field(type_name, "name"))
+        .and_then(|name| name.as_str())
+        .ok_or_else(|| anyhow!("Pool key is missing {} type: {}", side, pool_key))?;
+
+    // `TypeName`s are stored without the `0x` prefix
+    Ok(format!("0x{}", name.trim_start_matches("0x")))
+}
+
+/// Decodes the `(bid_order_count, ask_order_count)` of a pool from the JSON fields of its
+/// `PoolInner` struct, i.e. the lengths of the book's bid and ask `BigVector`s.
+pub(crate) fn order_counts_from_json(pool_inner: &serde_json::Value) -> Result<(u64, u64)> {
+    let book = move_field(pool_inner, "book")
+        .ok_or_else(|| anyhow!("Pool state is missing book"))?;
+    let length = |side: &str| move_field(book, side)
+        .and_then(|orders| move_field(orders, "length"))
+        .and_then(json_number::<u64>)
+        .ok_or_else(|| anyhow!("Book is missing the length of {}", side));
+
+    Ok((length("bids")?, length("asks")?))
+}
+
+/// Computes how old the latest DEEP price point of a pool is at `now_ms`, from the JSON fields of
+/// its `PoolInner` struct. Points are appended to the `base_prices` or `quote_prices` of its
+/// `deep_price`, depending on the reference pool, so the latest of both lasts is taken.
+pub(crate) fn deep_price_age_from_json(pool_inner: &serde_json::Value, now_ms: u64) -> Result<Duration> {
+    let deep_price = move_field(pool_inner, "deep_price")
+        .ok_or_else(|| anyhow!("Pool state is missing deep_price"))?;
+    let last_timestamp = |side: &str| -> Result<Option<u64>> {
+        let prices = move_field(deep_price, side)
+            .and_then(|prices| prices.as_array())
+            .ok_or_else(|| anyhow!("DEEP price is missing {}", side))?;
+        prices
+            .last()
+            .map(|price| move_field(price, "timestamp")
+                .and_then(json_number::<u64>)
+                .ok_or_else(|| anyhow!("Invalid DEEP price point: {}", price)))
+            .transpose()
+    };
+
+    let last_ms = last_timestamp("base_prices")?
+        .max(last_timestamp("quote_prices")?)
+        .ok_or_else(|| anyhow!("Pool has no DEEP price points"))?;
+
+    Ok(Duration::from_millis(now_ms.saturating_sub(last_ms)))
+}
+
+/// Looks up a field of a Move struct rendered as Sui JSON.
+/// Accepts both the flattened form (`{"name": ...}`) and the typed form (`{"fields": {"name": ...}}`).
+pub(crate) fn move_field<'a>(value: &'a serde_json::Value, name: &str) -> Option<&'a serde_json::Value> {
+    value
+        .get(name)
+        .or_else(|| value.get("fields").and_then(|fields| fields.get(name)))
+}
+
+/// Reads an object id from a Sui JSON value, either a plain address string or an `ID`/`UID` struct.
+pub(crate) fn json_object_id(value: &serde_json::Value) -> Option<ObjectID> {
+    match value {
+        serde_json::Value::String(s) => ObjectID::from_hex_literal(s).ok(),
+        _ => move_field(value, "id")
+            .or_else(|| move_field(value, "bytes"))
+            .and_then(json_object_id),
+    }
+}
+
+/// Reads a number from a Sui JSON value.
+/// Sui encodes `u64`/`u128` fields of parsed Move structs as strings, so both strings
+/// and plain JSON numbers are accepted.
+pub(crate) fn json_number<T: FromStr>(value: &serde_json::Value) -> Option<T> {
+    match value {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::config::DEEP_SCALAR;
+    use crate::utils::constants::TESTNET_COINS;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_decode_proposals() {
+        let governance = serde_json::json!({
+            "type": "0xdee9::governance::Governance",
+            "fields": {
+                "epoch": "512",
+                "proposals": {
+                    "type": "0x2::vec_map::VecMap<0x2::object::ID, 0xdee9::governance::Proposal>",
+                    "fields": {
+                        "contents": [
+                            {
+                                "fields": {
+                                    "key": "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9",
+                                    "value": {
+                                        "fields": {
+                                            "taker_fee": "1000000",
+                                            "maker_fee": "500000",
+                                            "stake_required": "100000000",
+                                            "votes": "250000000"
+                                        }
+                                    }
+                                }
+                            }
+                        ]
+                    }
+                }
+            }
+        });
+
+        let proposals = Proposal::from_governance_json(&governance, DEEP_SCALAR).unwrap();
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(
+            proposals[0].id,
+            ObjectID::from_hex_literal("0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9").unwrap()
+        );
+        assert_eq!(proposals[0].taker_fee, 0.001);
+        assert_eq!(proposals[0].maker_fee, 0.0005);
+        assert_eq!(proposals[0].stake_required, 100.0);
+        assert_eq!(proposals[0].votes, 250.0);
+
+        // A DEEP coin configured with 3 decimals
+        let proposals = Proposal::from_governance_json(&governance, 1_000).unwrap();
+        assert_eq!(proposals[0].stake_required, 100_000.0);
+        assert_eq!(proposals[0].votes, 250_000.0);
+    }
+
+    #[test]
+    fn test_proposal_voter_count_keeps_latest_vote_of_each_manager() {
+        let proposal_id = ObjectID::from_hex_literal("0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9").unwrap();
+        let other_proposal_id = ObjectID::from_hex_literal("0x1c19362ca52b8ffd7a33cee805a67d40f31e6ba303753fd3a4cfdfacea7163a5").unwrap();
+        let vote = |manager: &str, to_proposal_id: ObjectID, epoch: &str| serde_json::json!({
+            "pool_id": "0x2",
+            "balance_manager_id": manager,
+            "epoch": epoch,
+            "from_proposal_id": null,
+            "to_proposal_id": to_proposal_id.to_string(),
+            "stake": "100000000",
+        });
+        // Newest first
+        let events = [
+            vote("0xa", proposal_id, "512"),
+            // 0xb moved its vote to the other proposal
+            vote("0xb", other_proposal_id, "512"),
+            vote("0xb", proposal_id, "512"),
+            vote("0xc", proposal_id, "512"),
+            // Votes of an earlier epoch were reset
+            vote("0xd", proposal_id, "511"),
+        ];
+
+        assert_eq!(proposal_voter_count(&events, proposal_id, 512), 2);
+        assert_eq!(proposal_voter_count(&events, other_proposal_id, 512), 1);
+        assert_eq!(proposal_voter_count(&events, proposal_id, 513), 0);
+    }
+
+    #[test]
+    fn test_order_book_from_level2() {
+        // SUI (9 decimals) quoted in USDC (6 decimals): 3.5 USDC is 3_500_000 on-chain
+        let level2 = [vec![3_500_000, 3_400_000], vec![2_000_000_000, 500_000_000], vec![3_600_000], vec![1_000_000_000]];
+
+        let mut book = OrderBook::from_level2(level2, 1_000_000_000, 1_000_000);
+        assert_eq!(book.bids, vec![
+            BookLevel { price: 3.5, quantity: 2.0 },
+            BookLevel { price: 3.4, quantity: 0.5 },
+        ]);
+        assert_eq!(book.asks, vec![BookLevel { price: 3.6, quantity: 1.0 }]);
+
+        book.truncate(1);
+        assert_eq!(book.bids.len(), 1);
+        assert_eq!(book.asks.len(), 1);
+    }
+
+    #[test]
+    fn test_best_bid_ask_of_one_sided_book() {
+        // A one-tick read of a book with bids only
+        let level2 = [vec![3_500_000], vec![2_000_000_000], vec![], vec![]];
+
+        let book = OrderBook::from_level2(level2, 1_000_000_000, 1_000_000);
+        assert_eq!(book.best_bid_ask(), (Some(3.5), None));
+        assert_eq!(OrderBook::default().best_bid_ask(), (None, None));
+    }
+
+    #[test]
+    fn test_coin_types_from_registry_pool_keys() {
+        let sui = "0000000000000000000000000000000000000000000000000000000000000002::sui::SUI";
+        let deep = "36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8::deep::DEEP";
+        let usdc = "f7152c05930480cd740d7311b5b8b45c6f488e3a53a11c3f74a6fac36a52e0d7::DBUSDC::DBUSDC";
+        let pool_key = |base: &str, quote: &str| serde_json::json!({
+            "base": { "name": base },
+            "quote": { "name": quote },
+        });
+        let pool_keys = [pool_key(deep, sui), pool_key(sui, usdc), pool_key(deep, usdc)];
+
+        let coin_types = coin_types_from_pool_keys(&pool_keys).unwrap();
+        let expected: Vec<TypeTag> = [deep, sui, usdc]
+            .iter()
+            .map(|name| TypeTag::from_str(&format!("0x{}", name)).unwrap())
+            .collect();
+        assert_eq!(coin_types, expected);
+
+        assert!(coin_types_from_pool_keys(&[serde_json::json!({ "base": { "name": sui } })]).is_err());
+    }
+
+    #[test]
+    fn test_deep_price_age() {
+        let price = |timestamp: u64| serde_json::json!({
+            "type": "0xdee9::deep_price::Price",
+            "fields": { "conversion_rate": "20000000000", "timestamp": timestamp.to_string() }
+        });
+        let pool_inner = serde_json::json!({
+            "deep_price": {
+                "type": "0xdee9::deep_price::DeepPrice",
+                "fields": {
+                    "base_prices": [],
+                    "cumulative_base": "0",
+                    "quote_prices": [price(1_700_000_000_000), price(1_700_000_060_000)],
+                    "cumulative_quote": "40000000000"
+                }
+            }
+        });
+
+        let age = deep_price_age_from_json(&pool_inner, 1_700_000_090_000).unwrap();
+        assert_eq!(age, Duration::from_secs(30));
+        // A clock behind the price point doesn't underflow
+        assert_eq!(deep_price_age_from_json(&pool_inner, 1_700_000_000_000).unwrap(), Duration::ZERO);
+
+        let no_points = serde_json::json!({ "deep_price": { "base_prices": [], "quote_prices": [] } });
+        assert!(deep_price_age_from_json(&no_points, 1_700_000_090_000).is_err());
+    }
+
+    #[test]
+    fn test_book_metrics() {
+        let book = OrderBook {
+            bids: vec![BookLevel { price: 3.99, quantity: 100.0 }, BookLevel { price: 3.98, quantity: 200.0 }],
+            asks: vec![BookLevel { price: 4.01, quantity: 50.0 }, BookLevel { price: 4.03, quantity: 50.0 }],
+        };
+
+        let metrics = book.metrics().unwrap();
+        assert_eq!(metrics.best_bid, 3.99);
+        assert_eq!(metrics.best_ask, 4.01);
+        assert!((metrics.spread - 0.02).abs() < 1e-12);
+        assert!((metrics.spread_bps - 50.0).abs() < 1e-9);
+        assert_eq!(metrics.bid_depth, 300.0);
+        assert_eq!(metrics.ask_depth, 100.0);
+        assert_eq!(metrics.imbalance, 0.5);
+
+        assert_eq!(OrderBook { bids: book.bids, asks: vec![] }.metrics(), None);
+    }
+
+    #[test]
+    fn test_pool_params_from_batch_slots() {
+        // SUI (9 decimals) quoted in USDC (6 decimals), best bid 3.99 and best ask 4.01
+        let results = vec![
+            vec![bcs::to_bytes(&1_000u64).unwrap(), bcs::to_bytes(&100_000_000u64).unwrap(), bcs::to_bytes(&1_000_000_000u64).unwrap()],
+            vec![bcs::to_bytes(&1_000_000u64).unwrap(), bcs::to_bytes(&500_000u64).unwrap(), bcs::to_bytes(&100_000_000u64).unwrap()],
+            vec![bcs::to_bytes(&true).unwrap()],
+            vec![
+                bcs::to_bytes(&vec![3_990_000u64]).unwrap(),
+                bcs::to_bytes(&vec![5_000_000_000u64]).unwrap(),
+                bcs::to_bytes(&vec![4_010_000u64]).unwrap(),
+                bcs::to_bytes(&vec![2_000_000_000u64]).unwrap(),
+            ],
+        ];
+        fn slot(results: &[Vec<Vec<u8>>]) -> impl Fn(usize, usize) -> Option<&[u8]> {
+            move |command, index| results.get(command).and_then(|values| values.get(index)).map(Vec::as_slice)
+        }
+
+        let params = PoolParams::from_return_values(slot(&results), 1_000_000_000, 1_000_000).unwrap();
+        assert_eq!(params.book, PoolBookParams { tick_size: 1_000, lot_size: 100_000_000, min_size: 1_000_000_000 });
+        assert_eq!(params.trade, PoolTradeParams {
+            taker_fee: FeeRate::from_float_scalar(1_000_000),
+            maker_fee: FeeRate::from_float_scalar(500_000),
+            stake_required: 100_000_000,
+        });
+        assert!(params.whitelisted);
+        assert!((params.mid_price.unwrap() - 4.0).abs() < 1e-9);
+
+        // An empty ask side has no mid, and the other slots still decode
+        let mut one_sided = results.clone();
+        one_sided[3][2] = bcs::to_bytes(&Vec::<u64>::new()).unwrap();
+        let params = PoolParams::from_return_values(slot(&one_sided), 1_000_000_000, 1_000_000).unwrap();
+        assert_eq!(params.mid_price, None);
+        assert_eq!(params.book.tick_size, 1_000);
+
+        assert!(PoolParams::from_return_values(slot(&results[..3]), 1_000_000_000, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn test_pool_stats_from_order_filled_events() {
+        let fill = |price: u64, base_quantity: u64, taker_is_bid: bool, timestamp: u64| serde_json::json!({
+            "pool_id": "0xe05dafb5133bcffb8d59f4e12465dc0e9faeaa05e3e342a08fe135800e3e4407",
+            "maker_order_id": "68160737799100866923792791",
+            "taker_order_id": "170141183460537392451039660509112362617",
+            "price": price.to_string(),
+            "taker_is_bid": taker_is_bid,
+            "base_quantity": base_quantity.to_string(),
+            "quote_quantity": (price as u128 * base_quantity as u128 / 1_000_000_000).to_string(),
+            "timestamp": timestamp.to_string(),
+        });
+        // SUI (9 decimals) quoted in USDC (6 decimals), listed out of order
+        let events = [
+            fill(3_600_000, 2_000_000_000, true, 1_700_000_300_000),
+            fill(3_500_000, 1_000_000_000, false, 1_700_000_000_000),
+            fill(3_800_000, 1_000_000_000, true, 1_700_000_100_000),
+            fill(3_400_000, 4_000_000_000, false, 1_700_000_200_000),
+        ];
+        let trades: Vec<Trade> = events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| Trade::from_order_filled_json(i.to_string(), event, 1_000_000_000, 1_000_000).unwrap())
+            .collect();
+        assert_eq!(trades[0].price, 3.6);
+        assert_eq!(trades[0].quote_volume, 7.2);
+        assert!(!trades[1].taker_is_bid);
+
+        let stats = PoolStats::from_trades(&trades).unwrap();
+        assert_eq!(stats.volume_base, 8.0);
+        assert!((stats.volume_quote - 28.1).abs() < 1e-9);
+        assert_eq!(stats.high, 3.8);
+        assert_eq!(stats.low, 3.4);
+        assert_eq!(stats.last, 3.6);
+        // From the oldest trade at 3.5 to the latest at 3.6
+        assert!((stats.change_pct - 2.857142857).abs() < 1e-6);
+        assert!(PoolStats::from_trades(&[]).is_none());
+    }
+
+    #[test]
+    fn test_decode_manager_trade_from_order_filled_event() {
+        let maker = "0x344c2734b1d211bd15212bfb7847c66a3b18803f3f5ab00f5ff6f87b6fe6d27d";
+        let taker = "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9";
+        let event = serde_json::json!({
+            "pool_id": "0xe05dafb5133bcffb8d59f4e12465dc0e9faeaa05e3e342a08fe135800e3e4407",
+            "maker_order_id": "68160737799100866923792791",
+            "taker_order_id": "170141183460537392451039660509112362617",
+            "maker_client_order_id": "1",
+            "taker_client_order_id": "2",
+            "price": "3715500",
+            "taker_is_bid": false,
+            "taker_fee": "202500",
+            "taker_fee_is_deep": true,
+            "maker_fee": "750000",
+            "maker_fee_is_deep": false,
+            "base_quantity": "405000000000",
+            "quote_quantity": "1504777500",
+            "maker_balance_manager_id": maker,
+            "taker_balance_manager_id": taker,
+            "timestamp": "1738704071994"
+        });
+        // SUI (9 decimals) quoted in USDC (6 decimals), fees in DEEP (6 decimals)
+        let scalars = (1_000_000_000, 1_000_000, 1_000_000);
+        let decode = |manager: &str| ManagerTrade::from_order_filled_json(
+            "0".to_string(),
+            &event,
+            ObjectID::from_hex_literal(manager).unwrap(),
+            scalars,
+        ).unwrap();
+
+        let sold = decode(taker).unwrap();
+        assert_eq!(sold.order_id, 170141183460537392451039660509112362617);
+        assert_eq!(sold.trade.price, 3.7155);
+        assert_eq!(sold.trade.base_volume, 405.0);
+        assert!(!sold.is_bid);
+        assert!(!sold.is_maker);
+        assert_eq!(sold.trade.timestamp, 1_738_704_071_994);
+        assert_eq!(sold.fee_paid, 0.2025);
+        assert!(sold.fee_is_deep);
+
+        // The resting bid paid its fee in the quote coin it bought with
+        let bought = decode(maker).unwrap();
+        assert_eq!(bought.order_id, 68160737799100866923792791);
+        assert!(bought.is_bid);
+        assert!(bought.is_maker);
+        assert_eq!(bought.fee_paid, 0.75);
+        assert!(!bought.fee_is_deep);
+
+        assert_eq!(decode("0x1"), None);
+
+        // The order event carries the same fill in on-chain units
+        let Some(OrderEvent::Filled { price, base_quantity, taker_is_bid, timestamp, .. }) =
+            OrderEvent::from_event_json("OrderFilled", &event).unwrap() else {
+            panic!("expected a fill");
+        };
+        assert_eq!((price, base_quantity), (3_715_500, 405_000_000_000));
+        assert_eq!((taker_is_bid, timestamp), (false, sold.trade.timestamp));
+    }
+
+    #[test]
+    fn test_decode_manager_caps() {
+        let trade_cap = "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9";
+        let withdraw_cap = "0x2f8ba7c9b09fd1f17b39b4e59ab6b1ab0ee4e5c1f7b8d6d3e8c8c80b1f3c0a01";
+        let manager = serde_json::json!({
+            "type": "0xdee9::balance_manager::BalanceManager",
+            "fields": {
+                "id": { "id": "0x69fffdae0075f8f71f4fa793549c11079266910e8905169845af1f5d00e09dcb" },
+                "owner": "0x032abf8948dda67a271bcc18e776dbbcfb0d58c8d288a700ff0d5521e57a1ffe",
+                "balances": {
+                    "type": "0x2::bag::Bag",
+                    "fields": { "id": { "id": "0x36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8" }, "size": "2" }
+                },
+                "allow_listed": {
+                    "type": "0x2::vec_set::VecSet<0x2::object::ID>",
+                    "fields": { "contents": [trade_cap, withdraw_cap] }
+                }
+            }
+        });
+        let trade_cap = ObjectID::from_hex_literal(trade_cap).unwrap();
+        let withdraw_cap = ObjectID::from_hex_literal(withdraw_cap).unwrap();
+
+        let mut caps = ManagerCaps::from_manager_json(&manager).unwrap();
+        assert_eq!(
+            caps.owner,
+            SuiAddress::from_str("0x032abf8948dda67a271bcc18e776dbbcfb0d58c8d288a700ff0d5521e57a1ffe").unwrap()
+        );
+        assert_eq!(caps.allow_listed, vec![trade_cap, withdraw_cap]);
+
+        caps.classify(&[
+            (trade_cap, "0xdee9::balance_manager::TradeCap".to_string()),
+            (withdraw_cap, "0xdee9::balance_manager::WithdrawCap".to_string()),
+        ]);
+        assert_eq!(caps.trade_caps, vec![trade_cap]);
+        assert!(caps.deposit_caps.is_empty());
+        assert_eq!(caps.withdraw_caps, vec![withdraw_cap]);
+    }
+
+    #[test]
+    fn test_decode_deep_treasury_info() {
+        let treasury_cap = serde_json::json!({
+            "type": "0x2::coin::TreasuryCap<0xdeeb7a4662eec9f2f3def03fb937a663dddaa2e215b8078a284d026b7946c270::deep::DEEP>",
+            "fields": {
+                "id": { "id": "0x2f8ba7c9b09fd1f17b39b4e59ab6b1ab0ee4e5c1f7b8d6d3e8c8c80b1f3c0a01" },
+                "total_supply": {
+                    "type": "0x2::balance::Supply<0xdeeb7a4662eec9f2f3def03fb937a663dddaa2e215b8078a284d026b7946c270::deep::DEEP>",
+                    "fields": { "value": "9999998500000000" }
+                }
+            }
+        });
+
+        let info = DeepTreasuryInfo::from_treasury_cap_json(&treasury_cap, DEEP_SCALAR).unwrap();
+        assert_eq!(
+            info.treasury_cap_id,
+            ObjectID::from_hex_literal("0x2f8ba7c9b09fd1f17b39b4e59ab6b1ab0ee4e5c1f7b8d6d3e8c8c80b1f3c0a01").unwrap()
+        );
+        assert_eq!(info.total_supply, 9_999_998_500.0);
+        let info = DeepTreasuryInfo::from_treasury_cap_json(&treasury_cap, 1_000_000_000).unwrap();
+        assert_eq!(info.total_supply, 9_999_998.5);
+        assert!(DeepTreasuryInfo::from_treasury_cap_json(&serde_json::json!({ "fields": {} }), DEEP_SCALAR).is_err());
+    }
+
+    #[test]
+    fn test_decode_account_stake() {
+        let account: Account = fixtures::decode(&fixtures::hex_bytes(fixtures::ACCOUNT_HEX));
+
+        assert_eq!(account.epoch, 512);
+        assert_eq!(account.open_orders.contents.len(), 2);
+        assert_eq!((account.active_stake, account.inactive_stake), (150_000_000, 25_000_000));
+        assert_eq!(account.stakes(DEEP_SCALAR), (150.0, 25.0));
+        // A DEEP coin configured with 9 decimals scales the same raw stake down
+        assert_eq!(account.stakes(1_000_000_000), (0.15, 0.025));
+        assert!(account.voted_proposal.is_some());
+        assert_eq!(account.owed_balances.quote, 1_000_000);
+    }
+
+    #[test]
+    fn test_account_volumes() {
+        let account = Account {
+            taker_volume: 2_500_000_000,
+            maker_volume: 12_000_000_000_000,
+            ..fixtures::account(&[])
+        };
+
+        // Volumes are tracked in base units: 9 decimals for SUI, 6 for DEEP
+        assert_eq!(account.volumes(1_000_000_000), (12_000.0, 2.5));
+        assert_eq!(account.volumes(1_000_000), (12_000_000.0, 2_500.0));
+        assert_eq!(fixtures::account(&[]).volumes(1_000_000_000), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_fee_tier_at_stake_required() {
+        let trade_params = PoolTradeParams {
+            taker_fee: FeeRate::from_float_scalar(1_000_000),
+            maker_fee: FeeRate::from_float_scalar(500_000),
+            stake_required: 100_000_000,
+        };
+
+        assert_eq!(trade_params.fee_tier(0), FeeTier::Standard);
+        assert_eq!(trade_params.fee_tier(99_999_999), FeeTier::Standard);
+        assert_eq!(trade_params.fee_tier(100_000_000), FeeTier::Staked);
+        assert_eq!(trade_params.fee_tier(100_000_001), FeeTier::Staked);
+    }
+
+    #[test]
+    fn test_account_settled_amounts_scale_each_coin() {
+        let account = Account {
+            settled_balances: Balances { base: 2_500_000_000, quote: 7_250_000, deep: 125_000 },
+            owed_balances: Balances { base: 0, quote: 1_000_000, deep: 0 },
+            ..fixtures::account(&[])
+        };
+        let scalar = |coin_key: &str| TESTNET_COINS[coin_key].scalar;
+
+        // SUI has 9 decimals, DBUSDC and DEEP 6; owed balances are not part of what a withdrawal reclaims
+        assert_eq!(account.settled_amounts(scalar("SUI"), scalar("DBUSDC"), scalar("DEEP")), (2.5, 7.25, 0.125));
+        // A quote coin with 8 decimals, told apart from the 6 decimals of DEEP
+        assert_eq!(account.settled_amounts(scalar("SUI"), 100_000_000, scalar("DEEP")), (2.5, 0.0725, 0.125));
+        // A DEEP coin configured with 9 decimals
+        assert_eq!(account.settled_amounts(scalar("SUI"), scalar("DBUSDC"), 1_000_000_000), (2.5, 7.25, 0.000125));
+    }
+
+    #[test]
+    fn test_decode_order() {
+        let bytes = fixtures::hex_bytes(fixtures::ORDER_HEX);
+        // 32-byte manager id, u128 order id, 9-byte deep price and seven other fixed-size fields
+        assert_eq!(bytes.len(), 32 + 16 + 8 + 8 + 8 + 1 + 9 + 8 + 1 + 8);
+
+        let order: Order = fixtures::decode(&bytes);
+        let order_info: OrderInfo = fixtures::decode(&fixtures::hex_bytes(fixtures::ORDER_INFO_HEX));
+        assert_eq!(order.balance_manager_id, order_info.balance_manager_id);
+        assert_eq!(order.order_id, order_info.order_id);
+        assert_eq!(order.client_order_id, 42);
+        assert_eq!((order.quantity, order.filled_quantity), (2_000_000_000, 1_000_000_000));
+        assert_eq!(order.remaining_quantity(), 1_000_000_000);
+        assert!(order.fee_is_deep);
+        assert_eq!(order.order_deep_price, OrderDeepPrice { asset_is_base: false, deep_per_asset: 31_250_000 });
+        assert_eq!((order.epoch, order.status), (512, 1));
+        assert_eq!(order.expire_timestamp, u64::MAX);
+    }
+
+    #[test]
+    fn test_decode_account_balances_and_open_order_set() {
+        let account: Account = fixtures::decode(&fixtures::hex_bytes(fixtures::ACCOUNT_HEX));
+
+        // A bid at 2.4 DBUSDC and an ask at 2.6 DBUSDC
+        let (bid_order_id, ask_order_id) = ((2_400_000u128 << 64) | 5, (1 << 127) | (2_600_000u128 << 64) | 6);
+        assert_eq!(account.open_orders.contents, vec![bid_order_id, ask_order_id]);
+        assert_eq!((account.taker_volume, account.maker_volume), (2_500_000_000, 12_000_000_000_000));
+        assert!(!account.created_proposal);
+        let proposal_id = "0x1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90a";
+        assert_eq!(account.voted_proposal, Some(ObjectID::from_hex_literal(proposal_id).unwrap()));
+        assert_eq!(account.unclaimed_rebates, Balances { base: 0, quote: 0, deep: 1_250_000 });
+        assert_eq!(account.settled_balances, Balances { base: 2_500_000_000, quote: 7_250_000, deep: 125_000 });
+        assert_eq!(account.owed_balances, Balances { base: 0, quote: 1_000_000, deep: 0 });
+    }
+
+    #[test]
+    fn test_decode_order_info() {
+        let bytes = fixtures::hex_bytes(fixtures::ORDER_INFO_HEX);
+        // Fixed-size fields take 201 bytes, plus a one-byte length prefix and one fill
+        let fill_size = 16 + 8 + 8 + 32 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 9 + 8 + 1 + 8 + 1;
+        assert_eq!(bytes.len(), 201 + 1 + fill_size);
+
+        let order_info: OrderInfo = fixtures::decode(&bytes);
+        let pool_id = "0x520c89c6c78c566eed0ebf24f854a8c22d8fdd06a6f16ad01f108dad7f1baaea";
+        assert_eq!(order_info.pool_id, ObjectID::from_hex_literal(pool_id).unwrap());
+        assert_eq!(order_info.client_order_id, 42);
+        assert_eq!(order_info.order_type, 0);
+        assert_eq!(order_info.self_matching_option, 1);
+        assert_eq!(order_info.price, 2_500_000);
+        assert!(order_info.is_bid);
+        assert_eq!(order_info.original_quantity, 2_000_000_000);
+        assert_eq!(order_info.order_deep_price, OrderDeepPrice { asset_is_base: false, deep_per_asset: 31_250_000 });
+        assert_eq!(order_info.expire_timestamp, u64::MAX);
+        assert_eq!(order_info.executed_quantity, 1_000_000_000);
+        assert_eq!(order_info.cumulative_quote_quantity, 2_500_000);
+        let [fill] = order_info.fills.as_slice() else {
+            panic!("expected one fill, got {:?}", order_info.fills);
+        };
+        assert_eq!(fill.maker_client_order_id, 7);
+        assert_eq!(fill.base_quantity, 1_000_000_000);
+        assert_eq!(fill.maker_epoch, 511);
+        assert_eq!((fill.taker_fee, fill.maker_fee), (2_500, 1_250));
+        assert!(fill.maker_fee_is_deep);
+        assert_eq!(order_info.paid_fees, 2_500);
+        assert_eq!(order_info.epoch, 512);
+        assert_eq!(order_info.order_status(), Some(OrderStatus::PartiallyFilled));
+        // The last field only lines up if every field before it has the Move size
+        assert_eq!(order_info.timestamp, 1_700_000_000_000);
+        assert!(!order_info.is_filled());
+        assert!(order_info.is_resting());
+    }
+
+    #[test]
+    fn test_decode_partially_filled_ioc_order_info() {
+        let order_info: OrderInfo = fixtures::decode(&fixtures::hex_bytes(fixtures::IOC_ORDER_INFO_HEX));
+
+        assert_eq!(order_info.order_type, 1);
+        assert_eq!(order_info.original_quantity, 2_000_000_000);
+        assert_eq!(order_info.executed_quantity, 600_000_000);
+        assert_eq!(order_info.cumulative_quote_quantity, 1_500_000);
+        assert_eq!(order_info.fills.len(), 1);
+        assert_eq!(order_info.fills[0].base_quantity, 600_000_000);
+        assert_eq!(order_info.status, 3);
+        assert_eq!(order_info.order_status(), Some(OrderStatus::Canceled));
+        assert!(!order_info.order_inserted);
+        assert_eq!(order_info.timestamp, 1_700_000_000_500);
+        assert_eq!(order_info.canceled_quantity(), 1_400_000_000);
+        assert!(!order_info.is_filled());
+        assert!(!order_info.is_resting());
+        assert_eq!(OrderStatus::from_u8(9), None);
+    }
+
+    #[test]
+    fn test_pool_book_params_validate() {
+        let params = PoolBookParams { tick_size: 1_000, lot_size: 100_000, min_size: 1_000_000 };
+
+        assert!(params.validate(2_000_000, 1_000_000).is_ok());
+        assert!(params.validate(2_000_500, 1_000_000).unwrap_err().to_string().contains("tick size"));
+        assert!(params.validate(2_000_000, 1_050_000).unwrap_err().to_string().contains("lot size"));
+        assert!(params.validate(2_000_000, 900_000).unwrap_err().to_string().contains("min size"));
+    }
+
+    #[test]
+    fn test_decode_allowed_versions() {
+        let registry_inner = serde_json::json!({
+            "type": "0xdee9::registry::RegistryInner",
+            "fields": {
+                "allowed_versions": {
+                    "type": "0x2::vec_set::VecSet<u64>",
+                    "fields": { "contents": ["2", "1"] }
+                },
+                "treasury_address": "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9"
+            }
+        });
+
+        assert_eq!(allowed_versions_from_json(&registry_inner).unwrap(), vec![1, 2]);
+        assert!(allowed_versions_from_json(&serde_json::json!({ "fields": {} })).is_err());
+    }
+
+    #[test]
+    fn test_decode_order_counts() {
+        let big_vector = |length: &str| serde_json::json!({
+            "type": "0xdee9::big_vector::BigVector<0xdee9::order::Order>",
+            "fields": {
+                "id": { "id": "0x0cb45faadd6c3769bd825dfd3538e34d6c658a0b55a8caa52e03c46b07aef8b9" },
+                "depth": 1,
+                "length": length,
+                "max_slice_size": "64",
+                "max_fan_out": "64",
+                "root_id": "12",
+                "last_id": "13"
+            }
+        });
+        let pool_inner = serde_json::json!({
+            "type": "0xdee9::pool::PoolInner<0x2::sui::SUI, 0xdba3::usdc::USDC>",
+            "fields": {
+                "book": {
+                    "type": "0xdee9::book::Book",
+                    "fields": {
+                        "tick_size": "1000",
+                        "lot_size": "100000000",
+                        "min_size": "1000000000",
+                        "bids": big_vector("153"),
+                        "asks": big_vector("87"),
+                        "next_bid_order_id": "18446744073709551000",
+                        "next_ask_order_id": "500"
+                    }
+                }
+            }
+        });
+
+        assert_eq!(order_counts_from_json(&pool_inner).unwrap(), (153, 87));
+        assert!(order_counts_from_json(&serde_json::json!({ "fields": {} })).is_err());
+    }
+
+    #[test]
+    fn test_fee_rate_round_trips_bps() {
+        let fee_rate = FeeRate::from_bps(10.0);
+        assert_eq!(fee_rate.as_float_scalar(), 1_000_000);
+        assert_eq!(fee_rate, FeeRate::from_fraction(0.001));
+        assert_eq!(FeeRate::from_float_scalar(fee_rate.as_float_scalar()).as_bps(), 10.0);
+
+        assert_eq!(fee_rate.to_string(), "0.1%");
+        assert_eq!(FeeRate::from_bps(2.5).to_string(), "0.025%");
+        assert_eq!(FeeRate::from_fraction(0.01).to_string(), "1%");
+    }
+
+    #[test]
+    fn test_coin_metadata_detects_scalar_mismatch() {
+        let usdc = CoinMetadata { decimals: 6, symbol: "USDC".to_string(), name: "USD Coin".to_string() };
+
+        assert_eq!(usdc.scalar(), Some(1_000_000));
+        assert!(usdc.scalar_matches(1_000_000));
+        // A config written for a 9-decimal coin
+        assert!(!usdc.scalar_matches(1_000_000_000));
+        assert_eq!(CoinMetadata { decimals: 20, ..usdc }.scalar(), None);
+    }
+
+    #[test]
+    fn test_remaining_quantity_of_partially_filled_order() {
+        let order = Order { quantity: 10_000_000_000, filled_quantity: 2_500_000_000, ..fixtures::order(1, 1) };
+
+        assert_eq!(order.remaining_quantity(), 7_500_000_000);
+        assert_eq!(fixtures::order(1, 1).remaining_quantity(), 1_000_000);
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright (c) Jarry Han (styu12)
+// SPDX-License-Identifier: Apache-2.0
+//
+// This Rust SDK is inspired by the Sui TypeScript SDK and developed independently by Jarry Han (styu12).
+
+use deepbook::prelude::*;
+
+#[test]
+fn test_prelude_brings_common_names_into_scope() {
+    fn assert_in_scope<T>() {}
+    assert_in_scope::<DeepBookClient>();
+    assert_in_scope::<DeepBookConfig>();
+    assert_in_scope::<Coin>();
+    assert_in_scope::<Pool>();
+    assert_in_scope::<BalanceManager>();
+    assert_in_scope::<Order>();
+    assert_in_scope::<Account>();
+
+    let options = PlaceOrderOptions {
+        order_type: Some(OrderType::PostOnly),
+        self_matching_option: Some(SelfMatchingOptions::CancelTaker),
+        ..Default::default()
+    };
+    assert_eq!(options.fee_mode, FeeMode::Auto);
+    assert_eq!(deepbook::OrderType::PostOnly, OrderType::PostOnly);
+    assert_eq!(deepbook::SelfMatchingOptions::CancelTaker, SelfMatchingOptions::CancelTaker);
+}